@@ -0,0 +1,275 @@
+use embedded_hal_async::delay::DelayUs;
+
+use crate::mod_params::RadioError;
+use crate::mod_traits::Clock;
+
+/// A frequency sub-band and the share of time send operations may occupy it, as set by regional regulations (for
+/// example, 1% or 0.1% for the ETSI EN 300 220 sub-bands)
+#[derive(Clone, Copy)]
+#[allow(missing_docs)]
+pub struct SubBand {
+    pub min_frequency_in_hz: u32,
+    pub max_frequency_in_hz: u32,
+    pub max_duty_cycle_in_ppm: u32, // share of the accounting window, in parts per million (10_000 for 1%)
+}
+
+// A send operation recorded in the ledger of a sub-band
+#[derive(Clone, Copy)]
+struct Transmission {
+    end_in_ms: u64,
+    time_on_air_in_ms: u32,
+}
+
+/// Duty-cycle enforcement for send operations over a table of sub-bands.  The time-on-air of each send operation is
+/// recorded in a ledger of up to LEDGER_SIZE transmissions per sub-band, and counts against the limit of the sub-band
+/// until the transmission ended a full accounting window before the current time of the clock.  When a ledger is full,
+/// a new transmission is merged into the latest one, which keeps the accounting conservative.
+pub struct DutyCycleEnforcer<C, const SUB_BANDS: usize, const LEDGER_SIZE: usize> {
+    clock: C,
+    window_in_ms: u32,
+    sub_bands: [SubBand; SUB_BANDS],
+    ledgers: [[Option<Transmission>; LEDGER_SIZE]; SUB_BANDS],
+}
+
+impl<C, const SUB_BANDS: usize, const LEDGER_SIZE: usize> DutyCycleEnforcer<C, SUB_BANDS, LEDGER_SIZE>
+where
+    C: Clock,
+{
+    /// Create a duty-cycle enforcer for the sub-bands, accounting for the time-on-air of send operations over a rolling
+    /// window (one hour for ETSI EN 300 220)
+    pub fn new(clock: C, sub_bands: [SubBand; SUB_BANDS], window_in_ms: u32) -> Self {
+        Self {
+            clock,
+            window_in_ms,
+            sub_bands,
+            ledgers: [[None; LEDGER_SIZE]; SUB_BANDS],
+        }
+    }
+
+    /// Get the clock against which send operations are accounted
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Get the time at which a send operation with the given time-on-air is next allowed on the frequency, which is the
+    /// current time of the clock if it is allowed now
+    pub fn next_available_in_ms(&self, frequency_in_hz: u32, time_on_air_in_ms: u32) -> Result<u64, RadioError> {
+        let sub_band_index = self.sub_band_index(frequency_in_hz)?;
+        self.next_available_at(sub_band_index, time_on_air_in_ms, self.clock.now_in_ms())
+    }
+
+    /// Check a send operation with the given time-on-air is allowed now on the frequency, failing with
+    /// DutyCycleLimitReached, giving the time at which the sub-band is next available, otherwise
+    pub fn check(&self, frequency_in_hz: u32, time_on_air_in_ms: u32) -> Result<(), RadioError> {
+        let sub_band_index = self.sub_band_index(frequency_in_hz)?;
+        let now_in_ms = self.clock.now_in_ms();
+        match self.next_available_at(sub_band_index, time_on_air_in_ms, now_in_ms)? {
+            next_available_in_ms if next_available_in_ms > now_in_ms => {
+                Err(RadioError::DutyCycleLimitReached(next_available_in_ms))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Wait until a send operation with the given time-on-air is allowed on the frequency
+    pub async fn wait_until_available(
+        &self,
+        frequency_in_hz: u32,
+        time_on_air_in_ms: u32,
+        delay: &mut impl DelayUs,
+    ) -> Result<(), RadioError> {
+        let sub_band_index = self.sub_band_index(frequency_in_hz)?;
+        loop {
+            let now_in_ms = self.clock.now_in_ms();
+            let next_available_in_ms = self.next_available_at(sub_band_index, time_on_air_in_ms, now_in_ms)?;
+            if next_available_in_ms <= now_in_ms {
+                return Ok(());
+            }
+            let wait_in_ms = core::cmp::min(next_available_in_ms - now_in_ms, u32::MAX as u64) as u32;
+            delay.delay_ms(wait_in_ms).await;
+        }
+    }
+
+    /// Record a send operation with the given time-on-air starting now on the frequency
+    pub fn record(&mut self, frequency_in_hz: u32, time_on_air_in_ms: u32) -> Result<(), RadioError> {
+        let sub_band_index = self.sub_band_index(frequency_in_hz)?;
+        let now_in_ms = self.clock.now_in_ms();
+        let window_in_ms = self.window_in_ms as u64;
+        let transmission = Transmission {
+            end_in_ms: now_in_ms + time_on_air_in_ms as u64,
+            time_on_air_in_ms,
+        };
+
+        let ledger = &mut self.ledgers[sub_band_index];
+        for entry in ledger.iter_mut() {
+            if matches!(entry, Some(recorded) if recorded.end_in_ms + window_in_ms <= now_in_ms) {
+                *entry = None;
+            }
+        }
+        if let Some(entry) = ledger.iter_mut().find(|entry| entry.is_none()) {
+            *entry = Some(transmission);
+        } else if let Some(latest) = ledger.iter_mut().flatten().max_by_key(|recorded| recorded.end_in_ms) {
+            latest.end_in_ms = core::cmp::max(latest.end_in_ms, transmission.end_in_ms);
+            latest.time_on_air_in_ms += time_on_air_in_ms;
+        }
+        Ok(())
+    }
+
+    fn sub_band_index(&self, frequency_in_hz: u32) -> Result<usize, RadioError> {
+        self.sub_bands
+            .iter()
+            .position(|sub_band| {
+                (sub_band.min_frequency_in_hz..=sub_band.max_frequency_in_hz).contains(&frequency_in_hz)
+            })
+            .ok_or(RadioError::InvalidFrequency)
+    }
+
+    fn next_available_at(
+        &self,
+        sub_band_index: usize,
+        time_on_air_in_ms: u32,
+        now_in_ms: u64,
+    ) -> Result<u64, RadioError> {
+        let window_in_ms = self.window_in_ms as u64;
+        let limit_in_ms = window_in_ms * self.sub_bands[sub_band_index].max_duty_cycle_in_ppm as u64 / 1_000_000;
+        if time_on_air_in_ms as u64 > limit_in_ms {
+            return Err(RadioError::TimeOnAirExceedsDutyCycle);
+        }
+
+        let ledger = &self.ledgers[sub_band_index];
+        let available_at = |time_in_ms: u64| {
+            let used_in_ms: u64 = ledger
+                .iter()
+                .flatten()
+                .filter(|recorded| recorded.end_in_ms + window_in_ms > time_in_ms)
+                .map(|recorded| recorded.time_on_air_in_ms as u64)
+                .sum();
+            used_in_ms + time_on_air_in_ms as u64 <= limit_in_ms
+        };
+        if available_at(now_in_ms) {
+            return Ok(now_in_ms);
+        }
+
+        // the sub-band becomes available when enough recorded transmissions leave the window
+        Ok(ledger
+            .iter()
+            .flatten()
+            .map(|recorded| recorded.end_in_ms + window_in_ms)
+            .filter(|&time_in_ms| time_in_ms > now_in_ms && available_at(time_in_ms))
+            .min()
+            .unwrap_or(now_in_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::sim::block_on;
+
+    const ONE_HOUR_IN_MS: u32 = 3_600_000;
+
+    // g1 (868.0 - 868.6 MHz, 1%) and g2 (868.7 - 869.2 MHz, 0.1%) sub-bands of ETSI EN 300 220
+    const SUB_BANDS: [SubBand; 2] = [
+        SubBand {
+            min_frequency_in_hz: 868_000_000,
+            max_frequency_in_hz: 868_600_000,
+            max_duty_cycle_in_ppm: 10_000,
+        },
+        SubBand {
+            min_frequency_in_hz: 868_700_000,
+            max_frequency_in_hz: 869_200_000,
+            max_duty_cycle_in_ppm: 1_000,
+        },
+    ];
+
+    struct TestClock<'a>(&'a Cell<u64>);
+
+    impl Clock for TestClock<'_> {
+        fn now_in_ms(&self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    // A delay which advances the test clock
+    struct TestDelay<'a>(&'a Cell<u64>);
+
+    impl DelayUs for TestDelay<'_> {
+        async fn delay_us(&mut self, us: u32) {
+            self.0.set(self.0.get() + (us as u64 + 999) / 1000);
+        }
+
+        async fn delay_ms(&mut self, ms: u32) {
+            self.0.set(self.0.get() + ms as u64);
+        }
+    }
+
+    #[test]
+    fn limits_per_sub_band() {
+        let now = Cell::new(0);
+        let mut enforcer = DutyCycleEnforcer::<_, 2, 8>::new(TestClock(&now), SUB_BANDS, ONE_HOUR_IN_MS);
+
+        // 36 s per hour in g1, 3.6 s per hour in g2
+        enforcer.record(868_100_000, 30_000).unwrap();
+        assert_eq!(enforcer.check(868_300_000, 6_000), Ok(()));
+        assert_eq!(
+            enforcer.check(868_300_000, 6_001),
+            Err(RadioError::DutyCycleLimitReached(3_630_000))
+        );
+        assert_eq!(enforcer.check(868_800_000, 3_600), Ok(()));
+        assert_eq!(
+            enforcer.check(868_800_000, 3_601),
+            Err(RadioError::TimeOnAirExceedsDutyCycle)
+        );
+        assert_eq!(enforcer.check(868_650_000, 100), Err(RadioError::InvalidFrequency));
+
+        now.set(3_630_000);
+        assert_eq!(enforcer.check(868_300_000, 36_000), Ok(()));
+    }
+
+    #[test]
+    fn next_available_after_earliest_sufficient_expiry() {
+        let now = Cell::new(0);
+        let mut enforcer = DutyCycleEnforcer::<_, 2, 8>::new(TestClock(&now), SUB_BANDS, ONE_HOUR_IN_MS);
+
+        enforcer.record(868_800_000, 1_000).unwrap();
+        now.set(10_000);
+        enforcer.record(868_800_000, 2_000).unwrap();
+        now.set(20_000);
+        // 600 ms left, so 1 s is available once the first transmission leaves the window, 2 s once the second does
+        assert_eq!(enforcer.next_available_in_ms(868_800_000, 600), Ok(20_000));
+        assert_eq!(enforcer.next_available_in_ms(868_800_000, 1_000), Ok(3_601_000));
+        assert_eq!(enforcer.next_available_in_ms(868_800_000, 2_000), Ok(3_612_000));
+    }
+
+    #[test]
+    fn full_ledger_merges_transmissions() {
+        let now = Cell::new(0);
+        let mut enforcer = DutyCycleEnforcer::<_, 2, 2>::new(TestClock(&now), SUB_BANDS, ONE_HOUR_IN_MS);
+
+        for start_in_ms in [0, 1_000, 2_000] {
+            now.set(start_in_ms);
+            enforcer.record(868_100_000, 10_000).unwrap();
+        }
+        now.set(20_000);
+        // the third transmission counts with the second one until the later end
+        assert_eq!(enforcer.check(868_100_000, 6_000), Ok(()));
+        assert_eq!(
+            enforcer.check(868_100_000, 6_001),
+            Err(RadioError::DutyCycleLimitReached(3_610_000))
+        );
+    }
+
+    #[test]
+    fn wait_until_available() {
+        let now = Cell::new(0);
+        let mut enforcer = DutyCycleEnforcer::<_, 2, 8>::new(TestClock(&now), SUB_BANDS, ONE_HOUR_IN_MS);
+        let mut delay = TestDelay(&now);
+
+        enforcer.record(868_800_000, 3_000).unwrap();
+        block_on(enforcer.wait_until_available(868_800_000, 1_000, &mut delay)).unwrap();
+        assert_eq!(now.get(), 3_603_000);
+        assert_eq!(enforcer.check(868_800_000, 3_600), Ok(()));
+    }
+}
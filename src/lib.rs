@@ -4,47 +4,86 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+/// Regulatory duty-cycle accounting for send operations
+pub mod duty_cycle;
 /// The read/write interface between an embedded framework/MCU combination and a LoRa chip
 pub(crate) mod interface;
+/// Specific implementation to support Semtech Lr11xx chips
+pub mod lr11xx;
 /// Parameters used across the lora-phy crate to support various use cases
 pub mod mod_params;
 /// Traits implemented externally or internally to support control of LoRa chips
 pub mod mod_traits;
+/// Host-side simulations of LoRa chips, supporting tests without a LoRa board
+#[cfg(any(test, feature = "test-support"))]
+pub mod sim;
 /// Specific implementation to support Semtech Sx126x chips
 pub mod sx1261_2;
 /// Specific implementation to support Semtech Sx127x chips
 pub mod sx1276_7_8_9;
+/// Specific implementation to support Semtech Sx128x chips
+pub mod sx1280;
+/// Deadlines for the operations of LoRa chips without hardware timers
+pub mod timer;
 
+use duty_cycle::DutyCycleEnforcer;
 use embedded_hal_async::delay::DelayUs;
 use interface::*;
 use mod_params::*;
 use mod_traits::*;
 
+// Period of the RSSI measurements sensing the channel before a listen-before-talk send operation (us)
+const LBT_RSSI_SAMPLE_PERIOD_IN_US: u32 = 100;
+
+// Seed of the software generator for listen-before-talk backoffs, used if the LoRa chip has no random number generator
+const BACKOFF_RANDOM_SEED: u32 = 0x9e37_79b9;
+
+// Receive operation started once activity is detected by a CAD-to-receive operation, for LoRa chips without the CAD-RX
+// exit mode
+#[derive(Clone, Copy)]
+struct SoftwareCadRx {
+    rx_boosted_if_supported: bool,
+    symbol_timeout: u16,
+    rx_timeout_in_ms: u32,
+}
+
 /// Provides the physical layer API to support LoRa chips
 pub struct LoRa<RK> {
     radio_kind: RK,
     radio_mode: RadioMode,
     rx_continuous: bool,
     image_calibrated: bool,
+    modem: Modem,
+    sync_word: SyncWord,
+    cad_exit_mode: CadExitMode,
+    software_cad_rx: Option<SoftwareCadRx>,
+    backoff_random: u32,
 }
 
 impl<RK> LoRa<RK>
 where
     RK: RadioKind,
 {
-    /// Build and return a new instance of the LoRa physical layer API to control an initialized LoRa radio
+    /// Build and return a new instance of the LoRa physical layer API to control an initialized LoRa radio.  The sync
+    /// word is given either as a [`SyncWord`] or as a flag selecting the sync word of public or private networks.
     pub async fn new(
         radio_kind: RK,
-        enable_public_network: bool,
+        sync_word: impl Into<SyncWord>,
         delay: &mut impl DelayUs,
     ) -> Result<Self, RadioError> {
+        let sync_word = sync_word.into();
         let mut lora = Self {
             radio_kind,
             radio_mode: RadioMode::Sleep,
             rx_continuous: false,
             image_calibrated: false,
+            modem: Modem::LoRa,
+            sync_word,
+            cad_exit_mode: CadExitMode::CadOnly,
+            software_cad_rx: None,
+            backoff_random: BACKOFF_RANDOM_SEED,
         };
-        lora.init(enable_public_network, delay).await?;
+        lora.init(sync_word, delay).await?;
 
         Ok(lora)
     }
@@ -66,9 +105,30 @@ where
             ChipType::Sx1261 | ChipType::Sx1262 => {
                 ModulationParams::new_for_sx1261_2(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
             }
+            ChipType::Sx1268 => {
+                ModulationParams::new_for_sx1268(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
+            ChipType::Llcc68 => {
+                ModulationParams::new_for_llcc68(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
+            ChipType::Sx1272 => {
+                ModulationParams::new_for_sx1272(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
+            ChipType::Sx1273 => {
+                ModulationParams::new_for_sx1273(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
             ChipType::Sx1276 | ChipType::Sx1277 | ChipType::Sx1278 | ChipType::Sx1279 => {
                 ModulationParams::new_for_sx1276_7_8_9(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
             }
+            ChipType::Lr1110 => {
+                ModulationParams::new_for_lr1110(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
+            ChipType::Lr1120 | ChipType::Lr1121 => {
+                ModulationParams::new_for_lr11xx(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
+            ChipType::Sx1280 | ChipType::Sx1281 => {
+                ModulationParams::new_for_sx1280(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+            }
         }
     }
 
@@ -82,16 +142,8 @@ where
         modulation_params: &ModulationParams,
     ) -> Result<PacketParams, RadioError> {
         match self.radio_kind.get_board_type().into() {
-            ChipType::Sx1261 | ChipType::Sx1262 => PacketParams::new_for_sx1261_2(
-                preamble_length,
-                implicit_header,
-                0,
-                crc_on,
-                iq_inverted,
-                modulation_params,
-            ),
-            ChipType::Sx1276 | ChipType::Sx1277 | ChipType::Sx1278 | ChipType::Sx1279 => {
-                PacketParams::new_for_sx1276_7_8_9(
+            ChipType::Sx1261 | ChipType::Sx1262 | ChipType::Sx1268 | ChipType::Llcc68 => {
+                PacketParams::new_for_sx1261_2(
                     preamble_length,
                     implicit_header,
                     0,
@@ -100,6 +152,35 @@ where
                     modulation_params,
                 )
             }
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => PacketParams::new_for_sx1276_7_8_9(
+                preamble_length,
+                implicit_header,
+                0,
+                crc_on,
+                iq_inverted,
+                modulation_params,
+            ),
+            ChipType::Lr1110 | ChipType::Lr1120 | ChipType::Lr1121 => PacketParams::new_for_lr11xx(
+                preamble_length,
+                implicit_header,
+                0,
+                crc_on,
+                iq_inverted,
+                modulation_params,
+            ),
+            ChipType::Sx1280 | ChipType::Sx1281 => PacketParams::new_for_sx1280(
+                preamble_length,
+                implicit_header,
+                0,
+                crc_on,
+                iq_inverted,
+                modulation_params,
+            ),
         }
     }
 
@@ -114,7 +195,22 @@ where
         modulation_params: &ModulationParams,
     ) -> Result<PacketParams, RadioError> {
         match self.radio_kind.get_board_type().into() {
-            ChipType::Sx1261 | ChipType::Sx1262 => PacketParams::new_for_sx1261_2(
+            ChipType::Sx1261 | ChipType::Sx1262 | ChipType::Sx1268 | ChipType::Llcc68 => {
+                PacketParams::new_for_sx1261_2(
+                    preamble_length,
+                    implicit_header,
+                    max_payload_length,
+                    crc_on,
+                    iq_inverted,
+                    modulation_params,
+                )
+            }
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => PacketParams::new_for_sx1276_7_8_9(
                 preamble_length,
                 implicit_header,
                 max_payload_length,
@@ -122,21 +218,165 @@ where
                 iq_inverted,
                 modulation_params,
             ),
-            ChipType::Sx1276 | ChipType::Sx1277 | ChipType::Sx1278 | ChipType::Sx1279 => {
-                PacketParams::new_for_sx1276_7_8_9(
+            ChipType::Lr1110 | ChipType::Lr1120 | ChipType::Lr1121 => PacketParams::new_for_lr11xx(
+                preamble_length,
+                implicit_header,
+                max_payload_length,
+                crc_on,
+                iq_inverted,
+                modulation_params,
+            ),
+            ChipType::Sx1280 | ChipType::Sx1281 => PacketParams::new_for_sx1280(
+                preamble_length,
+                implicit_header,
+                max_payload_length,
+                crc_on,
+                iq_inverted,
+                modulation_params,
+            ),
+        }
+    }
+
+    /// Create FSK modulation parameters for a communication channel
+    pub fn create_fsk_modulation_params(
+        &mut self,
+        bitrate_in_bps: u32,
+        frequency_deviation_in_hz: u32,
+        pulse_shape: PulseShape,
+        rx_bandwidth_in_hz: u32,
+        frequency_in_hz: u32,
+    ) -> Result<FskModulationParams, RadioError> {
+        match self.radio_kind.get_board_type().into() {
+            ChipType::Sx1261 | ChipType::Sx1262 | ChipType::Llcc68 => FskModulationParams::new_for_sx1261_2(
+                bitrate_in_bps,
+                frequency_deviation_in_hz,
+                pulse_shape,
+                rx_bandwidth_in_hz,
+                frequency_in_hz,
+            ),
+            ChipType::Sx1268 => FskModulationParams::new_for_sx1268(
+                bitrate_in_bps,
+                frequency_deviation_in_hz,
+                pulse_shape,
+                rx_bandwidth_in_hz,
+                frequency_in_hz,
+            ),
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => FskModulationParams::new_for_sx1276_7_8_9(
+                bitrate_in_bps,
+                frequency_deviation_in_hz,
+                pulse_shape,
+                rx_bandwidth_in_hz,
+                frequency_in_hz,
+            ),
+            ChipType::Lr1110 | ChipType::Lr1120 | ChipType::Lr1121 | ChipType::Sx1280 | ChipType::Sx1281 => {
+                Err(RadioError::FskUnsupported)
+            }
+        }
+    }
+
+    /// Create FSK packet parameters for a send operation on a communication channel
+    pub fn create_fsk_tx_packet_params(
+        &mut self,
+        preamble_length: u16,
+        sync_word: &[u8],
+        variable_length: bool,
+        crc_type: FskCrcType,
+        whitening: bool,
+        modulation_params: &FskModulationParams,
+    ) -> Result<FskPacketParams, RadioError> {
+        match self.radio_kind.get_board_type().into() {
+            ChipType::Sx1261 | ChipType::Sx1262 | ChipType::Sx1268 | ChipType::Llcc68 => {
+                FskPacketParams::new_for_sx1261_2(
                     preamble_length,
-                    implicit_header,
+                    sync_word,
+                    variable_length,
+                    0,
+                    crc_type,
+                    whitening,
+                    modulation_params,
+                )
+            }
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => FskPacketParams::new_for_sx1276_7_8_9(
+                preamble_length,
+                sync_word,
+                variable_length,
+                0,
+                crc_type,
+                whitening,
+                modulation_params,
+            ),
+            ChipType::Lr1110 | ChipType::Lr1120 | ChipType::Lr1121 | ChipType::Sx1280 | ChipType::Sx1281 => {
+                Err(RadioError::FskUnsupported)
+            }
+        }
+    }
+
+    /// Create FSK packet parameters for a receive operation on a communication channel
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_fsk_rx_packet_params(
+        &mut self,
+        preamble_length: u16,
+        sync_word: &[u8],
+        variable_length: bool,
+        max_payload_length: u8,
+        crc_type: FskCrcType,
+        whitening: bool,
+        modulation_params: &FskModulationParams,
+    ) -> Result<FskPacketParams, RadioError> {
+        match self.radio_kind.get_board_type().into() {
+            ChipType::Sx1261 | ChipType::Sx1262 | ChipType::Sx1268 | ChipType::Llcc68 => {
+                FskPacketParams::new_for_sx1261_2(
+                    preamble_length,
+                    sync_word,
+                    variable_length,
                     max_payload_length,
-                    crc_on,
-                    iq_inverted,
+                    crc_type,
+                    whitening,
                     modulation_params,
                 )
             }
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => FskPacketParams::new_for_sx1276_7_8_9(
+                preamble_length,
+                sync_word,
+                variable_length,
+                max_payload_length,
+                crc_type,
+                whitening,
+                modulation_params,
+            ),
+            ChipType::Lr1110 | ChipType::Lr1120 | ChipType::Lr1121 | ChipType::Sx1280 | ChipType::Sx1281 => {
+                Err(RadioError::FskUnsupported)
+            }
         }
     }
 
+    /// Get the time on air in microseconds of a packet sent using the given modulation and packet parameters
+    pub fn get_time_on_air_in_us(
+        &self,
+        mdltn_params: &ModulationParams,
+        pkt_params: &PacketParams,
+    ) -> Result<u32, RadioError> {
+        mdltn_params.time_on_air_in_us(pkt_params, self.radio_kind.get_board_type().into())
+    }
+
     /// Initialize a Semtech chip as the radio for LoRa physical layer communications
-    pub async fn init(&mut self, enable_public_network: bool, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+    pub async fn init(&mut self, sync_word: impl Into<SyncWord>, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        let sync_word = sync_word.into();
         self.image_calibrated = false;
         self.radio_kind.reset(delay).await?;
         self.radio_kind.ensure_ready(self.radio_mode).await?;
@@ -144,7 +384,9 @@ where
         self.radio_kind.set_standby().await?;
         self.radio_mode = RadioMode::Standby;
         self.rx_continuous = false;
-        self.radio_kind.set_lora_modem(enable_public_network).await?;
+        self.radio_kind.set_lora_modem(sync_word).await?;
+        self.modem = Modem::LoRa;
+        self.sync_word = sync_word;
         self.radio_kind.set_oscillator().await?;
         self.radio_kind.set_regulator_mode().await?;
         self.radio_kind.set_tx_rx_buffer_base_address(0, 0).await?;
@@ -152,9 +394,56 @@ where
             .set_tx_power_and_ramp_time(0, None, false, false)
             .await?;
         self.radio_kind.set_irq_params(Some(self.radio_mode)).await?;
+        if self.radio_kind.get_device_errors().await?.any() {
+            self.recover_from_device_errors().await?;
+        }
         self.radio_kind.update_retention_list().await
     }
 
+    /// Get the errors reported by the Semtech chip, such as calibration, oscillator start, or PLL lock failures.  Only
+    /// Sx126x chips report device errors.
+    pub async fn device_errors(&mut self) -> Result<DeviceErrors, RadioError> {
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.get_device_errors().await
+    }
+
+    /// Clear the errors reported by the Semtech chip and calibrate it again, failing with DeviceError if errors are
+    /// still reported.  The chip is left in standby mode.  This is done by init() if the chip reports errors once
+    /// initialized.
+    pub async fn recover_from_device_errors(&mut self) -> Result<(), RadioError> {
+        self.rx_continuous = false;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.set_standby().await?;
+        self.radio_mode = RadioMode::Standby;
+        self.radio_kind.clear_device_errors_and_calibrate().await?;
+        self.image_calibrated = false;
+        match self.radio_kind.get_device_errors().await? {
+            device_errors if device_errors.any() => Err(RadioError::DeviceError(device_errors)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the LoRa sync word
+    pub fn get_sync_word(&self) -> SyncWord {
+        self.sync_word
+    }
+
+    /// Change the LoRa sync word, which takes effect for the next operation.  The chip is placed in standby mode if it
+    /// is using the LoRa modem.
+    pub async fn set_sync_word(&mut self, sync_word: SyncWord) -> Result<(), RadioError> {
+        self.sync_word = sync_word;
+        if self.modem == Modem::LoRa {
+            self.rx_continuous = false;
+            self.radio_kind.ensure_ready(self.radio_mode).await?;
+            if self.radio_mode != RadioMode::Standby {
+                self.radio_kind.set_standby().await?;
+                self.radio_mode = RadioMode::Standby;
+            }
+            self.radio_kind.set_lora_modem(sync_word).await?;
+        }
+        Ok(())
+    }
+
     /// Place the LoRa physical layer in low power mode, using warm start if the Semtech chip supports it
     pub async fn sleep(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
         if self.radio_mode != RadioMode::Sleep {
@@ -181,6 +470,7 @@ where
             self.radio_kind.set_standby().await?;
             self.radio_mode = RadioMode::Standby;
         }
+        self.set_modem(Modem::LoRa).await?;
         self.radio_kind.set_modulation_params(mdltn_params).await?;
         self.radio_kind
             .set_tx_power_and_ramp_time(output_power, Some(mdltn_params), tx_boosted_if_possible, true)
@@ -194,6 +484,20 @@ where
         tx_pkt_params: &mut PacketParams,
         buffer: &[u8],
         timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        self.start_tx(mdltn_params, tx_pkt_params, buffer, timeout_in_ms)
+            .await?;
+        self.tx_complete().await
+    }
+
+    /// Initiate a send operation, returning once the Semtech chip is transmitting, so the caller can do other work
+    /// before awaiting the end of the operation with tx_complete()
+    pub async fn start_tx(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        tx_pkt_params: &mut PacketParams,
+        buffer: &[u8],
+        timeout_in_ms: u32,
     ) -> Result<(), RadioError> {
         self.rx_continuous = false;
         self.radio_kind.ensure_ready(self.radio_mode).await?;
@@ -210,15 +514,28 @@ where
         }
         self.radio_kind.set_channel(mdltn_params.frequency_in_hz).await?;
         self.radio_kind.set_payload(buffer).await?;
-        self.radio_mode = RadioMode::Transmit;
-        self.radio_kind.set_irq_params(Some(self.radio_mode)).await?;
+        self.radio_kind.set_irq_params(Some(RadioMode::Transmit)).await?;
         self.radio_kind.do_tx(timeout_in_ms).await?;
+        // only once the operation has started, so tx_complete() does not await a send operation which failed to start
+        self.radio_mode = RadioMode::Transmit;
+        Ok(())
+    }
+
+    /// Await the end of a send operation initiated by start_tx(), after which the Semtech chip is in standby mode
+    pub async fn tx_complete(&mut self) -> Result<(), RadioError> {
+        if self.radio_mode != RadioMode::Transmit {
+            return Err(RadioError::InvalidRadioMode);
+        }
         match self
             .radio_kind
             .process_irq(self.radio_mode, self.rx_continuous, None)
             .await
         {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                // the chip returns to standby mode once the packet is sent
+                self.radio_mode = RadioMode::Standby;
+                Ok(())
+            }
             Err(err) => {
                 self.radio_kind.ensure_ready(self.radio_mode).await?;
                 self.radio_kind.set_standby().await?;
@@ -228,6 +545,164 @@ where
         }
     }
 
+    /// Start transmitting an unmodulated carrier on the frequency, for certification tests and antenna tuning, until
+    /// stop_continuous_tx() is called
+    pub async fn start_continuous_wave(&mut self, frequency_in_hz: u32, output_power: i32) -> Result<(), RadioError> {
+        self.rx_continuous = false;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        if self.radio_mode != RadioMode::Standby {
+            self.radio_kind.set_standby().await?;
+            self.radio_mode = RadioMode::Standby;
+        }
+        // the Sx127x transmits an unmodulated carrier with its FSK modem
+        let chip_type: ChipType = self.radio_kind.get_board_type().into();
+        if matches!(
+            chip_type,
+            ChipType::Sx1272
+                | ChipType::Sx1273
+                | ChipType::Sx1276
+                | ChipType::Sx1277
+                | ChipType::Sx1278
+                | ChipType::Sx1279
+        ) {
+            self.set_modem(Modem::Fsk).await?;
+        }
+
+        self.radio_kind
+            .set_tx_power_and_ramp_time(output_power, None, false, true)
+            .await?;
+        if !self.image_calibrated {
+            self.radio_kind.calibrate_image(frequency_in_hz).await?;
+            self.image_calibrated = true;
+        }
+        self.radio_kind.set_channel(frequency_in_hz).await?;
+        self.radio_kind.do_tx_continuous_wave().await?;
+        self.radio_mode = RadioMode::ContinuousTransmit;
+        Ok(())
+    }
+
+    /// Start transmitting an endless LoRa preamble with the modulation parameters, for certification tests and antenna
+    /// tuning, until stop_continuous_tx() is called.  The Sx127x, which has no endless preamble, sends packets with the
+    /// longest preamble it supports back to back.
+    pub async fn start_continuous_preamble(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        output_power: i32,
+    ) -> Result<(), RadioError> {
+        self.rx_continuous = false;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        if self.radio_mode != RadioMode::Standby {
+            self.radio_kind.set_standby().await?;
+            self.radio_mode = RadioMode::Standby;
+        }
+        self.set_modem(Modem::LoRa).await?;
+
+        self.radio_kind.set_modulation_params(mdltn_params).await?;
+        self.radio_kind
+            .set_tx_power_and_ramp_time(output_power, Some(mdltn_params), false, true)
+            .await?;
+        if !self.image_calibrated {
+            self.radio_kind.calibrate_image(mdltn_params.frequency_in_hz).await?;
+            self.image_calibrated = true;
+        }
+        self.radio_kind.set_channel(mdltn_params.frequency_in_hz).await?;
+        self.radio_kind.do_tx_continuous_preamble().await?;
+        self.radio_mode = RadioMode::ContinuousTransmit;
+        Ok(())
+    }
+
+    /// Stop a continuous wave or preamble transmission, placing the Semtech chip in standby mode
+    pub async fn stop_continuous_tx(&mut self) -> Result<(), RadioError> {
+        if self.radio_mode != RadioMode::ContinuousTransmit {
+            return Err(RadioError::InvalidRadioMode);
+        }
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.set_standby().await?;
+        self.radio_mode = RadioMode::Standby;
+        Ok(())
+    }
+
+    /// Execute a send operation once the channel is sensed to be free (listen-before-talk), as required in some regions.
+    /// While the channel is busy, the operation backs off for a random time, using the random number generator of the
+    /// chip if it has one, then senses the channel again, within the attempts and time allowed by the LBT parameters.
+    /// The time allowed covers the CAD operations, the RSSI listen times, and the backoffs.  As for tx(),
+    /// prepare_for_tx() must be called first.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn tx_with_lbt(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        tx_pkt_params: &mut PacketParams,
+        buffer: &[u8],
+        timeout_in_ms: u32,
+        lbt_params: &LbtParams,
+        delay: &mut impl DelayUs,
+    ) -> Result<(), RadioError> {
+        if lbt_params.min_backoff_in_ms > lbt_params.max_backoff_in_ms {
+            return Err(RadioError::InvalidLbtParams);
+        }
+        // time taken to sense the channel on each attempt
+        let mut sensing_time_in_us = 0u64;
+        if lbt_params.cad {
+            let cad_params = CadParams::recommended_for(mdltn_params.spreading_factor);
+            sensing_time_in_us += cad_params.duration_in_us(mdltn_params) as u64;
+        }
+        if lbt_params.rssi_threshold.is_some() {
+            sensing_time_in_us += lbt_params.listen_time_in_us as u64;
+        }
+        let mut elapsed_in_us = 0u64;
+        for attempt in 1..=lbt_params.max_attempts {
+            if !self.lbt_channel_busy(mdltn_params, lbt_params, delay).await? {
+                return self.tx(mdltn_params, tx_pkt_params, buffer, timeout_in_ms).await;
+            }
+            if attempt == lbt_params.max_attempts {
+                break;
+            }
+            let backoff_in_ms = self.lbt_backoff_in_ms(lbt_params).await?;
+            elapsed_in_us += sensing_time_in_us + (backoff_in_ms as u64 * 1000);
+            if let Some(deadline_in_ms) = lbt_params.deadline_in_ms {
+                if elapsed_in_us > deadline_in_ms as u64 * 1000 {
+                    break;
+                }
+            }
+            delay.delay_ms(backoff_in_ms).await;
+        }
+
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.set_standby().await?;
+        self.radio_mode = RadioMode::Standby;
+        Err(RadioError::ChannelBusy)
+    }
+
+    /// Execute a send operation within the duty-cycle limit of the sub-band of its frequency, recording its time-on-air
+    /// with the enforcer.  If the limit is reached, the operation is refused with DutyCycleLimitReached, giving the time
+    /// at which the sub-band is next available, or, if wait_if_limited is set, delayed until then.  As for tx(),
+    /// prepare_for_tx() must be called first.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn tx_with_duty_cycle<C: Clock, const SUB_BANDS: usize, const LEDGER_SIZE: usize>(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        tx_pkt_params: &mut PacketParams,
+        buffer: &[u8],
+        timeout_in_ms: u32,
+        enforcer: &mut DutyCycleEnforcer<C, SUB_BANDS, LEDGER_SIZE>,
+        wait_if_limited: bool,
+        delay: &mut impl DelayUs,
+    ) -> Result<(), RadioError> {
+        tx_pkt_params.set_payload_length(buffer.len())?;
+        let time_on_air_in_ms = (self.get_time_on_air_in_us(mdltn_params, tx_pkt_params)? + 999) / 1000;
+        let frequency_in_hz = mdltn_params.frequency_in_hz;
+        if wait_if_limited {
+            enforcer
+                .wait_until_available(frequency_in_hz, time_on_air_in_ms, delay)
+                .await?;
+        } else {
+            enforcer.check(frequency_in_hz, time_on_air_in_ms)?;
+        }
+        // recorded before the operation, since a failed operation may still have occupied the channel
+        enforcer.record(frequency_in_hz, time_on_air_in_ms)?;
+        self.tx(mdltn_params, tx_pkt_params, buffer, timeout_in_ms).await
+    }
+
     /// Prepare the Semtech chip for a receive operation (single shot, continuous, or duty cycled) and initiate the operation
     #[allow(clippy::too_many_arguments)]
     pub async fn prepare_for_rx(
@@ -246,6 +721,7 @@ where
             self.radio_kind.set_standby().await?;
             self.radio_mode = RadioMode::Standby;
         }
+        self.set_modem(Modem::LoRa).await?;
 
         self.radio_kind.set_modulation_params(mdltn_params).await?;
         self.radio_kind.set_packet_params(rx_pkt_params).await?;
@@ -277,6 +753,11 @@ where
         rx_pkt_params: &PacketParams,
         receiving_buffer: &mut [u8],
     ) -> Result<(u8, PacketStatus), RadioError> {
+        // a send operation initiated by start_tx() must be completed by tx_complete() first, and a continuous one
+        // stopped by stop_continuous_tx()
+        if matches!(self.radio_mode, RadioMode::Transmit | RadioMode::ContinuousTransmit) {
+            return Err(RadioError::InvalidRadioMode);
+        }
         match self
             .radio_kind
             .process_irq(self.radio_mode, self.rx_continuous, None)
@@ -299,28 +780,136 @@ where
         }
     }
 
-    /// Prepare the Semtech chip for a channel activity detection operation and initiate the operation
-    pub async fn prepare_for_cad(
+    /// Prepare the Semtech chip for RSSI measurements on a channel, putting it in continuous receive mode without waiting
+    /// for a packet
+    pub async fn prepare_for_rssi(
         &mut self,
         mdltn_params: &ModulationParams,
         rx_boosted_if_supported: bool,
     ) -> Result<(), RadioError> {
-        self.rx_continuous = false;
+        // no packet is decoded, so the implicit header mode, which every chip accepts at every spreading factor (unlike
+        // the explicit header mode on Sx127x chips at spreading factor 6), is used
+        let rx_pkt_params = self.create_rx_packet_params(8, true, 255, true, false, mdltn_params)?;
+        self.rx_continuous = true;
         self.radio_kind.ensure_ready(self.radio_mode).await?;
         if self.radio_mode != RadioMode::Standby {
             self.radio_kind.set_standby().await?;
             self.radio_mode = RadioMode::Standby;
         }
+        self.set_modem(Modem::LoRa).await?;
 
         self.radio_kind.set_modulation_params(mdltn_params).await?;
+        self.radio_kind.set_packet_params(&rx_pkt_params).await?;
         if !self.image_calibrated {
             self.radio_kind.calibrate_image(mdltn_params.frequency_in_hz).await?;
             self.image_calibrated = true;
         }
         self.radio_kind.set_channel(mdltn_params.frequency_in_hz).await?;
-        self.radio_mode = RadioMode::ChannelActivityDetection;
+        self.radio_mode = RadioMode::Receive;
         self.radio_kind.set_irq_params(Some(self.radio_mode)).await?;
-        self.radio_kind.do_cad(mdltn_params, rx_boosted_if_supported).await
+        self.radio_kind
+            .do_rx(&rx_pkt_params, None, true, rx_boosted_if_supported, 0, 0)
+            .await
+    }
+
+    /// Get the instantaneous RSSI (dBm) of the channel, once prepare_for_rssi() or prepare_for_rx() has put the Semtech
+    /// chip in receive mode
+    pub async fn current_rssi(&mut self) -> Result<i16, RadioError> {
+        if self.radio_mode != RadioMode::Receive {
+            return Err(RadioError::InvalidRadioMode);
+        }
+        self.radio_kind.get_rssi().await
+    }
+
+    /// Get the reception statistics (packets received, CRC errors, and header errors) accumulated since the last reset,
+    /// counted by the driver for chips without statistics counters (Sx127x and Sx128x)
+    pub async fn get_stats(&mut self) -> Result<RxStats, RadioError> {
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.get_rx_stats().await
+    }
+
+    /// Reset the reception statistics
+    pub async fn reset_stats(&mut self) -> Result<(), RadioError> {
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.reset_rx_stats().await
+    }
+
+    /// Prepare the Semtech chip for a channel activity detection operation and initiate the operation.  A receive or
+    /// send operation chained by the exit mode of the CAD parameters uses the packet parameters and payload already
+    /// given to the chip, and is completed with rx() or tx_complete() once cad() returns.  prepare_for_cad_rx() and
+    /// prepare_for_cad_lbt() give them for the chained operation.
+    pub async fn prepare_for_cad(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        cad_params: &CadParams,
+        rx_boosted_if_supported: bool,
+    ) -> Result<(), RadioError> {
+        self.software_cad_rx = None;
+        self.start_cad(mdltn_params, None, None, cad_params, rx_boosted_if_supported)
+            .await
+    }
+
+    /// Prepare the Semtech chip for a channel activity detection operation followed by a receive operation if activity
+    /// is detected, as used by wake-on-radio listeners, and initiate the operation.  The receive operation is chained
+    /// by the Sx126x and Lr11xx chips, for the timeout of the CAD parameters, and started by cad_rx() for other chips,
+    /// with the symbol timeout and the timeout of the CAD parameters.  The exit mode of the CAD parameters is ignored.
+    pub async fn prepare_for_cad_rx(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        rx_pkt_params: &PacketParams,
+        cad_params: &CadParams,
+        rx_boosted_if_supported: bool,
+        symbol_timeout: u16,
+    ) -> Result<(), RadioError> {
+        let mut cad_params = *cad_params;
+        if self.radio_kind.cad_rx_supported() {
+            cad_params.exit_mode = CadExitMode::CadRx;
+            self.software_cad_rx = None;
+        } else {
+            cad_params.exit_mode = CadExitMode::CadOnly;
+            self.software_cad_rx = Some(SoftwareCadRx {
+                rx_boosted_if_supported,
+                symbol_timeout,
+                rx_timeout_in_ms: cad_params.timeout_in_ms,
+            });
+        }
+        self.start_cad(
+            mdltn_params,
+            Some(rx_pkt_params),
+            None,
+            &cad_params,
+            rx_boosted_if_supported,
+        )
+        .await
+    }
+
+    /// Prepare the Semtech chip for a channel activity detection operation followed by a send operation of the buffer
+    /// if no activity is detected (listen-before-talk), and initiate the operation.  The send operation is chained by
+    /// the Sx126x and Lr11xx chips, for the timeout of the CAD parameters, with the output power given by
+    /// prepare_for_tx(), and completed with tx_complete() once cad() returns false.  Other chips refuse the operation
+    /// with RadioError::CadExitModeUnsupported.  The exit mode of the CAD parameters is ignored.  The RF switch of the
+    /// interface variant stays set for the CAD operation, so boards need an RF switch driven by the chip, such as by
+    /// DIO2 on Sx126x chips.
+    pub async fn prepare_for_cad_lbt(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        tx_pkt_params: &mut PacketParams,
+        buffer: &[u8],
+        cad_params: &CadParams,
+        rx_boosted_if_supported: bool,
+    ) -> Result<(), RadioError> {
+        let mut cad_params = *cad_params;
+        cad_params.exit_mode = CadExitMode::CadLbt;
+        self.software_cad_rx = None;
+        tx_pkt_params.set_payload_length(buffer.len())?;
+        self.start_cad(
+            mdltn_params,
+            Some(tx_pkt_params),
+            Some(buffer),
+            &cad_params,
+            rx_boosted_if_supported,
+        )
+        .await
     }
 
     /// Obtain the results of a channel activity detection operation
@@ -331,7 +920,15 @@ where
             .process_irq(self.radio_mode, self.rx_continuous, Some(&mut cad_activity_detected))
             .await
         {
-            Ok(()) => Ok(cad_activity_detected),
+            Ok(()) => {
+                // the chip either returns to standby mode or continues with the operation chained by the exit mode
+                self.radio_mode = match (self.cad_exit_mode, cad_activity_detected) {
+                    (CadExitMode::CadRx, true) => RadioMode::Receive,
+                    (CadExitMode::CadLbt, false) => RadioMode::Transmit,
+                    _ => RadioMode::Standby,
+                };
+                Ok(cad_activity_detected)
+            }
             Err(err) => {
                 self.radio_kind.ensure_ready(self.radio_mode).await?;
                 self.radio_kind.set_standby().await?;
@@ -340,6 +937,279 @@ where
             }
         }
     }
+
+    /// Obtain the results of a CAD-to-receive operation, giving None if no activity is detected, otherwise the number
+    /// of bytes received and the status of the packet
+    pub async fn cad_rx(
+        &mut self,
+        rx_pkt_params: &PacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<Option<(u8, PacketStatus)>, RadioError> {
+        let software_cad_rx = self.software_cad_rx.take();
+        if !self.cad().await? {
+            return Ok(None);
+        }
+        if let Some(software_cad_rx) = software_cad_rx {
+            // the chip returned to standby mode once activity was detected
+            self.radio_mode = RadioMode::Receive;
+            self.radio_kind.set_irq_params(Some(self.radio_mode)).await?;
+            if let Err(err) = self
+                .radio_kind
+                .do_rx(
+                    rx_pkt_params,
+                    None,
+                    false,
+                    software_cad_rx.rx_boosted_if_supported,
+                    software_cad_rx.symbol_timeout,
+                    software_cad_rx.rx_timeout_in_ms,
+                )
+                .await
+            {
+                self.radio_kind.ensure_ready(self.radio_mode).await?;
+                self.radio_kind.set_standby().await?;
+                self.radio_mode = RadioMode::Standby;
+                return Err(err);
+            }
+        }
+        self.rx(rx_pkt_params, receiving_buffer).await.map(Some)
+    }
+
+    /// Prepare the Semtech chip for an FSK send operation
+    pub async fn prepare_for_fsk_tx(
+        &mut self,
+        mdltn_params: &FskModulationParams,
+        output_power: i32,
+        tx_boosted_if_possible: bool,
+    ) -> Result<(), RadioError> {
+        self.rx_continuous = false;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        if self.radio_mode != RadioMode::Standby {
+            self.radio_kind.set_standby().await?;
+            self.radio_mode = RadioMode::Standby;
+        }
+        self.set_modem(Modem::Fsk).await?;
+        self.radio_kind.set_fsk_modulation_params(mdltn_params).await?;
+        self.radio_kind
+            .set_tx_power_and_ramp_time(output_power, None, tx_boosted_if_possible, true)
+            .await
+    }
+
+    /// Execute an FSK send operation
+    pub async fn fsk_tx(
+        &mut self,
+        mdltn_params: &FskModulationParams,
+        tx_pkt_params: &mut FskPacketParams,
+        buffer: &[u8],
+        timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        self.rx_continuous = false;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        if self.radio_mode != RadioMode::Standby {
+            self.radio_kind.set_standby().await?;
+            self.radio_mode = RadioMode::Standby;
+        }
+
+        tx_pkt_params.set_payload_length(buffer.len())?;
+        self.radio_kind.set_fsk_packet_params(tx_pkt_params).await?;
+        if !self.image_calibrated {
+            self.radio_kind.calibrate_image(mdltn_params.frequency_in_hz).await?;
+            self.image_calibrated = true;
+        }
+        self.radio_kind.set_channel(mdltn_params.frequency_in_hz).await?;
+        self.radio_kind.set_payload(buffer).await?;
+        self.radio_kind.set_irq_params(Some(RadioMode::Transmit)).await?;
+        self.radio_kind.do_tx(timeout_in_ms).await?;
+        self.radio_mode = RadioMode::Transmit;
+        match self
+            .radio_kind
+            .process_irq(self.radio_mode, self.rx_continuous, None)
+            .await
+        {
+            Ok(()) => {
+                // the chip returns to standby mode once the packet is sent
+                self.radio_mode = RadioMode::Standby;
+                Ok(())
+            }
+            Err(err) => {
+                self.radio_kind.ensure_ready(self.radio_mode).await?;
+                self.radio_kind.set_standby().await?;
+                self.radio_mode = RadioMode::Standby;
+                Err(err)
+            }
+        }
+    }
+
+    /// Prepare the Semtech chip for an FSK receive operation (single shot or continuous) and initiate the operation
+    pub async fn prepare_for_fsk_rx(
+        &mut self,
+        mdltn_params: &FskModulationParams,
+        rx_pkt_params: &FskPacketParams,
+        rx_continuous: bool,
+        rx_boosted_if_supported: bool,
+        rx_timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        self.rx_continuous = rx_continuous;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        if self.radio_mode != RadioMode::Standby {
+            self.radio_kind.set_standby().await?;
+            self.radio_mode = RadioMode::Standby;
+        }
+        self.set_modem(Modem::Fsk).await?;
+
+        self.radio_kind.set_fsk_modulation_params(mdltn_params).await?;
+        self.radio_kind.set_fsk_packet_params(rx_pkt_params).await?;
+        if !self.image_calibrated {
+            self.radio_kind.calibrate_image(mdltn_params.frequency_in_hz).await?;
+            self.image_calibrated = true;
+        }
+        self.radio_kind.set_channel(mdltn_params.frequency_in_hz).await?;
+        self.radio_mode = RadioMode::Receive;
+        self.radio_kind.set_irq_params(Some(self.radio_mode)).await?;
+        self.radio_kind
+            .do_fsk_rx(self.rx_continuous, rx_boosted_if_supported, rx_timeout_in_ms)
+            .await
+    }
+
+    /// Obtain the results of an FSK read operation
+    pub async fn fsk_rx(
+        &mut self,
+        rx_pkt_params: &FskPacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<(u8, PacketStatus), RadioError> {
+        // a send operation initiated by start_tx() must be completed by tx_complete() first, and a continuous one
+        // stopped by stop_continuous_tx()
+        if matches!(self.radio_mode, RadioMode::Transmit | RadioMode::ContinuousTransmit) {
+            return Err(RadioError::InvalidRadioMode);
+        }
+        match self
+            .radio_kind
+            .process_irq(self.radio_mode, self.rx_continuous, None)
+            .await
+        {
+            Ok(()) => {
+                let received_len = self
+                    .radio_kind
+                    .get_fsk_rx_payload(rx_pkt_params, receiving_buffer)
+                    .await?;
+                let rx_pkt_status = self.radio_kind.get_fsk_rx_packet_status().await?;
+                Ok((received_len, rx_pkt_status))
+            }
+            Err(err) => {
+                // if in rx continuous mode, allow the caller to determine whether to keep receiving
+                if !self.rx_continuous {
+                    self.radio_kind.ensure_ready(self.radio_mode).await?;
+                    self.radio_kind.set_standby().await?;
+                    self.radio_mode = RadioMode::Standby;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    // Initiate a CAD operation, after giving the packet parameters and payload of the receive or send operation which
+    // may follow
+    async fn start_cad(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        pkt_params: Option<&PacketParams>,
+        payload: Option<&[u8]>,
+        cad_params: &CadParams,
+        rx_boosted_if_supported: bool,
+    ) -> Result<(), RadioError> {
+        self.rx_continuous = false;
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        if self.radio_mode != RadioMode::Standby {
+            self.radio_kind.set_standby().await?;
+            self.radio_mode = RadioMode::Standby;
+        }
+        self.set_modem(Modem::LoRa).await?;
+
+        self.radio_kind.set_modulation_params(mdltn_params).await?;
+        if let Some(pkt_params) = pkt_params {
+            self.radio_kind.set_packet_params(pkt_params).await?;
+        }
+        if !self.image_calibrated {
+            self.radio_kind.calibrate_image(mdltn_params.frequency_in_hz).await?;
+            self.image_calibrated = true;
+        }
+        self.radio_kind.set_channel(mdltn_params.frequency_in_hz).await?;
+        if let Some(payload) = payload {
+            self.radio_kind.set_payload(payload).await?;
+        }
+        self.radio_mode = RadioMode::ChannelActivityDetection;
+        self.cad_exit_mode = cad_params.exit_mode;
+        self.radio_kind.set_irq_params(Some(self.radio_mode)).await?;
+        self.radio_kind.do_cad(cad_params, rx_boosted_if_supported).await
+    }
+
+    // Sense the channel with a CAD operation and/or RSSI measurements over the listen time, as given by the LBT parameters
+    async fn lbt_channel_busy(
+        &mut self,
+        mdltn_params: &ModulationParams,
+        lbt_params: &LbtParams,
+        delay: &mut impl DelayUs,
+    ) -> Result<bool, RadioError> {
+        if lbt_params.cad {
+            let cad_params = CadParams::recommended_for(mdltn_params.spreading_factor);
+            self.prepare_for_cad(mdltn_params, &cad_params, false).await?;
+            if self.cad().await? {
+                return Ok(true);
+            }
+        }
+        if let Some(rssi_threshold) = lbt_params.rssi_threshold {
+            self.prepare_for_rssi(mdltn_params, false).await?;
+            let samples = core::cmp::max(1, lbt_params.listen_time_in_us / LBT_RSSI_SAMPLE_PERIOD_IN_US);
+            for _ in 0..samples {
+                delay.delay_us(LBT_RSSI_SAMPLE_PERIOD_IN_US).await;
+                let rssi = self.current_rssi().await?;
+                // the noise in the RSSI readings also feeds the software backoff generator
+                self.backoff_random = self.backoff_random.rotate_left(7) ^ (rssi as u16 as u32);
+                if rssi >= rssi_threshold {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    // Random backoff within the range given by the LBT parameters, leaving the chip in standby mode
+    async fn lbt_backoff_in_ms(&mut self, lbt_params: &LbtParams) -> Result<u32, RadioError> {
+        self.radio_kind.ensure_ready(self.radio_mode).await?;
+        self.radio_kind.set_standby().await?;
+        self.radio_mode = RadioMode::Standby;
+
+        let random_number = match self.radio_kind.get_random_number().await {
+            Ok(random_number) => {
+                self.radio_kind.set_standby().await?;
+                random_number
+            }
+            Err(RadioError::RngUnsupported) => {
+                // xorshift generator
+                if self.backoff_random == 0 {
+                    self.backoff_random = BACKOFF_RANDOM_SEED;
+                }
+                self.backoff_random ^= self.backoff_random << 13;
+                self.backoff_random ^= self.backoff_random >> 17;
+                self.backoff_random ^= self.backoff_random << 5;
+                self.backoff_random
+            }
+            Err(err) => return Err(err),
+        };
+        let range = (lbt_params.max_backoff_in_ms - lbt_params.min_backoff_in_ms) as u64 + 1;
+        Ok(lbt_params.min_backoff_in_ms + (random_number as u64 % range) as u32)
+    }
+
+    // Switch a multi-protocol chip to the given modem, which requires the chip to be in standby mode
+    async fn set_modem(&mut self, modem: Modem) -> Result<(), RadioError> {
+        if self.modem != modem {
+            match modem {
+                Modem::LoRa => self.radio_kind.set_lora_modem(self.sync_word).await?,
+                Modem::Fsk => self.radio_kind.set_fsk_modem().await?,
+            }
+            self.modem = modem;
+        }
+        Ok(())
+    }
 }
 
 impl<RK> AsyncRng for LoRa<RK>
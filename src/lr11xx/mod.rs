@@ -0,0 +1,859 @@
+pub(crate) mod radio_kind_params;
+
+use defmt::debug;
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::spi::*;
+use radio_kind_params::*;
+
+use crate::mod_params::*;
+use crate::{InterfaceVariant, RadioKind, SpiInterface};
+
+// Frequency of the RTC clocking the timeouts (Hz)
+const LR11XX_RTC_FREQ: u64 = 32768;
+
+// Timeout value requesting continuous receive
+const LR11XX_RX_CONTINUOUS_TIMEOUT: u32 = 0x00ff_ffff;
+
+// Frequencies supported by the sub-GHz radio of all Lr11xx chips and the 2.4 GHz radio of the Lr1120 and Lr1121 (Hz)
+const LR11XX_SUB_GHZ_FREQUENCY_RANGE_IN_HZ: core::ops::RangeInclusive<u32> = 150_000_000..=960_000_000;
+const LR11XX_2_4_GHZ_FREQUENCY_RANGE_IN_HZ: core::ops::RangeInclusive<u32> = 2_400_000_000..=2_500_000_000;
+
+impl ModulationParams {
+    /// Create modulation parameters specific to the LoRa chip kind and type.  The Lr1120 and Lr1121 support the 2.4 GHz
+    /// band as well as the sub-GHz band.
+    pub fn new_for_lr11xx(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        // Parameter validation
+        spreading_factor_value(spreading_factor)?;
+        bandwidth_value(bandwidth)?;
+        coding_rate_value(coding_rate)?;
+        // the 203, 406, and 812 kHz bandwidths are only available in the 2.4 GHz band
+        let high_frequency_bandwidth =
+            matches!(bandwidth, Bandwidth::_203KHz | Bandwidth::_406KHz | Bandwidth::_812KHz);
+        if LR11XX_2_4_GHZ_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            if !high_frequency_bandwidth
+                && !matches!(bandwidth, Bandwidth::_125KHz | Bandwidth::_250KHz | Bandwidth::_500KHz)
+            {
+                return Err(RadioError::InvalidBandwidthForFrequency);
+            }
+        } else if !LR11XX_SUB_GHZ_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            return Err(RadioError::InvalidFrequency);
+        } else if high_frequency_bandwidth {
+            return Err(RadioError::InvalidBandwidthForFrequency);
+        }
+
+        let mut low_data_rate_optimize = 0x00u8;
+        if (((spreading_factor == SpreadingFactor::_11) || (spreading_factor == SpreadingFactor::_12))
+            && (bandwidth == Bandwidth::_125KHz))
+            || ((spreading_factor == SpreadingFactor::_12) && (bandwidth == Bandwidth::_250KHz))
+        {
+            low_data_rate_optimize = 0x01u8;
+        }
+        Ok(Self {
+            spreading_factor,
+            bandwidth,
+            coding_rate,
+            low_data_rate_optimize,
+            frequency_in_hz,
+        })
+    }
+
+    /// Create modulation parameters specific to the Lr1110 chip, which supports the sub-GHz band only
+    pub fn new_for_lr1110(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        if !LR11XX_SUB_GHZ_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            return Err(RadioError::InvalidFrequency);
+        }
+        Self::new_for_lr11xx(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+    }
+}
+
+impl PacketParams {
+    /// Create packet parameters specific to the LoRa chip kind and type
+    pub fn new_for_lr11xx(
+        mut preamble_length: u16,
+        implicit_header: bool,
+        payload_length: u8,
+        crc_on: bool,
+        iq_inverted: bool,
+        modulation_params: &ModulationParams,
+    ) -> Result<Self, RadioError> {
+        if ((modulation_params.spreading_factor == SpreadingFactor::_5)
+            || (modulation_params.spreading_factor == SpreadingFactor::_6))
+            && (preamble_length < 12)
+        {
+            preamble_length = 12;
+        }
+
+        Ok(Self {
+            preamble_length,
+            implicit_header,
+            payload_length,
+            crc_on,
+            iq_inverted,
+        })
+    }
+}
+
+/// Base for the RadioKind implementation for the LoRa chip kind and board type
+pub struct LR11xx<SPI, IV> {
+    board_config: BoardConfig,
+    intf: SpiInterface<SPI, IV>,
+}
+
+impl<SPI, IV> LR11xx<SPI, IV>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+{
+    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board configuration, given as a
+    /// BoardConfig or as the BoardType of a preset board
+    pub fn new(board_config: impl Into<BoardConfig>, spi: SPI, mut iv: IV) -> Self {
+        let board_config = board_config.into();
+        iv.set_board_type(board_config.board_type);
+        let intf = SpiInterface::new(spi, iv);
+        Self { board_config, intf }
+    }
+
+    // Utility functions
+
+    // Send a command, then read its response once the radio is no longer busy.  The response starts with the status of
+    // the command (Stat1), which is checked for errors.
+    async fn read_response(&mut self, command: &[u8], response: &mut [u8]) -> Result<(), RadioError> {
+        self.intf.write(&[command], false).await?;
+        let read_status = self.intf.read_with_status(&[], response).await?;
+        if CommandStatus::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        Ok(())
+    }
+
+    // Get the pending interrupts, returned as part of the status following the GetStatus opcode
+    async fn get_irq_status(&mut self) -> Result<u32, RadioError> {
+        let mut irq_status = [0x00u8; 4];
+        self.intf
+            .read(&[&OpCode::GetStatus.bytes()], &mut irq_status, None)
+            .await?;
+        Ok(u32::from_be_bytes(irq_status))
+    }
+
+    async fn clear_irq_status(&mut self, irq_flags: u32) -> Result<(), RadioError> {
+        let op_code = OpCode::ClearIrq.bytes();
+        let irq = irq_flags.to_be_bytes();
+        let op_code_and_irq_status = [op_code[0], op_code[1], irq[0], irq[1], irq[2], irq[3]];
+        self.intf.write(&[&op_code_and_irq_status], false).await
+    }
+
+    async fn set_pa_config(
+        &mut self,
+        pa_selection: PaSelection,
+        pa_supply: PaSupply,
+        pa_duty_cycle: u8,
+        hp_sel: u8,
+    ) -> Result<(), RadioError> {
+        let op_code = OpCode::SetPaConfig.bytes();
+        let op_code_and_pa_config = [
+            op_code[0],
+            op_code[1],
+            pa_selection.value(),
+            pa_supply.value(),
+            pa_duty_cycle,
+            hp_sel,
+        ];
+        self.intf.write(&[&op_code_and_pa_config], false).await
+    }
+
+    // Convert a duration in milliseconds to RTC steps of 30.52 us, limited to the 24 bit timeouts of the radio
+    fn convert_ms_to_rtc_steps(timeout_in_ms: u32) -> u32 {
+        core::cmp::min(
+            (timeout_in_ms as u64 * LR11XX_RTC_FREQ) / 1000,
+            LR11XX_RX_CONTINUOUS_TIMEOUT as u64,
+        ) as u32
+    }
+
+    fn timeout_1(timeout: u32) -> u8 {
+        ((timeout >> 16) & 0xFF) as u8
+    }
+    fn timeout_2(timeout: u32) -> u8 {
+        ((timeout >> 8) & 0xFF) as u8
+    }
+    fn timeout_3(timeout: u32) -> u8 {
+        (timeout & 0xFF) as u8
+    }
+}
+
+impl<SPI, IV> RadioKind for LR11xx<SPI, IV>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+{
+    fn get_board_type(&self) -> BoardType {
+        self.board_config.board_type
+    }
+
+    async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        self.intf.iv.reset(delay).await
+    }
+
+    // Wakeup the radio if it is in Sleep or ReceiveDutyCycle mode; otherwise, ensure it is not busy.
+    async fn ensure_ready(&mut self, mode: RadioMode) -> Result<(), RadioError> {
+        if mode == RadioMode::Sleep || mode == RadioMode::ReceiveDutyCycle {
+            self.intf.write(&[&OpCode::GetStatus.bytes()], false).await?;
+        } else {
+            self.intf.iv.wait_on_busy().await?;
+        }
+        Ok(())
+    }
+
+    // The DIOs controlling an RF switch depend on the board; the generic boards control it through the interface variant.
+    async fn init_rf_switch(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    // Use standby mode RC (not XOSC).
+    async fn set_standby(&mut self) -> Result<(), RadioError> {
+        let op_code = OpCode::SetStandby.bytes();
+        let op_code_and_standby_mode = [op_code[0], op_code[1], StandbyMode::RC.value()];
+        self.intf.write(&[&op_code_and_standby_mode], false).await?;
+        self.intf.iv.disable_rf_switch().await
+    }
+
+    async fn set_sleep(&mut self, delay: &mut impl DelayUs) -> Result<bool, RadioError> {
+        self.intf.iv.disable_rf_switch().await?;
+        let sleep_params = SleepParams {
+            wakeup_rtc: false,
+            warm_start: true,
+        };
+        let op_code = OpCode::SetSleep.bytes();
+        // no sleep time, since the radio is woken up by the next operation rather than the RTC
+        let op_code_and_sleep_params = [op_code[0], op_code[1], sleep_params.value(), 0x00, 0x00, 0x00, 0x00];
+        self.intf.write(&[&op_code_and_sleep_params], true).await?;
+        delay.delay_ms(2).await;
+
+        Ok(sleep_params.warm_start) // indicate if warm start enabled
+    }
+
+    /// Configure the radio for LoRa and the given sync word.
+    async fn set_lora_modem(&mut self, sync_word: SyncWord) -> Result<(), RadioError> {
+        let op_code = OpCode::SetPacketType.bytes();
+        let op_code_and_packet_type = [op_code[0], op_code[1], PacketType::LoRa.value()];
+        self.intf.write(&[&op_code_and_packet_type], false).await?;
+
+        let op_code = OpCode::SetLoRaSyncWord.bytes();
+        let op_code_and_syncword = [op_code[0], op_code[1], sync_word.value()];
+        self.intf.write(&[&op_code_and_syncword], false).await
+    }
+
+    async fn set_fsk_modem(&mut self) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn set_oscillator(&mut self) -> Result<(), RadioError> {
+        // voltage supplied to the TCXO from the VTCXO pin
+        let (voltage, startup_time_in_ms) = match self.board_config.oscillator {
+            Oscillator::Tcxo {
+                voltage,
+                startup_time_in_ms,
+            } => (TcxoCtrlVoltage::from(voltage), startup_time_in_ms),
+            Oscillator::Xtal => return Ok(()),
+        };
+        let timeout = Self::convert_ms_to_rtc_steps(startup_time_in_ms); // duration allowed for TCXO to reach 32MHz
+        let op_code = OpCode::SetTcxoMode.bytes();
+        let op_code_and_tcxo_control = [
+            op_code[0],
+            op_code[1],
+            voltage.value() & 0x07,
+            Self::timeout_1(timeout),
+            Self::timeout_2(timeout),
+            Self::timeout_3(timeout),
+        ];
+        self.intf.write(&[&op_code_and_tcxo_control], false).await?;
+
+        // the blocks clocked by the TCXO need to be calibrated again once it is enabled
+        let calibration_params = CalibrationParams {
+            lf_rc_enable: true,
+            hf_rc_enable: true,
+            pll_enable: true,
+            adc_enable: true,
+            img_enable: true,
+            pll_tx_enable: true,
+        };
+        let op_code = OpCode::Calibrate.bytes();
+        let op_code_and_calibration = [op_code[0], op_code[1], calibration_params.value()];
+        self.intf.write(&[&op_code_and_calibration], false).await
+    }
+
+    // Set the power regulators operating mode to DC_DC if the board allows it.  Using only LDO implies that the Rx/Tx
+    // current is doubled.
+    async fn set_regulator_mode(&mut self) -> Result<(), RadioError> {
+        let regulator_mode = match self.board_config.regulator {
+            Regulator::Ldo => RegulatorMode::UseLDO,
+            Regulator::DcDc => RegulatorMode::UseDCDC,
+        };
+        let op_code = OpCode::SetRegMode.bytes();
+        let op_code_and_regulator_mode = [op_code[0], op_code[1], regulator_mode.value()];
+        self.intf.write(&[&op_code_and_regulator_mode], false).await
+    }
+
+    // The Lr11xx chips use separate send and receive buffers, without configurable base addresses.
+    async fn set_tx_rx_buffer_base_address(
+        &mut self,
+        tx_base_addr: usize,
+        rx_base_addr: usize,
+    ) -> Result<(), RadioError> {
+        if tx_base_addr != 0 || rx_base_addr != 0 {
+            return Err(RadioError::InvalidBaseAddress(tx_base_addr, rx_base_addr));
+        }
+        Ok(())
+    }
+
+    // Set parameters associated with power for a send operation.
+    //   output_power            desired RF output power (dBm)
+    //   mdltn_params            needed to select the 2.4 GHz PA for channels in that band
+    //   tx_boosted_if_possible  determine if the sub-GHz high power PA should be used instead of the low power PA
+    //   is_tx_prep              indicates which ramp up time to use
+    async fn set_tx_power_and_ramp_time(
+        &mut self,
+        output_power: i32,
+        mdltn_params: Option<&ModulationParams>,
+        tx_boosted_if_possible: bool,
+        is_tx_prep: bool,
+    ) -> Result<(), RadioError> {
+        let tx_params_power;
+        let ramp_time = match is_tx_prep {
+            true => RampTime::Ramp48Us,   // for instance, prior to TX or CAD
+            false => RampTime::Ramp208Us, // for instance, on initialization
+        };
+
+        let high_frequency = match mdltn_params {
+            Some(m_p) => !LR11XX_SUB_GHZ_FREQUENCY_RANGE_IN_HZ.contains(&m_p.frequency_in_hz),
+            None => false,
+        };
+        if high_frequency {
+            if !(-18..=13).contains(&output_power) {
+                return Err(RadioError::InvalidOutputPowerForFrequency);
+            }
+            self.set_pa_config(PaSelection::HighFrequency, PaSupply::Internal, 0x00, 0x00)
+                .await?;
+            tx_params_power = output_power as u8;
+        } else if tx_boosted_if_possible {
+            if !(-9..=22).contains(&output_power) {
+                return Err(RadioError::InvalidOutputPower);
+            }
+            match output_power {
+                22 => {
+                    self.set_pa_config(PaSelection::HighPower, PaSupply::Vbat, 0x04, 0x07)
+                        .await?;
+                    tx_params_power = 22;
+                }
+                20 => {
+                    self.set_pa_config(PaSelection::HighPower, PaSupply::Vbat, 0x03, 0x05)
+                        .await?;
+                    tx_params_power = 22;
+                }
+                17 => {
+                    self.set_pa_config(PaSelection::HighPower, PaSupply::Vbat, 0x02, 0x03)
+                        .await?;
+                    tx_params_power = 22;
+                }
+                14 => {
+                    self.set_pa_config(PaSelection::HighPower, PaSupply::Vbat, 0x02, 0x02)
+                        .await?;
+                    tx_params_power = 22;
+                }
+                _ => {
+                    self.set_pa_config(PaSelection::HighPower, PaSupply::Vbat, 0x04, 0x07)
+                        .await?;
+                    tx_params_power = output_power as u8;
+                }
+            }
+        } else {
+            if !(-17..=15).contains(&output_power) {
+                return Err(RadioError::InvalidOutputPower);
+            }
+            match output_power {
+                15 => {
+                    self.set_pa_config(PaSelection::LowPower, PaSupply::Internal, 0x07, 0x00)
+                        .await?;
+                    tx_params_power = 14;
+                }
+                14 => {
+                    self.set_pa_config(PaSelection::LowPower, PaSupply::Internal, 0x04, 0x00)
+                        .await?;
+                    tx_params_power = 14;
+                }
+                10 => {
+                    self.set_pa_config(PaSelection::LowPower, PaSupply::Internal, 0x00, 0x00)
+                        .await?;
+                    tx_params_power = 13;
+                }
+                _ => {
+                    self.set_pa_config(PaSelection::LowPower, PaSupply::Internal, 0x04, 0x00)
+                        .await?;
+                    tx_params_power = output_power as u8;
+                }
+            }
+        }
+
+        debug!("tx power = {}", tx_params_power);
+
+        let op_code = OpCode::SetTxParams.bytes();
+        let op_code_and_tx_params = [op_code[0], op_code[1], tx_params_power, ramp_time.value()];
+        self.intf.write(&[&op_code_and_tx_params], false).await
+    }
+
+    // The whole configuration is retained in sleep mode when warm starting.
+    async fn update_retention_list(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn set_modulation_params(&mut self, mdltn_params: &ModulationParams) -> Result<(), RadioError> {
+        let spreading_factor_val = spreading_factor_value(mdltn_params.spreading_factor)?;
+        let bandwidth_val = bandwidth_value(mdltn_params.bandwidth)?;
+        let coding_rate_val = coding_rate_value(mdltn_params.coding_rate)?;
+        debug!(
+            "sf = {}, bw = {}, cr = {}",
+            spreading_factor_val, bandwidth_val, coding_rate_val
+        );
+        let op_code = OpCode::SetModulationParams.bytes();
+        let op_code_and_mod_params = [
+            op_code[0],
+            op_code[1],
+            spreading_factor_val,
+            bandwidth_val,
+            coding_rate_val,
+            mdltn_params.low_data_rate_optimize,
+        ];
+        self.intf.write(&[&op_code_and_mod_params], false).await
+    }
+
+    async fn set_packet_params(&mut self, pkt_params: &PacketParams) -> Result<(), RadioError> {
+        let op_code = OpCode::SetPacketParams.bytes();
+        let op_code_and_pkt_params = [
+            op_code[0],
+            op_code[1],
+            ((pkt_params.preamble_length >> 8) & 0xFF) as u8,
+            (pkt_params.preamble_length & 0xFF) as u8,
+            pkt_params.implicit_header as u8,
+            pkt_params.payload_length,
+            pkt_params.crc_on as u8,
+            pkt_params.iq_inverted as u8,
+        ];
+        self.intf.write(&[&op_code_and_pkt_params], false).await
+    }
+
+    async fn set_fsk_modulation_params(&mut self, _mdltn_params: &FskModulationParams) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn set_fsk_packet_params(&mut self, _pkt_params: &FskPacketParams) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    // Calibrate the image rejection based on the given frequency, which is only needed in the sub-GHz band
+    async fn calibrate_image(&mut self, frequency_in_hz: u32) -> Result<(), RadioError> {
+        let mut cal_freq = [0x00u8, 0x00u8];
+
+        if !LR11XX_SUB_GHZ_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            return Ok(());
+        } else if frequency_in_hz > 900000000 {
+            cal_freq[0] = 0xE1;
+            cal_freq[1] = 0xE9;
+        } else if frequency_in_hz > 850000000 {
+            cal_freq[0] = 0xD7;
+            cal_freq[1] = 0xDB;
+        } else if frequency_in_hz > 770000000 {
+            cal_freq[0] = 0xC1;
+            cal_freq[1] = 0xC5;
+        } else if frequency_in_hz > 460000000 {
+            cal_freq[0] = 0x75;
+            cal_freq[1] = 0x81;
+        } else if frequency_in_hz > 425000000 {
+            cal_freq[0] = 0x6B;
+            cal_freq[1] = 0x6F;
+        } else {
+            // calibrate over the 4 MHz steps surrounding the frequency
+            let step = (frequency_in_hz / 4000000) as u8;
+            cal_freq[0] = step;
+            cal_freq[1] = step + 1;
+        }
+
+        let op_code = OpCode::CalibImage.bytes();
+        let op_code_and_cal_freq = [op_code[0], op_code[1], cal_freq[0], cal_freq[1]];
+        self.intf.write(&[&op_code_and_cal_freq], false).await
+    }
+
+    // The frequency is given to the radio in Hz
+    async fn set_channel(&mut self, frequency_in_hz: u32) -> Result<(), RadioError> {
+        debug!("channel = {}", frequency_in_hz);
+        let op_code = OpCode::SetRfFrequency.bytes();
+        let frequency = frequency_in_hz.to_be_bytes();
+        let op_code_and_frequency = [
+            op_code[0],
+            op_code[1],
+            frequency[0],
+            frequency[1],
+            frequency[2],
+            frequency[3],
+        ];
+        self.intf.write(&[&op_code_and_frequency], false).await
+    }
+
+    async fn set_payload(&mut self, payload: &[u8]) -> Result<(), RadioError> {
+        self.intf.write(&[&OpCode::WriteBuffer8.bytes(), payload], false).await
+    }
+
+    async fn do_tx(&mut self, timeout_in_ms: u32) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+
+        let timeout = Self::convert_ms_to_rtc_steps(timeout_in_ms);
+        let op_code = OpCode::SetTx.bytes();
+        let op_code_and_timeout = [
+            op_code[0],
+            op_code[1],
+            Self::timeout_1(timeout),
+            Self::timeout_2(timeout),
+            Self::timeout_3(timeout),
+        ];
+        self.intf.write(&[&op_code_and_timeout], false).await
+    }
+
+    async fn do_tx_continuous_wave(&mut self) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.intf.write(&[&OpCode::SetTxCw.bytes()], false).await
+    }
+
+    async fn do_tx_continuous_preamble(&mut self) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.intf.write(&[&OpCode::SetTxInfinitePreamble.bytes()], false).await
+    }
+
+    async fn do_rx(
+        &mut self,
+        _rx_pkt_params: &PacketParams,
+        duty_cycle_params: Option<&DutyCycleParams>,
+        rx_continuous: bool,
+        rx_boosted_if_supported: bool,
+        symbol_timeout: u16,
+        rx_timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        let mut symbol_timeout_final = symbol_timeout;
+        let mut rx_timeout_final = Self::convert_ms_to_rtc_steps(rx_timeout_in_ms);
+
+        if let Some(&_duty_cycle) = duty_cycle_params {
+            if rx_continuous {
+                return Err(RadioError::DutyCycleRxContinuousUnsupported);
+            } else {
+                symbol_timeout_final = 0;
+            }
+        }
+
+        self.intf.iv.enable_rf_switch_rx().await?;
+
+        if rx_continuous {
+            symbol_timeout_final = 0;
+            rx_timeout_final = LR11XX_RX_CONTINUOUS_TIMEOUT;
+        }
+        if symbol_timeout_final > u8::MAX as u16 {
+            return Err(RadioError::InvalidSymbolTimeout);
+        }
+
+        // stop the Rx timer on header/syncword detection rather than preamble detection
+        let op_code = OpCode::StopTimeoutOnPreamble.bytes();
+        let op_code_and_false_flag = [op_code[0], op_code[1], 0x00u8];
+        self.intf.write(&[&op_code_and_false_flag], false).await?;
+
+        let op_code = OpCode::SetLoRaSynchTimeout.bytes();
+        let op_code_and_symbol_timeout = [op_code[0], op_code[1], symbol_timeout_final as u8];
+        self.intf.write(&[&op_code_and_symbol_timeout], false).await?;
+
+        // if Rx boosted, increase current by ~2mA for around ~2dB in sensitivity
+        let op_code = OpCode::SetRxBoosted.bytes();
+        let op_code_and_rx_boosted = [op_code[0], op_code[1], rx_boosted_if_supported as u8];
+        self.intf.write(&[&op_code_and_rx_boosted], false).await?;
+
+        match duty_cycle_params {
+            Some(&duty_cycle) => {
+                let op_code = OpCode::SetRxDutyCycle.bytes();
+                let op_code_and_duty_cycle = [
+                    op_code[0],
+                    op_code[1],
+                    Self::timeout_1(duty_cycle.rx_time),
+                    Self::timeout_2(duty_cycle.rx_time),
+                    Self::timeout_3(duty_cycle.rx_time),
+                    Self::timeout_1(duty_cycle.sleep_time),
+                    Self::timeout_2(duty_cycle.sleep_time),
+                    Self::timeout_3(duty_cycle.sleep_time),
+                    0x00u8, // receive, rather than perform channel activity detection, while awake
+                ];
+                self.intf.write(&[&op_code_and_duty_cycle], false).await
+            }
+            None => {
+                let op_code = OpCode::SetRx.bytes();
+                let op_code_and_timeout = [
+                    op_code[0],
+                    op_code[1],
+                    Self::timeout_1(rx_timeout_final),
+                    Self::timeout_2(rx_timeout_final),
+                    Self::timeout_3(rx_timeout_final),
+                ];
+                self.intf.write(&[&op_code_and_timeout], false).await
+            }
+        }
+    }
+
+    async fn get_rx_payload(
+        &mut self,
+        rx_pkt_params: &PacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError> {
+        let mut rx_buffer_status = [0x00u8; 2];
+        self.read_response(&OpCode::GetRxBufferStatus.bytes(), &mut rx_buffer_status)
+            .await?;
+
+        let payload_length = match rx_pkt_params.implicit_header {
+            true => rx_pkt_params.payload_length,
+            false => rx_buffer_status[0],
+        };
+        let offset = rx_buffer_status[1];
+
+        if (payload_length as usize) > receiving_buffer.len() {
+            Err(RadioError::PayloadSizeMismatch(
+                payload_length as usize,
+                receiving_buffer.len(),
+            ))
+        } else {
+            let op_code = OpCode::ReadBuffer8.bytes();
+            let op_code_and_offset_and_length = [op_code[0], op_code[1], offset, payload_length];
+            self.read_response(
+                &op_code_and_offset_and_length,
+                &mut receiving_buffer[..payload_length as usize],
+            )
+            .await?;
+            Ok(payload_length)
+        }
+    }
+
+    async fn get_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
+        let mut pkt_status = [0x00u8; 3];
+        self.read_response(&OpCode::GetPacketStatus.bytes(), &mut pkt_status)
+            .await?;
+        let rssi = ((-(pkt_status[0] as i32)) >> 1) as i16;
+        let snr = (((pkt_status[1] as i8) + 2) >> 2) as i16;
+        let signal_rssi = ((-(pkt_status[2] as i32)) >> 1) as i16;
+
+        Ok(PacketStatus {
+            rssi,
+            snr,
+            signal_rssi: Some(signal_rssi),
+            ..Default::default()
+        })
+    }
+
+    async fn get_rssi(&mut self) -> Result<i16, RadioError> {
+        let mut rssi = [0x00u8];
+        self.read_response(&OpCode::GetRssiInst.bytes(), &mut rssi).await?;
+        Ok(((-(rssi[0] as i32)) >> 1) as i16)
+    }
+
+    async fn get_rx_stats(&mut self) -> Result<RxStats, RadioError> {
+        // NbPktReceived, NbPktCrcError, NbPktHeaderErr, NbPktFalseSync
+        let mut stats = [0x00u8; 8];
+        self.read_response(&OpCode::GetStats.bytes(), &mut stats).await?;
+        Ok(RxStats {
+            packets_received: u16::from_be_bytes([stats[0], stats[1]]),
+            crc_errors: u16::from_be_bytes([stats[2], stats[3]]),
+            header_errors: Some(u16::from_be_bytes([stats[4], stats[5]])),
+        })
+    }
+
+    async fn reset_rx_stats(&mut self) -> Result<(), RadioError> {
+        self.intf.write(&[&OpCode::ResetStats.bytes()], false).await
+    }
+
+    async fn do_fsk_rx(
+        &mut self,
+        _rx_continuous: bool,
+        _rx_boosted_if_supported: bool,
+        _rx_timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn get_fsk_rx_payload(
+        &mut self,
+        _rx_pkt_params: &FskPacketParams,
+        _receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn get_fsk_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    fn cad_rx_supported(&self) -> bool {
+        true
+    }
+
+    async fn do_cad(&mut self, cad_params: &CadParams, rx_boosted_if_supported: bool) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_rx().await?;
+
+        let op_code = OpCode::SetRxBoosted.bytes();
+        let op_code_and_rx_boosted = [op_code[0], op_code[1], rx_boosted_if_supported as u8];
+        self.intf.write(&[&op_code_and_rx_boosted], false).await?;
+
+        let exit_mode = match cad_params.exit_mode {
+            CadExitMode::CadOnly => CADExitMode::Standby,
+            CadExitMode::CadRx => CADExitMode::Rx,
+            CadExitMode::CadLbt => CADExitMode::Lbt,
+        };
+        let timeout = Self::convert_ms_to_rtc_steps(cad_params.timeout_in_ms).to_be_bytes();
+        let op_code = OpCode::SetCadParams.bytes();
+        let op_code_and_cad_params = [
+            op_code[0],
+            op_code[1],
+            CADSymbols::from(cad_params.symbols).value(), // number of symbols for detection
+            cad_params.det_peak,                          // limit for detection of SNR peak
+            cad_params.det_min,                           // minimum symbol recognition
+            exit_mode.value(),
+            timeout[1], // timeout of the chained receive or send operation
+            timeout[2],
+            timeout[3],
+        ];
+        self.intf.write(&[&op_code_and_cad_params], false).await?;
+
+        self.intf.write(&[&OpCode::SetCad.bytes()], false).await
+    }
+
+    // Interrupts are signalled on DIO9; DIO11 is not used.
+    async fn set_irq_params(&mut self, radio_mode: Option<RadioMode>) -> Result<(), RadioError> {
+        let dio9_mask: u32 = match radio_mode {
+            Some(RadioMode::Standby) => IrqMask::All.value(),
+            Some(RadioMode::Transmit) => IrqMask::TxDone.value() | IrqMask::RxTxTimeout.value(),
+            Some(RadioMode::Receive) | Some(RadioMode::ReceiveDutyCycle) => IrqMask::All.value(),
+            // include the events of a receive or send operation chained by the CAD exit mode
+            Some(RadioMode::ChannelActivityDetection) => {
+                IrqMask::CADDone.value()
+                    | IrqMask::CADActivityDetected.value()
+                    | IrqMask::TxDone.value()
+                    | IrqMask::RxDone.value()
+                    | IrqMask::HeaderError.value()
+                    | IrqMask::CRCError.value()
+                    | IrqMask::RxTxTimeout.value()
+            }
+            _ => IrqMask::None.value(),
+        };
+        let dio11_mask: u32 = IrqMask::None.value();
+
+        let op_code = OpCode::SetDioIrqParams.bytes();
+        let dio9 = dio9_mask.to_be_bytes();
+        let dio11 = dio11_mask.to_be_bytes();
+        let op_code_and_masks = [
+            op_code[0], op_code[1], dio9[0], dio9[1], dio9[2], dio9[3], dio11[0], dio11[1], dio11[2], dio11[3],
+        ];
+        self.intf.write(&[&op_code_and_masks], false).await
+    }
+
+    async fn get_device_errors(&mut self) -> Result<DeviceErrors, RadioError> {
+        Ok(DeviceErrors::default())
+    }
+
+    async fn clear_device_errors_and_calibrate(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn process_irq(
+        &mut self,
+        radio_mode: RadioMode,
+        _rx_continuous: bool,
+        cad_activity_detected: Option<&mut bool>,
+    ) -> Result<(), RadioError> {
+        loop {
+            debug!("process_irq loop entered");
+
+            self.intf.iv.await_irq().await?;
+            let irq_flags = self.get_irq_status().await?;
+            self.clear_irq_status(irq_flags).await?;
+
+            debug!(
+                "process_irq satisfied: irq_flags = 0x{:x} in radio mode {}",
+                irq_flags, radio_mode
+            );
+
+            if (irq_flags & IrqMask::HeaderValid.value()) == IrqMask::HeaderValid.value() {
+                debug!("HeaderValid in radio mode {}", radio_mode);
+            }
+            if (irq_flags & IrqMask::PreambleDetected.value()) == IrqMask::PreambleDetected.value() {
+                debug!("PreambleDetected in radio mode {}", radio_mode);
+            }
+            if (irq_flags & IrqMask::CommandError.value()) == IrqMask::CommandError.value() {
+                debug!("CommandError in radio mode {}", radio_mode);
+            }
+
+            if radio_mode == RadioMode::Transmit {
+                if (irq_flags & IrqMask::TxDone.value()) == IrqMask::TxDone.value() {
+                    debug!("TxDone in radio mode {}", radio_mode);
+                    return Ok(());
+                }
+                if (irq_flags & IrqMask::RxTxTimeout.value()) == IrqMask::RxTxTimeout.value() {
+                    debug!("RxTxTimeout in radio mode {}", radio_mode);
+                    return Err(RadioError::TransmitTimeout);
+                }
+            } else if (radio_mode == RadioMode::Receive) || (radio_mode == RadioMode::ReceiveDutyCycle) {
+                if (irq_flags & IrqMask::HeaderError.value()) == IrqMask::HeaderError.value() {
+                    debug!("HeaderError in radio mode {}", radio_mode);
+                }
+                if (irq_flags & IrqMask::CRCError.value()) == IrqMask::CRCError.value() {
+                    debug!("CRCError in radio mode {}", radio_mode);
+                }
+                if (irq_flags & IrqMask::RxDone.value()) == IrqMask::RxDone.value() {
+                    debug!("RxDone in radio mode {}", radio_mode);
+                    return Ok(());
+                }
+                if (irq_flags & IrqMask::RxTxTimeout.value()) == IrqMask::RxTxTimeout.value() {
+                    debug!("RxTxTimeout in radio mode {}", radio_mode);
+                    return Err(RadioError::ReceiveTimeout);
+                }
+            } else if radio_mode == RadioMode::ChannelActivityDetection
+                && (irq_flags & IrqMask::CADDone.value()) == IrqMask::CADDone.value()
+            {
+                debug!("CADDone in radio mode {}", radio_mode);
+                if cad_activity_detected.is_some() {
+                    *(cad_activity_detected.unwrap()) =
+                        (irq_flags & IrqMask::CADActivityDetected.value()) == IrqMask::CADActivityDetected.value();
+                }
+                return Ok(());
+            }
+
+            // if an interrupt occurred for other than an error or operation completion, loop to wait again
+        }
+    }
+
+    /// Generate a 32 bit random value using the noise of the receiver, after disabling all interrupts.
+    /// The random numbers produced by the generator do not have a uniform or Gaussian distribution.
+    /// If uniformity is needed, perform appropriate software post-processing.
+    async fn get_random_number(&mut self) -> Result<u32, RadioError> {
+        self.set_irq_params(None).await?;
+
+        let mut number_buffer = [0x00u8; 4];
+        self.read_response(&OpCode::GetRandomNumber.bytes(), &mut number_buffer)
+            .await?;
+        Ok(u32::from_be_bytes(number_buffer))
+    }
+}
+
+impl<SPI, IV> crate::RngRadio for LR11xx<SPI, IV>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+{
+}
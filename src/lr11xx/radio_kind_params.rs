@@ -0,0 +1,349 @@
+use crate::mod_params::*;
+
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum PacketType {
+    None = 0x00,
+    GFSK = 0x01,
+    LoRa = 0x02,
+}
+
+impl PacketType {
+    pub const fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(u32)]
+pub enum IrqMask {
+    None = 0x0000_0000,
+    TxDone = 0x0000_0004,
+    RxDone = 0x0000_0008,
+    PreambleDetected = 0x0000_0010,
+    HeaderValid = 0x0000_0020,
+    HeaderError = 0x0000_0040,
+    CRCError = 0x0000_0080,
+    CADDone = 0x0000_0100,
+    CADActivityDetected = 0x0000_0200,
+    RxTxTimeout = 0x0000_0400,
+    CommandError = 0x0040_0000,
+    All = 0xFFFF_FFFF,
+}
+
+impl IrqMask {
+    pub fn value(self) -> u32 {
+        self as u32
+    }
+}
+
+// Commands are identified by two byte opcodes, the first byte giving the command group (system, radio, ...)
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum OpCode {
+    GetStatus = 0x0100,
+    GetVersion = 0x0101,
+    WriteBuffer8 = 0x0109,
+    ReadBuffer8 = 0x010A,
+    ClearRxBuffer = 0x010B,
+    GetErrors = 0x010D,
+    ClearErrors = 0x010E,
+    Calibrate = 0x010F,
+    SetRegMode = 0x0110,
+    CalibImage = 0x0111,
+    SetDioAsRfSwitch = 0x0112,
+    SetDioIrqParams = 0x0113,
+    ClearIrq = 0x0114,
+    SetTcxoMode = 0x0117,
+    SetSleep = 0x011B,
+    SetStandby = 0x011C,
+    SetFs = 0x011D,
+    GetRandomNumber = 0x0120,
+    ResetStats = 0x0200,
+    GetStats = 0x0201,
+    GetPacketType = 0x0202,
+    GetRxBufferStatus = 0x0203,
+    GetPacketStatus = 0x0204,
+    GetRssiInst = 0x0205,
+    SetRx = 0x0209,
+    SetTx = 0x020A,
+    SetRfFrequency = 0x020B,
+    SetCadParams = 0x020D,
+    SetPacketType = 0x020E,
+    SetModulationParams = 0x020F,
+    SetPacketParams = 0x0210,
+    SetTxParams = 0x0211,
+    SetRxTxFallbackMode = 0x0213,
+    SetRxDutyCycle = 0x0214,
+    SetPaConfig = 0x0215,
+    StopTimeoutOnPreamble = 0x0217,
+    SetCad = 0x0218,
+    SetTxCw = 0x0219,
+    SetTxInfinitePreamble = 0x021A,
+    SetLoRaSynchTimeout = 0x021B,
+    SetRxBoosted = 0x0227,
+    SetLoRaSyncWord = 0x022B,
+}
+
+impl OpCode {
+    pub fn bytes(self) -> [u8; 2] {
+        (self as u16).to_be_bytes()
+    }
+}
+
+// Status of the last command, reported in bits 3:1 of the first status byte (Stat1)
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum CommandStatus {
+    Fail = 0x00,
+    ParameterError = 0x01,
+    Ok = 0x02,
+    Data = 0x03,
+}
+
+impl CommandStatus {
+    pub fn value(self) -> u8 {
+        (self as u8) << 1
+    }
+
+    pub fn is_error(stat1: u8) -> bool {
+        let status = stat1 & 0x0e;
+        status == CommandStatus::Fail.value() || status == CommandStatus::ParameterError.value()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SleepParams {
+    pub wakeup_rtc: bool, // get out of sleep mode when the sleep time elapses
+    pub warm_start: bool, // retain the configuration while sleeping
+}
+
+impl SleepParams {
+    pub fn value(self) -> u8 {
+        ((self.wakeup_rtc as u8) << 1) | (self.warm_start as u8)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum StandbyMode {
+    RC = 0x00,
+    XOSC = 0x01,
+}
+
+impl StandbyMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum RegulatorMode {
+    UseLDO = 0x00,
+    UseDCDC = 0x01,
+}
+
+impl RegulatorMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct CalibrationParams {
+    pub lf_rc_enable: bool,  // calibrate the 32 kHz RC oscillator
+    pub hf_rc_enable: bool,  // calibrate the 16 MHz RC oscillator
+    pub pll_enable: bool,    // calibrate PLL
+    pub adc_enable: bool,    // calibrate ADC
+    pub img_enable: bool,    // calibrate the image rejection over the default band
+    pub pll_tx_enable: bool, // calibrate the PLL for transmission
+}
+
+impl CalibrationParams {
+    pub fn value(self) -> u8 {
+        ((self.pll_tx_enable as u8) << 5)
+            | ((self.img_enable as u8) << 4)
+            | ((self.adc_enable as u8) << 3)
+            | ((self.pll_enable as u8) << 2)
+            | ((self.hf_rc_enable as u8) << 1)
+            | (self.lf_rc_enable as u8)
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum TcxoCtrlVoltage {
+    Ctrl1V6 = 0x00,
+    Ctrl1V7 = 0x01,
+    Ctrl1V8 = 0x02,
+    Ctrl2V2 = 0x03,
+    Ctrl2V4 = 0x04,
+    Ctrl2V7 = 0x05,
+    Ctrl3V0 = 0x06,
+    Ctrl3V3 = 0x07,
+}
+
+impl TcxoCtrlVoltage {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<TcxoVoltage> for TcxoCtrlVoltage {
+    fn from(voltage: TcxoVoltage) -> Self {
+        match voltage {
+            TcxoVoltage::_1V6 => TcxoCtrlVoltage::Ctrl1V6,
+            TcxoVoltage::_1V7 => TcxoCtrlVoltage::Ctrl1V7,
+            TcxoVoltage::_1V8 => TcxoCtrlVoltage::Ctrl1V8,
+            TcxoVoltage::_2V2 => TcxoCtrlVoltage::Ctrl2V2,
+            TcxoVoltage::_2V4 => TcxoCtrlVoltage::Ctrl2V4,
+            TcxoVoltage::_2V7 => TcxoCtrlVoltage::Ctrl2V7,
+            TcxoVoltage::_3V0 => TcxoCtrlVoltage::Ctrl3V0,
+            TcxoVoltage::_3V3 => TcxoCtrlVoltage::Ctrl3V3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum PaSelection {
+    LowPower = 0x00,      // sub-GHz low power PA, up to 15 dBm
+    HighPower = 0x01,     // sub-GHz high power PA, up to 22 dBm
+    HighFrequency = 0x02, // 2.4 GHz PA, up to 13 dBm (Lr1120 and Lr1121)
+}
+
+impl PaSelection {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum PaSupply {
+    Internal = 0x00, // internal regulator
+    Vbat = 0x01,     // battery supply, needed for the high power PA above 14 dBm
+}
+
+impl PaSupply {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+#[allow(clippy::enum_variant_names)]
+pub enum RampTime {
+    Ramp16Us = 0x00,
+    Ramp32Us = 0x01,
+    Ramp48Us = 0x02,
+    Ramp64Us = 0x03,
+    Ramp80Us = 0x04,
+    Ramp96Us = 0x05,
+    Ramp112Us = 0x06,
+    Ramp128Us = 0x07,
+    Ramp144Us = 0x08,
+    Ramp160Us = 0x09,
+    Ramp176Us = 0x0A,
+    Ramp192Us = 0x0B,
+    Ramp208Us = 0x0C,
+    Ramp240Us = 0x0D,
+    Ramp272Us = 0x0E,
+    Ramp304Us = 0x0F,
+}
+
+impl RampTime {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum CADExitMode {
+    Standby = 0x00,
+    Rx = 0x01,
+    Lbt = 0x10,
+}
+
+impl CADExitMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+pub fn spreading_factor_value(spreading_factor: SpreadingFactor) -> Result<u8, RadioError> {
+    match spreading_factor {
+        SpreadingFactor::_5 => Ok(0x05),
+        SpreadingFactor::_6 => Ok(0x06),
+        SpreadingFactor::_7 => Ok(0x07),
+        SpreadingFactor::_8 => Ok(0x08),
+        SpreadingFactor::_9 => Ok(0x09),
+        SpreadingFactor::_10 => Ok(0x0A),
+        SpreadingFactor::_11 => Ok(0x0B),
+        SpreadingFactor::_12 => Ok(0x0C),
+    }
+}
+
+pub fn bandwidth_value(bandwidth: Bandwidth) -> Result<u8, RadioError> {
+    match bandwidth {
+        Bandwidth::_7KHz => Err(RadioError::UnavailableBandwidth),
+        Bandwidth::_10KHz => Ok(0x08),
+        Bandwidth::_15KHz => Ok(0x01),
+        Bandwidth::_20KHz => Ok(0x09),
+        Bandwidth::_31KHz => Ok(0x02),
+        Bandwidth::_41KHz => Ok(0x0A),
+        Bandwidth::_62KHz => Ok(0x03),
+        Bandwidth::_125KHz => Ok(0x04),
+        Bandwidth::_250KHz => Ok(0x05),
+        Bandwidth::_500KHz => Ok(0x06),
+        Bandwidth::_203KHz => Ok(0x0D),
+        Bandwidth::_406KHz => Ok(0x0E),
+        Bandwidth::_812KHz => Ok(0x0F),
+        Bandwidth::_1625KHz => Err(RadioError::UnavailableBandwidth),
+    }
+}
+
+pub fn coding_rate_value(coding_rate: CodingRate) -> Result<u8, RadioError> {
+    match coding_rate {
+        CodingRate::_4_5 => Ok(0x01),
+        CodingRate::_4_6 => Ok(0x02),
+        CodingRate::_4_7 => Ok(0x03),
+        CodingRate::_4_8 => Ok(0x04),
+        CodingRate::_4_5Li => Ok(0x05),
+        CodingRate::_4_6Li => Ok(0x06),
+        CodingRate::_4_8Li => Ok(0x07),
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum CADSymbols {
+    _1 = 0x01,
+    _2 = 0x02,
+    _4 = 0x04,
+    _8 = 0x08,
+    _16 = 0x10,
+}
+
+impl CADSymbols {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<CadSymbols> for CADSymbols {
+    fn from(symbols: CadSymbols) -> Self {
+        match symbols {
+            CadSymbols::_1 => CADSymbols::_1,
+            CadSymbols::_2 => CADSymbols::_2,
+            CadSymbols::_4 => CADSymbols::_4,
+            CadSymbols::_8 => CADSymbols::_8,
+            CadSymbols::_16 => CADSymbols::_16,
+        }
+    }
+}
@@ -37,16 +37,81 @@ pub enum RadioError {
     ReceiveDoneUnexpected,
     DutyCycleUnsupported,
     DutyCycleRxContinuousUnsupported,
+    InvalidDutyCycleParams,
     CADUnexpected,
+    CadExitModeUnsupported,
     RngUnsupported,
+    FskUnsupported,
+    UnavailableBitrate,
+    UnavailableFrequencyDeviation,
+    UnavailableRxBandwidth,
+    UnavailablePulseShape,
+    InvalidSyncWordLength,
+    InvalidPreambleLength,
+    InvalidFrequency,
+    InvalidRadioMode,
+    InvalidLbtParams,
+    ChannelBusy,
+    DutyCycleLimitReached(u64),
+    TimeOnAirExceedsDutyCycle,
+    DeviceError(DeviceErrors),
 }
 
-/// Status for a received packet
-#[derive(Clone, Copy)]
+/// Errors reported by the LoRa chip itself (Sx126x), which persist until cleared
+#[derive(Clone, Copy, Default, Debug, defmt::Format, PartialEq)]
+#[allow(missing_docs)]
+pub struct DeviceErrors {
+    pub rc64k_calibration: bool, // RC64k oscillator calibration failure
+    pub rc13m_calibration: bool, // RC13M oscillator calibration failure
+    pub pll_calibration: bool,   // PLL calibration failure
+    pub adc_calibration: bool,   // ADC calibration failure
+    pub image_calibration: bool, // image rejection calibration failure
+    pub xosc_start: bool,        // crystal oscillator or TCXO failed to start
+    pub pll_lock: bool,          // PLL failed to lock
+    pub pa_ramp: bool,           // PA ramping failure
+}
+
+impl DeviceErrors {
+    /// Check whether any error is reported
+    pub fn any(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+/// Reception statistics, counted by the chip or, for chips without statistics counters, by the driver as it
+/// processes receive operations.  For FSK packets on the Sx126x, header errors are packet length errors.
+#[derive(Clone, Copy, Default, Debug, defmt::Format, PartialEq)]
+#[allow(missing_docs)]
+pub struct RxStats {
+    pub packets_received: u16,      // packets received, including those with a CRC error
+    pub crc_errors: u16,            // packets received with a CRC error
+    pub header_errors: Option<u16>, // LoRa headers with a CRC error, if reported by the chip
+}
+
+/// Status for a received packet, where metadata the chip does not report is None.  Since metadata may be added, radio
+/// kinds outside this crate create it with new() and set the optional fields they report.
+#[derive(Clone, Copy, Default)]
+#[non_exhaustive]
 #[allow(missing_docs)]
 pub struct PacketStatus {
     pub rssi: i16,
     pub snr: i16,
+    pub signal_rssi: Option<i16>,           // RSSI of the LoRa signal after despreading
+    pub frequency_error_in_hz: Option<i32>, // offset of the received carrier from the channel frequency
+    pub coding_rate: Option<CodingRate>,    // coding rate given in the received header (explicit header mode)
+    pub crc_present: Option<bool>,          // payload CRC flag given in the received header (explicit header mode)
+    pub header_payload_length: Option<u8>,  // payload length given in the received header (explicit header mode)
+}
+
+impl PacketStatus {
+    /// Create the status of a received packet from its RSSI and SNR, without further metadata
+    pub fn new(rssi: i16, snr: i16) -> Self {
+        Self {
+            rssi,
+            snr,
+            ..Default::default()
+        }
+    }
 }
 
 /// LoRa boards supported by this crate
@@ -54,12 +119,22 @@ pub struct PacketStatus {
 #[allow(missing_docs)]
 pub enum BoardType {
     GenericSx1261, // placeholder for Sx1261-specific features
+    GenericSx1272,
+    GenericSx1273,
+    GenericLlcc68,
+    GenericSx1268,
+    GenericLr1110,
+    GenericLr1120,
+    GenericLr1121,
+    GenericSx1280,
+    GenericSx1281,
     HeltecWifiLoraV31262,
     RpPicoWaveshareSx1262,
     Rak4631Sx1262,
     Rak3172Sx1262,
     Stm32l0Sx1276,
     Stm32wlSx1262,
+    Custom(ChipType), // board described by a user-defined BoardConfig
 }
 
 /// LoRa chips supported by this crate
@@ -68,23 +143,188 @@ pub enum BoardType {
 pub enum ChipType {
     Sx1261,
     Sx1262,
+    Sx1268,
+    Llcc68,
+    Sx1272,
+    Sx1273,
     Sx1276,
     Sx1277,
     Sx1278,
     Sx1279,
+    Lr1110,
+    Lr1120,
+    Lr1121,
+    Sx1280,
+    Sx1281,
 }
 
 impl From<BoardType> for ChipType {
     fn from(board_type: BoardType) -> Self {
         match board_type {
             BoardType::GenericSx1261 => ChipType::Sx1261,
+            BoardType::GenericSx1272 => ChipType::Sx1272,
+            BoardType::GenericSx1273 => ChipType::Sx1273,
+            BoardType::GenericLlcc68 => ChipType::Llcc68,
+            BoardType::GenericSx1268 => ChipType::Sx1268,
+            BoardType::GenericLr1110 => ChipType::Lr1110,
+            BoardType::GenericLr1120 => ChipType::Lr1120,
+            BoardType::GenericLr1121 => ChipType::Lr1121,
+            BoardType::GenericSx1280 => ChipType::Sx1280,
+            BoardType::GenericSx1281 => ChipType::Sx1281,
             BoardType::HeltecWifiLoraV31262 => ChipType::Sx1262,
             BoardType::RpPicoWaveshareSx1262 => ChipType::Sx1262,
             BoardType::Rak4631Sx1262 => ChipType::Sx1262,
             BoardType::Rak3172Sx1262 => ChipType::Sx1262,
             BoardType::Stm32l0Sx1276 => ChipType::Sx1276,
             BoardType::Stm32wlSx1262 => ChipType::Sx1262,
+            BoardType::Custom(chip_type) => chip_type,
+        }
+    }
+}
+
+/// Voltage supplied by the chip to control a TCXO
+#[derive(Clone, Copy, PartialEq)]
+#[allow(missing_docs)]
+pub enum TcxoVoltage {
+    _1V6,
+    _1V7,
+    _1V8,
+    _2V2,
+    _2V4,
+    _2V7,
+    _3V0,
+    _3V3,
+}
+
+/// Reference oscillator of the chip
+#[derive(Clone, Copy, PartialEq)]
+pub enum Oscillator {
+    /// A crystal
+    Xtal,
+    /// A TCXO, which the Sx126x chips power from DIO3 and the Lr11xx chips from VTCXO at the given voltage, waiting for
+    /// it on wake up (the Sx127x chips only need to know it is present)
+    Tcxo {
+        /// control voltage
+        voltage: TcxoVoltage,
+        /// duration allowed for the TCXO to start (ms)
+        startup_time_in_ms: u32,
+    },
+}
+
+/// Regulator powering the chip
+#[derive(Clone, Copy, PartialEq)]
+pub enum Regulator {
+    /// Linear regulator only, which doubles the Rx/Tx current
+    Ldo,
+    /// DC-DC converter, which needs the inductor fitted on the board
+    DcDc,
+}
+
+/// Power amplifier outputs connected to the antenna
+#[derive(Clone, Copy, PartialEq)]
+pub enum PaWiring {
+    /// The low power PA (Sx1261, Stm32wl RFO_LP) or the RFO pin (Sx127x)
+    LowPower,
+    /// The high power PA (Sx1262, Sx1268, Llcc68, Stm32wl RFO_HP) or the PA_BOOST pin (Sx127x)
+    HighPower,
+    /// Both outputs, the high power one being used when boosted transmission is requested
+    LowAndHighPower,
+}
+
+/// Configuration of a board: the LoRa chip and how it is wired.  The BoardType variants are available as presets, so
+/// a board not known to this crate is supported by filling in this configuration with a BoardType::Custom board type,
+/// possibly starting from the preset of a similar board.
+#[derive(Clone, Copy, PartialEq)]
+pub struct BoardConfig {
+    /// Board type reported to the interface variant, which also gives the chip type
+    pub board_type: BoardType,
+    /// Reference oscillator
+    pub oscillator: Oscillator,
+    /// DIO2 controls the RF switch (Sx126x chips)
+    pub dio2_rf_switch: bool,
+    /// Regulator mode (Sx126x and Lr11xx chips)
+    pub regulator: Regulator,
+    /// Power amplifier outputs in use
+    pub pa_wiring: PaWiring,
+    /// Maximum output power allowed by the board (dBm)
+    pub max_tx_power: i32,
+    /// Random numbers may be generated from RSSI readings
+    pub rng_supported: bool,
+}
+
+impl BoardConfig {
+    /// LoRa chip on the board
+    pub fn chip_type(&self) -> ChipType {
+        self.board_type.into()
+    }
+}
+
+impl From<BoardType> for BoardConfig {
+    fn from(board_type: BoardType) -> Self {
+        let chip_type: ChipType = board_type.into();
+        let tcxo_1v7 = Oscillator::Tcxo {
+            voltage: TcxoVoltage::_1V7,
+            startup_time_in_ms: 10,
+        };
+        let (oscillator, dio2_rf_switch, regulator, pa_wiring) = match chip_type {
+            ChipType::Sx1261 => (tcxo_1v7, true, Regulator::DcDc, PaWiring::LowPower),
+            ChipType::Sx1262 | ChipType::Sx1268 | ChipType::Llcc68 => {
+                (tcxo_1v7, true, Regulator::DcDc, PaWiring::HighPower)
+            }
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => (tcxo_1v7, false, Regulator::Ldo, PaWiring::LowAndHighPower),
+            ChipType::Lr1110 | ChipType::Lr1120 | ChipType::Lr1121 => (
+                Oscillator::Tcxo {
+                    voltage: TcxoVoltage::_1V8,
+                    startup_time_in_ms: 5,
+                },
+                false,
+                Regulator::DcDc,
+                PaWiring::LowAndHighPower,
+            ),
+            ChipType::Sx1280 | ChipType::Sx1281 => (Oscillator::Xtal, false, Regulator::DcDc, PaWiring::HighPower),
+        };
+        let max_tx_power = match chip_type {
+            ChipType::Sx1261 => 15,
+            ChipType::Sx1272
+            | ChipType::Sx1273
+            | ChipType::Sx1276
+            | ChipType::Sx1277
+            | ChipType::Sx1278
+            | ChipType::Sx1279 => 20,
+            ChipType::Sx1280 | ChipType::Sx1281 => 13,
+            _ => 22,
+        };
+        let mut board_config = Self {
+            board_type,
+            oscillator,
+            dio2_rf_switch,
+            regulator,
+            pa_wiring,
+            max_tx_power,
+            rng_supported: true,
+        };
+        match board_type {
+            BoardType::HeltecWifiLoraV31262 => {
+                board_config.oscillator = Oscillator::Tcxo {
+                    voltage: TcxoVoltage::_1V8,
+                    startup_time_in_ms: 10,
+                }
+            }
+            BoardType::Rak3172Sx1262 => board_config.oscillator = Oscillator::Xtal,
+            BoardType::Stm32wlSx1262 => {
+                // the RF switch is driven by GPIOs and the stm32wl often returns 0 on the first random number
+                // generation operation (its documentation does not recommend LNA register modification)
+                board_config.dio2_rf_switch = false;
+                board_config.rng_supported = false;
+            }
+            _ => {}
         }
+        board_config
     }
 }
 
@@ -96,6 +336,7 @@ pub enum RadioMode {
     Standby,                  // standby mode
     FrequencySynthesis,       // frequency synthesis mode
     Transmit,                 // transmit mode
+    ContinuousTransmit,       // continuous wave or preamble transmit mode, for test purposes
     Receive,                  // receive mode
     ReceiveDutyCycle,         // receive duty cycle mode
     ChannelActivityDetection, // channel activity detection mode
@@ -115,6 +356,22 @@ pub enum SpreadingFactor {
     _12,
 }
 
+impl SpreadingFactor {
+    // Spreading factor as the base 2 logarithm of the number of chips per symbol
+    pub(crate) fn factor(self) -> u32 {
+        match self {
+            SpreadingFactor::_5 => 5,
+            SpreadingFactor::_6 => 6,
+            SpreadingFactor::_7 => 7,
+            SpreadingFactor::_8 => 8,
+            SpreadingFactor::_9 => 9,
+            SpreadingFactor::_10 => 10,
+            SpreadingFactor::_11 => 11,
+            SpreadingFactor::_12 => 12,
+        }
+    }
+}
+
 /// Valid bandwidths for one or more LoRa chips supported by this crate
 #[derive(Clone, Copy, PartialEq)]
 #[allow(missing_docs)]
@@ -129,6 +386,10 @@ pub enum Bandwidth {
     _125KHz,
     _250KHz,
     _500KHz,
+    _203KHz,
+    _406KHz,
+    _812KHz,
+    _1625KHz,
 }
 
 impl Bandwidth {
@@ -145,18 +406,25 @@ impl Bandwidth {
             Bandwidth::_125KHz => 125000u32,
             Bandwidth::_250KHz => 250000u32,
             Bandwidth::_500KHz => 500000u32,
+            Bandwidth::_203KHz => 203125u32,
+            Bandwidth::_406KHz => 406250u32,
+            Bandwidth::_812KHz => 812500u32,
+            Bandwidth::_1625KHz => 1625000u32,
         }
     }
 }
 
 /// Valid coding rates for one or more LoRa chips supported by this crate
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 #[allow(missing_docs)]
 pub enum CodingRate {
     _4_5,
     _4_6,
     _4_7,
     _4_8,
+    _4_5Li, // long interleaving
+    _4_6Li, // long interleaving
+    _4_8Li, // long interleaving
 }
 
 /// Modulation parameters for a send and/or receive communication channel
@@ -177,20 +445,426 @@ pub struct PacketParams {
     pub(crate) iq_inverted: bool,
 }
 
+impl ModulationParams {
+    /// Get the time on air in microseconds (rounded up) of a packet sent using these modulation parameters and the
+    /// given packet parameters, following the time-on-air formulas of the Semtech datasheets.  The chip type is needed
+    /// since the Sx126x, Sx128x, and Lr11xx chips use a shorter header and a longer preamble for spreading factors 5
+    /// and 6.  The long interleaving coding rates, whose packets are counted by a different formula, are not supported.
+    pub fn time_on_air_in_us(&self, pkt_params: &PacketParams, chip_type: ChipType) -> Result<u32, RadioError> {
+        let spreading_factor = self.spreading_factor.factor() as i64;
+        let coding_rate_denominator: i64 = match self.coding_rate {
+            CodingRate::_4_5 => 5,
+            CodingRate::_4_6 => 6,
+            CodingRate::_4_7 => 7,
+            CodingRate::_4_8 => 8,
+            CodingRate::_4_5Li | CodingRate::_4_6Li | CodingRate::_4_8Li => {
+                return Err(RadioError::UnavailableCodingRate)
+            }
+        };
+        let sx126x_low_spreading_factor = matches!(
+            chip_type,
+            ChipType::Sx1261
+                | ChipType::Sx1262
+                | ChipType::Sx1268
+                | ChipType::Llcc68
+                | ChipType::Lr1110
+                | ChipType::Lr1120
+                | ChipType::Lr1121
+                | ChipType::Sx1280
+                | ChipType::Sx1281
+        ) && (spreading_factor <= 6);
+
+        let mut numerator = (8 * pkt_params.payload_length as i64) - (4 * spreading_factor);
+        if pkt_params.crc_on {
+            numerator += 16;
+        }
+        if !pkt_params.implicit_header {
+            numerator += 20;
+        }
+        if !sx126x_low_spreading_factor {
+            numerator += 8;
+        }
+        let denominator = match self.low_data_rate_optimize != 0 {
+            true => 4 * (spreading_factor - 2),
+            false => 4 * spreading_factor,
+        };
+        let payload_symbols =
+            8 + ((core::cmp::max(numerator, 0) + denominator - 1) / denominator) * coding_rate_denominator;
+
+        // count in quarter symbols to account for the 4.25 (or 6.25) symbols added to the preamble
+        let preamble_quarter_symbols = match sx126x_low_spreading_factor {
+            true => 25,
+            false => 17,
+        };
+        let quarter_symbols =
+            (4 * pkt_params.preamble_length as i64) + preamble_quarter_symbols + (4 * payload_symbols);
+
+        let numerator_in_us = (quarter_symbols as u64) * (1u64 << spreading_factor) * 1_000_000u64;
+        let denominator_in_us = 4u64 * (self.bandwidth.value_in_hz() as u64);
+        Ok(((numerator_in_us + denominator_in_us - 1) / denominator_in_us) as u32)
+    }
+}
+
 impl PacketParams {
-    pub(crate) fn set_payload_length(&mut self, payload_length: usize) -> Result<(), RadioError> {
+    /// Set the length of the payload, for instance to calculate the time on air of a packet prior to a send operation
+    pub fn set_payload_length(&mut self, payload_length: usize) -> Result<(), RadioError> {
+        if payload_length > 255 {
+            return Err(RadioError::PayloadSizeUnexpected(payload_length));
+        }
+        self.payload_length = payload_length as u8;
+        Ok(())
+    }
+}
+
+/// LoRa sync word, given as the one-byte value used by the Sx127x chips.  Other chips use a two-byte form, derived by
+/// placing each nibble in the upper nibble of a byte whose lower nibble is 4 (for instance, 0x34 becomes 0x3444).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyncWord(u8);
+
+impl SyncWord {
+    /// Sync word of public networks, such as LoRaWAN
+    pub const PUBLIC: SyncWord = SyncWord(0x34);
+    /// Sync word of private networks
+    pub const PRIVATE: SyncWord = SyncWord(0x12);
+
+    /// Create a sync word from its one-byte Sx127x value (for instance, 0x2B for Meshtastic)
+    pub const fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Get the one-byte Sx127x value of the sync word
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    /// Get the two-byte Sx126x register value of the sync word
+    pub const fn sx126x_value(self) -> u16 {
+        ((((self.0 & 0xF0) | 0x04) as u16) << 8) | ((((self.0 & 0x0F) << 4) | 0x04) as u16)
+    }
+}
+
+impl From<bool> for SyncWord {
+    /// Select the sync word of public or private networks
+    fn from(enable_public_network: bool) -> Self {
+        match enable_public_network {
+            true => SyncWord::PUBLIC,
+            false => SyncWord::PRIVATE,
+        }
+    }
+}
+
+/// The modem used by a multi-protocol chip
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Modem {
+    LoRa,
+    Fsk,
+}
+
+/// Valid pulse shapes (Gaussian filter bandwidth-time products) for FSK modulation, or filters for OOK modulation, on
+/// one or more LoRa chips supported by this crate
+#[derive(Clone, Copy, PartialEq)]
+#[allow(missing_docs)]
+pub enum PulseShape {
+    None,
+    GaussianBt0_3,
+    GaussianBt0_5,
+    GaussianBt0_7,
+    GaussianBt1_0,
+    Ook,                // OOK modulation without filtering
+    OokCutoffBitrate,   // OOK modulation filtered with a cutoff frequency of the bitrate
+    OokCutoff2xBitrate, // OOK modulation filtered with a cutoff frequency of twice the bitrate
+}
+
+/// Valid CRC types for FSK packets on one or more LoRa chips supported by this crate
+#[derive(Clone, Copy, PartialEq)]
+pub enum FskCrcType {
+    /// No CRC
+    None,
+    /// Two-byte CCITT CRC (polynomial 0x1021, initial value 0x1D0F, inverted)
+    Ccitt,
+    /// Two-byte IBM CRC (polynomial 0x8005, initial value 0xFFFF)
+    Ibm,
+}
+
+/// FSK modulation parameters for a send and/or receive communication channel
+pub struct FskModulationParams {
+    pub(crate) bitrate_in_bps: u32,
+    pub(crate) frequency_deviation_in_hz: u32,
+    pub(crate) pulse_shape: PulseShape,
+    pub(crate) rx_bandwidth_in_hz: u32, // smallest receive bandwidth supported by the chip covering the requested one
+    pub(crate) frequency_in_hz: u32,
+}
+
+/// FSK packet parameters for a send or receive communication channel
+pub struct FskPacketParams {
+    pub(crate) preamble_length: u16, // number of bytes in the preamble
+    pub(crate) sync_word: [u8; 8],
+    pub(crate) sync_word_length: u8,  // number of bytes in the sync word
+    pub(crate) variable_length: bool, // if the length is variable, it is transmitted as the first byte of the packet
+    pub(crate) payload_length: u8,
+    pub(crate) crc_type: FskCrcType,
+    pub(crate) whitening: bool,
+}
+
+impl FskPacketParams {
+    /// Set the length of the payload prior to a send operation
+    pub fn set_payload_length(&mut self, payload_length: usize) -> Result<(), RadioError> {
         if payload_length > 255 {
             return Err(RadioError::PayloadSizeUnexpected(payload_length));
         }
         self.payload_length = payload_length as u8;
         Ok(())
     }
+
+    pub(crate) fn new(
+        preamble_length: u16,
+        sync_word: &[u8],
+        variable_length: bool,
+        payload_length: u8,
+        crc_type: FskCrcType,
+        whitening: bool,
+    ) -> Result<Self, RadioError> {
+        if sync_word.is_empty() || sync_word.len() > 8 {
+            return Err(RadioError::InvalidSyncWordLength);
+        }
+        let mut sync_word_bytes = [0x00u8; 8];
+        sync_word_bytes[..sync_word.len()].copy_from_slice(sync_word);
+
+        Ok(Self {
+            preamble_length,
+            sync_word: sync_word_bytes,
+            sync_word_length: sync_word.len() as u8,
+            variable_length,
+            payload_length,
+            crc_type,
+            whitening,
+        })
+    }
 }
 
-/// Receive duty cycle parameters
+/// Receive duty cycle parameters.  The Sx126x chips time the intervals in hardware.  The Sx127x chips have no timer,
+/// so the intervals are timed in software with the delay of the radio kind, in the same steps as the Sx126x chips, and
+/// a receive window in which a preamble is detected is extended until the packet is received or the window times out.
 #[derive(Clone, Copy)]
 #[allow(missing_docs)]
 pub struct DutyCycleParams {
-    pub rx_time: u32,    // receive interval
-    pub sleep_time: u32, // sleep interval
+    pub rx_time: u32, // receive interval, in timer steps of the chip (15.625 us for Sx126x, emulated on Sx127x)
+    pub sleep_time: u32, // sleep interval, in timer steps of the chip
+}
+
+/// Listen-before-talk parameters, giving how the channel is sensed before a send operation and how long to back off
+/// while it is busy
+#[derive(Clone, Copy)]
+#[allow(missing_docs)]
+pub struct LbtParams {
+    pub cad: bool,                   // sense LoRa activity with a channel activity detection operation
+    pub rssi_threshold: Option<i16>, // channel busy if the RSSI reaches this level (dBm) during the listen time
+    pub listen_time_in_us: u32,      // duration of the RSSI measurements
+    pub min_backoff_in_ms: u32,      // shortest random backoff while the channel is busy
+    pub max_backoff_in_ms: u32,      // longest random backoff while the channel is busy
+    pub max_attempts: u32,           // channel sensing attempts before giving up
+    pub deadline_in_ms: Option<u32>, // give up once the CAD, listen, and backoff times add up to this duration
+}
+
+/// Number of symbols over which a channel activity detection operation looks for LoRa activity
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum CadSymbols {
+    _1,
+    _2,
+    _4,
+    _8,
+    _16,
+}
+
+/// Operation the LoRa chip chains at the end of a channel activity detection operation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CadExitMode {
+    /// Return to standby mode
+    CadOnly,
+    /// Start a receive operation if activity is detected, otherwise return to standby mode
+    CadRx,
+    /// Start a send operation if no activity is detected (listen-before-talk), otherwise return to standby mode
+    CadLbt,
+}
+
+/// Channel activity detection parameters.  The detection thresholds depend on the spreading factor and bandwidth;
+/// recommended values for a 125 kHz bandwidth are given by recommended_for().  The Sx127x chips only support the
+/// number of symbols of their hardware and the Sx128x chips only the number of symbols; other settings are ignored.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub struct CadParams {
+    pub symbols: CadSymbols,
+    pub det_peak: u8, // limit for detection of the SNR peak (Sx126x, LR11xx)
+    pub det_min: u8,  // minimum symbol recognition (Sx126x, LR11xx)
+    pub exit_mode: CadExitMode,
+    pub timeout_in_ms: u32, // duration of the chained receive or send operation (0 for none)
+}
+
+impl CadParams {
+    /// Recommended parameters for the spreading factor at a 125 kHz bandwidth, from Semtech application note
+    /// AN1200.48, without a chained operation
+    pub fn recommended_for(spreading_factor: SpreadingFactor) -> Self {
+        let (symbols, det_peak) = match spreading_factor {
+            // SF5 and SF6 are not covered by the application note and use the SF7 values
+            SpreadingFactor::_5 | SpreadingFactor::_6 | SpreadingFactor::_7 | SpreadingFactor::_8 => {
+                (CadSymbols::_2, 22)
+            }
+            SpreadingFactor::_9 => (CadSymbols::_4, 23),
+            SpreadingFactor::_10 => (CadSymbols::_4, 24),
+            SpreadingFactor::_11 => (CadSymbols::_4, 25),
+            SpreadingFactor::_12 => (CadSymbols::_4, 28),
+        };
+        Self {
+            symbols,
+            det_peak,
+            det_min: 10,
+            exit_mode: CadExitMode::CadOnly,
+            timeout_in_ms: 0,
+        }
+    }
+
+    // Duration of a CAD operation using these parameters and symbols of the given modulation parameters
+    pub(crate) fn duration_in_us(&self, mdltn_params: &ModulationParams) -> u32 {
+        let symbols = match self.symbols {
+            CadSymbols::_1 => 1u64,
+            CadSymbols::_2 => 2,
+            CadSymbols::_4 => 4,
+            CadSymbols::_8 => 8,
+            CadSymbols::_16 => 16,
+        };
+        let chips = symbols << mdltn_params.spreading_factor.factor();
+        ((chips * 1_000_000) / mdltn_params.bandwidth.value_in_hz() as u64) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reference values from the Semtech LoRa Calculator (8 symbol preamble, explicit header, CRC on, unless noted)
+
+    #[test]
+    fn time_on_air_sf7_bw125() {
+        let mdltn_params = ModulationParams::new_for_sx1276_7_8_9(
+            SpreadingFactor::_7,
+            Bandwidth::_125KHz,
+            CodingRate::_4_5,
+            868_100_000,
+        )
+        .unwrap();
+        let mut pkt_params = PacketParams::new_for_sx1276_7_8_9(8, false, 10, true, false, &mdltn_params).unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1276).unwrap(),
+            41_216
+        );
+        pkt_params.set_payload_length(64).unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1276).unwrap(),
+            118_016
+        );
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1262).unwrap(),
+            118_016
+        );
+    }
+
+    #[test]
+    fn time_on_air_sf10_and_sf12_bw125() {
+        let mut mdltn_params =
+            ModulationParams::new_for_sx1261_2(SpreadingFactor::_10, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+                .unwrap();
+        let mut pkt_params = PacketParams::new_for_sx1261_2(8, false, 13, true, false, &mdltn_params).unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1262).unwrap(),
+            288_768
+        );
+
+        // low data rate optimization
+        mdltn_params =
+            ModulationParams::new_for_sx1261_2(SpreadingFactor::_12, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+                .unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1262).unwrap(),
+            1_155_072
+        );
+        pkt_params.set_payload_length(64).unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1262).unwrap(),
+            2_793_472
+        );
+    }
+
+    #[test]
+    fn time_on_air_low_spreading_factors() {
+        let mut mdltn_params =
+            ModulationParams::new_for_sx1261_2(SpreadingFactor::_5, Bandwidth::_500KHz, CodingRate::_4_5, 915_000_000)
+                .unwrap();
+        let pkt_params = PacketParams::new_for_sx1261_2(8, false, 10, true, false, &mdltn_params).unwrap();
+        assert_eq!(pkt_params.preamble_length, 12);
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1262).unwrap(),
+            3_280
+        );
+
+        // Sx126x and Sx127x differ for spreading factor 6 (implicit header required on Sx127x)
+        mdltn_params = ModulationParams::new_for_sx1276_7_8_9(
+            SpreadingFactor::_6,
+            Bandwidth::_125KHz,
+            CodingRate::_4_5,
+            915_000_000,
+        )
+        .unwrap();
+        let pkt_params = PacketParams::new_for_sx1276_7_8_9(8, true, 10, true, false, &mdltn_params).unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1276).unwrap(),
+            20_608
+        );
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1262).unwrap(),
+            19_072
+        );
+    }
+
+    #[test]
+    fn time_on_air_rounds_up() {
+        let mdltn_params = ModulationParams::new_for_sx1276_7_8_9(
+            SpreadingFactor::_7,
+            Bandwidth::_41KHz,
+            CodingRate::_4_8,
+            433_000_000,
+        )
+        .unwrap();
+        let pkt_params = PacketParams::new_for_sx1276_7_8_9(8, false, 1, false, false, &mdltn_params).unwrap();
+        // 28.25 symbols of 128 / 41670 s
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1276).unwrap(),
+            86_778
+        );
+    }
+
+    #[test]
+    fn time_on_air_long_interleaving_unavailable() {
+        let mdltn_params = ModulationParams::new_for_sx1280(
+            SpreadingFactor::_7,
+            Bandwidth::_812KHz,
+            CodingRate::_4_5Li,
+            2_440_000_000,
+        )
+        .unwrap();
+        let pkt_params = PacketParams::new_for_sx1280(12, false, 10, true, false, &mdltn_params).unwrap();
+        assert_eq!(
+            mdltn_params.time_on_air_in_us(&pkt_params, ChipType::Sx1280),
+            Err(RadioError::UnavailableCodingRate)
+        );
+    }
+
+    #[test]
+    fn sync_word_sx126x_value() {
+        assert_eq!(SyncWord::PUBLIC.sx126x_value(), 0x3444);
+        assert_eq!(SyncWord::PRIVATE.sx126x_value(), 0x1424);
+        assert_eq!(SyncWord::new(0x2b).sx126x_value(), 0x24b4);
+        assert_eq!(SyncWord::from(true), SyncWord::PUBLIC);
+        assert_eq!(SyncWord::from(false).value(), 0x12);
+    }
 }
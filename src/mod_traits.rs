@@ -40,8 +40,10 @@ pub trait RadioKind {
     async fn set_standby(&mut self) -> Result<(), RadioError>;
     /// Place the LoRa chip in power-saving mode
     async fn set_sleep(&mut self, delay: &mut impl DelayUs) -> Result<bool, RadioError>;
-    /// Perform operations to set a multi-protocol chip as a LoRa chip
-    async fn set_lora_modem(&mut self, enable_public_network: bool) -> Result<(), RadioError>;
+    /// Perform operations to set a multi-protocol chip as a LoRa chip using the given sync word
+    async fn set_lora_modem(&mut self, sync_word: SyncWord) -> Result<(), RadioError>;
+    /// Perform operations to set a multi-protocol chip as an FSK chip
+    async fn set_fsk_modem(&mut self) -> Result<(), RadioError>;
     /// Perform operations to set the LoRa chip oscillator
     async fn set_oscillator(&mut self) -> Result<(), RadioError>;
     /// Set the LoRa chip voltage regulator mode
@@ -66,6 +68,10 @@ pub trait RadioKind {
     async fn set_modulation_params(&mut self, mdltn_params: &ModulationParams) -> Result<(), RadioError>;
     /// Set the LoRa chip packet parameters prior to sending or receiving packets
     async fn set_packet_params(&mut self, pkt_params: &PacketParams) -> Result<(), RadioError>;
+    /// Set the FSK modulation parameters prior to using a communication channel
+    async fn set_fsk_modulation_params(&mut self, mdltn_params: &FskModulationParams) -> Result<(), RadioError>;
+    /// Set the FSK packet parameters prior to sending or receiving packets
+    async fn set_fsk_packet_params(&mut self, pkt_params: &FskPacketParams) -> Result<(), RadioError>;
     /// Set the LoRa chip to support a given communication channel frequency
     async fn calibrate_image(&mut self, frequency_in_hz: u32) -> Result<(), RadioError>;
     /// Set the frequency for a communication channel
@@ -74,6 +80,10 @@ pub trait RadioKind {
     async fn set_payload(&mut self, payload: &[u8]) -> Result<(), RadioError>;
     /// Perform a send operation
     async fn do_tx(&mut self, timeout_in_ms: u32) -> Result<(), RadioError>;
+    /// Transmit an unmodulated carrier on the channel, until the LoRa chip is placed in standby mode
+    async fn do_tx_continuous_wave(&mut self) -> Result<(), RadioError>;
+    /// Transmit an endless LoRa preamble on the channel, until the LoRa chip is placed in standby mode
+    async fn do_tx_continuous_preamble(&mut self) -> Result<(), RadioError>;
     /// Set up to perform a receive operation (single-shot, continuous, or duty cycle)
     async fn do_rx(
         &mut self,
@@ -92,14 +102,40 @@ pub trait RadioKind {
     ) -> Result<u8, RadioError>;
     /// Get the RSSI and SNR for the packet made available as the result of a receive operation
     async fn get_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError>;
-    /// Perform a channel activity detection operation
-    async fn do_cad(
+    /// Get the instantaneous RSSI (dBm) of the channel while receiving
+    async fn get_rssi(&mut self) -> Result<i16, RadioError>;
+    /// Get the reception statistics accumulated since the last reset
+    async fn get_rx_stats(&mut self) -> Result<RxStats, RadioError>;
+    /// Reset the reception statistics
+    async fn reset_rx_stats(&mut self) -> Result<(), RadioError>;
+    /// Set up to perform an FSK receive operation (single-shot or continuous)
+    async fn do_fsk_rx(
         &mut self,
-        mdltn_params: &ModulationParams,
+        rx_continuous: bool,
         rx_boosted_if_supported: bool,
+        rx_timeout_in_ms: u32,
     ) -> Result<(), RadioError>;
+    /// Get an available packet made available as the result of an FSK receive operation
+    async fn get_fsk_rx_payload(
+        &mut self,
+        rx_pkt_params: &FskPacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError>;
+    /// Get the RSSI for the packet made available as the result of an FSK receive operation
+    async fn get_fsk_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError>;
+    /// Indicate if the LoRa chip can chain a receive operation to a channel activity detection operation (CAD-RX exit
+    /// mode)
+    fn cad_rx_supported(&self) -> bool {
+        false
+    }
+    /// Perform a channel activity detection operation, chaining the operation given by the exit mode
+    async fn do_cad(&mut self, cad_params: &CadParams, rx_boosted_if_supported: bool) -> Result<(), RadioError>;
     /// Set the LoRa chip to provide notification of specific events based on radio state
     async fn set_irq_params(&mut self, radio_mode: Option<RadioMode>) -> Result<(), RadioError>;
+    /// Get the errors reported by the LoRa chip, such as calibration or oscillator failures
+    async fn get_device_errors(&mut self) -> Result<DeviceErrors, RadioError>;
+    /// Clear the errors reported by the LoRa chip and calibrate it again, which requires standby mode
+    async fn clear_device_errors_and_calibrate(&mut self) -> Result<(), RadioError>;
     /// Process LoRa chip notifications of events
     async fn process_irq(
         &mut self,
@@ -107,13 +143,23 @@ pub trait RadioKind {
         rx_continuous: bool,
         cad_activity_detected: Option<&mut bool>,
     ) -> Result<(), RadioError>;
+    /// Generate a 32 bit random value, if the LoRa chip has a random number generator.  Radio kinds without one need
+    /// not implement this.
+    async fn get_random_number(&mut self) -> Result<u32, RadioError> {
+        Err(RadioError::RngUnsupported)
+    }
 }
 
-/// Internal trait for specifying that a [`RadioKind`] object has RNG capability.
-pub(crate) trait RngRadio: RadioKind {
-    async fn get_random_number(&mut self) -> Result<u32, RadioError>;
+/// A monotonic clock provided by the embedded framework, giving the time against which the duty-cycle of send
+/// operations is accounted
+pub trait Clock {
+    /// Get the current time in milliseconds
+    fn now_in_ms(&self) -> u64;
 }
 
+/// Internal trait for specifying that a [`RadioKind`] object has RNG capability, providing [`AsyncRng`].
+pub(crate) trait RngRadio: RadioKind {}
+
 /// If the LoRa chip supports it, provides an async implementation of the onboard RNG. This trait makes no guarantees
 /// with regards to the distribution of the generated random numbers (ie, uniform or Gaussian). If uniformity is
 /// needed, apply necessary software processing.
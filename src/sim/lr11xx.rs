@@ -0,0 +1,927 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::spi::{ErrorType, SpiBus, SpiBusFlush, SpiBusRead, SpiBusWrite};
+
+use crate::lr11xx::radio_kind_params::{CommandStatus, IrqMask, OpCode, PacketType};
+use crate::mod_params::*;
+use crate::mod_traits::InterfaceVariant;
+use crate::sim::{SimPacket, BUFFER_SIZE};
+
+const MAX_COMMAND_SIZE: usize = 2 + BUFFER_SIZE;
+
+// Timeout value requesting continuous receive
+const RX_CONTINUOUS_TIMEOUT: u32 = 0x00ff_ffff;
+
+const ALL_OP_CODES: [OpCode; 42] = [
+    OpCode::GetStatus,
+    OpCode::GetVersion,
+    OpCode::WriteBuffer8,
+    OpCode::ReadBuffer8,
+    OpCode::ClearRxBuffer,
+    OpCode::GetErrors,
+    OpCode::ClearErrors,
+    OpCode::Calibrate,
+    OpCode::SetRegMode,
+    OpCode::CalibImage,
+    OpCode::SetDioAsRfSwitch,
+    OpCode::SetDioIrqParams,
+    OpCode::ClearIrq,
+    OpCode::SetTcxoMode,
+    OpCode::SetSleep,
+    OpCode::SetStandby,
+    OpCode::SetFs,
+    OpCode::GetRandomNumber,
+    OpCode::ResetStats,
+    OpCode::GetStats,
+    OpCode::GetPacketType,
+    OpCode::GetRxBufferStatus,
+    OpCode::GetPacketStatus,
+    OpCode::GetRssiInst,
+    OpCode::SetRx,
+    OpCode::SetTx,
+    OpCode::SetRfFrequency,
+    OpCode::SetCadParams,
+    OpCode::SetPacketType,
+    OpCode::SetModulationParams,
+    OpCode::SetPacketParams,
+    OpCode::SetTxParams,
+    OpCode::SetRxTxFallbackMode,
+    OpCode::SetRxDutyCycle,
+    OpCode::SetPaConfig,
+    OpCode::StopTimeoutOnPreamble,
+    OpCode::SetCad,
+    OpCode::SetTxCw,
+    OpCode::SetTxInfinitePreamble,
+    OpCode::SetLoRaSynchTimeout,
+    OpCode::SetRxBoosted,
+    OpCode::SetLoRaSyncWord,
+];
+
+// An operation started by a command, completed when the simulated chip raises the corresponding IRQ
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    None,
+    Tx,
+    Rx { timeout: u32 },
+    Cad,
+}
+
+struct State {
+    board_type: Option<BoardType>,
+    mode: RadioMode,
+    warm_start: bool,
+    nss_low: bool,
+    command: [u8; MAX_COMMAND_SIZE],
+    command_len: usize,
+    command_status: CommandStatus,
+    response: [u8; BUFFER_SIZE],
+    response_len: usize,
+    tx_buffer: [u8; BUFFER_SIZE],
+    rx_buffer: [u8; BUFFER_SIZE],
+    rx_payload_len: u8,
+    packet_type: u8,
+    frequency_in_hz: u32,
+    tcxo_mode: [u8; 4],
+    calibration: u8,
+    regulator_mode: u8,
+    pa_config: [u8; 4],
+    tx_params: [u8; 2],
+    image_calibration: [u8; 2],
+    sync_word: u8,
+    modulation_params: [u8; 4],
+    packet_params: [u8; 6],
+    rx_boosted: bool,
+    irq_mask: u32,
+    irq_status: u32,
+    operation: Operation,
+    rx_packet: Option<SimPacket>,
+    rx_crc_error: bool,
+    tx_packet: Option<SimPacket>,
+    channel_activity: bool,
+    packet_status: [u8; 3],
+    stats: [u16; 4],
+    random: u32,
+}
+
+impl State {
+    fn new() -> Self {
+        let mut state = Self {
+            board_type: None,
+            mode: RadioMode::Standby,
+            warm_start: false,
+            nss_low: false,
+            command: [0x00u8; MAX_COMMAND_SIZE],
+            command_len: 0,
+            command_status: CommandStatus::Ok,
+            response: [0x00u8; BUFFER_SIZE],
+            response_len: 0,
+            tx_buffer: [0x00u8; BUFFER_SIZE],
+            rx_buffer: [0x00u8; BUFFER_SIZE],
+            rx_payload_len: 0,
+            packet_type: PacketType::None.value(),
+            frequency_in_hz: 0,
+            tcxo_mode: [0x00u8; 4],
+            calibration: 0,
+            regulator_mode: 0,
+            pa_config: [0x00u8; 4],
+            tx_params: [0x00u8; 2],
+            image_calibration: [0x00u8; 2],
+            sync_word: 0x12,
+            modulation_params: [0x00u8; 4],
+            packet_params: [0x00u8; 6],
+            rx_boosted: false,
+            irq_mask: IrqMask::None.value(),
+            irq_status: IrqMask::None.value(),
+            operation: Operation::None,
+            rx_packet: None,
+            rx_crc_error: false,
+            tx_packet: None,
+            channel_activity: false,
+            packet_status: [0x00u8; 3],
+            stats: [0u16; 4],
+            random: 0x2545_f491,
+        };
+        state.reset();
+        state
+    }
+
+    // Restore the power-on configuration, keeping the packets and channel conditions supplied by a test
+    fn reset(&mut self) {
+        self.mode = RadioMode::Standby;
+        self.warm_start = false;
+        self.command_len = 0;
+        self.command_status = CommandStatus::Ok;
+        self.response_len = 0;
+        self.tx_buffer = [0x00u8; BUFFER_SIZE];
+        self.rx_buffer = [0x00u8; BUFFER_SIZE];
+        self.rx_payload_len = 0;
+        self.packet_type = PacketType::None.value();
+        self.frequency_in_hz = 0;
+        self.tcxo_mode = [0x00u8; 4];
+        self.calibration = 0;
+        self.regulator_mode = 0;
+        self.pa_config = [0x00u8; 4];
+        self.tx_params = [0x00u8; 2];
+        self.image_calibration = [0x00u8; 2];
+        self.sync_word = 0x12;
+        self.modulation_params = [0x00u8; 4];
+        self.packet_params = [0x00u8; 6];
+        self.rx_boosted = false;
+        self.irq_mask = IrqMask::None.value();
+        self.irq_status = IrqMask::None.value();
+        self.operation = Operation::None;
+    }
+
+    // Stat1: status of the last command and state of the IRQ line
+    fn stat1(&self) -> u8 {
+        self.command_status.value() | (self.dio9() as u8)
+    }
+
+    // Stat2: operating mode of the chip
+    fn stat2(&self) -> u8 {
+        let chip_mode = match self.mode {
+            RadioMode::Sleep => 0x00,
+            RadioMode::Standby => 0x01,
+            RadioMode::FrequencySynthesis => 0x03,
+            RadioMode::Receive | RadioMode::ReceiveDutyCycle | RadioMode::ChannelActivityDetection => 0x04,
+            RadioMode::Transmit | RadioMode::ContinuousTransmit => 0x05,
+        };
+        chip_mode << 1
+    }
+
+    fn select(&mut self) {
+        if self.mode == RadioMode::Sleep {
+            // a falling edge on NSS wakes the chip up
+            if !self.warm_start {
+                self.reset();
+            }
+            self.mode = RadioMode::Standby;
+        }
+        self.nss_low = true;
+        self.command_len = 0;
+    }
+
+    fn deselect(&mut self) {
+        if self.nss_low {
+            self.nss_low = false;
+            self.execute();
+        }
+    }
+
+    // Shift one byte into the chip, returning the byte shifted out
+    fn exchange(&mut self, mosi: u8) -> u8 {
+        let index = self.command_len;
+        if index < MAX_COMMAND_SIZE {
+            self.command[index] = mosi;
+        }
+        self.command_len += 1;
+
+        // the response to a read command is clocked out after Stat1, while the host sends NOPs
+        if self.response_len > 0 && self.command[0] == 0x00 {
+            return match index {
+                0 => self.stat1(),
+                _ => self.response.get(index - 1).copied().unwrap_or(0x00),
+            };
+        }
+        // otherwise, the status is clocked out while a command is sent
+        match index {
+            0 => self.stat1(),
+            1 => self.stat2(),
+            2..=5 => self.irq_status.to_be_bytes()[index - 2],
+            _ => 0x00,
+        }
+    }
+
+    fn set_response(&mut self, data: &[u8]) {
+        let len = core::cmp::min(data.len(), BUFFER_SIZE);
+        self.response[..len].copy_from_slice(&data[..len]);
+        self.response_len = len;
+        self.command_status = CommandStatus::Data;
+    }
+
+    // Execute a command once NSS is released
+    fn execute(&mut self) {
+        let len = core::cmp::min(self.command_len, MAX_COMMAND_SIZE);
+        if len == 0 {
+            return;
+        }
+        if self.response_len > 0 && self.command[0] == 0x00 {
+            // the response has been read
+            self.response_len = 0;
+            return;
+        }
+        self.response_len = 0;
+        if len < 2 {
+            self.command_status = CommandStatus::Fail;
+            return;
+        }
+        let command = self.command;
+        let params = &command[2..len];
+        let param = |i: usize| params.get(i).copied().unwrap_or(0x00);
+        let timeout = ((param(0) as u32) << 16) | ((param(1) as u32) << 8) | (param(2) as u32);
+
+        self.command_status = CommandStatus::Ok;
+        match decode(command[0], command[1]) {
+            None => self.command_status = CommandStatus::Fail,
+            Some(OpCode::WriteBuffer8) => {
+                for (i, byte) in params.iter().take(BUFFER_SIZE).enumerate() {
+                    self.tx_buffer[i] = *byte;
+                }
+            }
+            Some(OpCode::ReadBuffer8) => {
+                let mut data = [0x00u8; BUFFER_SIZE];
+                for (i, byte) in data.iter_mut().take(param(1) as usize).enumerate() {
+                    *byte = self.rx_buffer[(param(0) as usize + i) % BUFFER_SIZE];
+                }
+                self.set_response(&data[..param(1) as usize]);
+            }
+            Some(OpCode::ClearRxBuffer) => self.rx_buffer = [0x00u8; BUFFER_SIZE],
+            Some(OpCode::GetVersion) => {
+                let use_case = match self.board_type.map(ChipType::from) {
+                    Some(ChipType::Lr1120) => 0x02,
+                    Some(ChipType::Lr1121) => 0x03,
+                    _ => 0x01,
+                };
+                self.set_response(&[0x22, use_case, 0x03, 0x08]);
+            }
+            Some(OpCode::GetErrors) => self.set_response(&[0x00, 0x00]),
+            Some(OpCode::Calibrate) => self.calibration = param(0),
+            Some(OpCode::SetRegMode) => self.regulator_mode = param(0),
+            Some(OpCode::CalibImage) => self.image_calibration = [param(0), param(1)],
+            Some(OpCode::SetDioIrqParams) => {
+                self.irq_mask = u32::from_be_bytes([param(0), param(1), param(2), param(3)]);
+            }
+            Some(OpCode::ClearIrq) => {
+                self.irq_status &= !u32::from_be_bytes([param(0), param(1), param(2), param(3)]);
+            }
+            Some(OpCode::SetTcxoMode) => self.tcxo_mode = [param(0), param(1), param(2), param(3)],
+            Some(OpCode::SetSleep) => {
+                self.warm_start = (param(0) & 0x01) != 0;
+                self.mode = RadioMode::Sleep;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetStandby) => {
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetFs) => self.mode = RadioMode::FrequencySynthesis,
+            Some(OpCode::GetRandomNumber) => {
+                self.generate_random_number();
+                self.set_response(&self.random.to_be_bytes());
+            }
+            Some(OpCode::ResetStats) => self.stats = [0u16; 4],
+            Some(OpCode::GetStats) => {
+                let mut data = [0x00u8; 8];
+                for (i, stat) in self.stats.iter().enumerate() {
+                    data[2 * i..2 * i + 2].copy_from_slice(&stat.to_be_bytes());
+                }
+                self.set_response(&data);
+            }
+            Some(OpCode::GetPacketType) => self.set_response(&[self.packet_type]),
+            Some(OpCode::GetRxBufferStatus) => self.set_response(&[self.rx_payload_len, 0x00]),
+            // RssiPkt, SnrPkt, SignalRssiPkt
+            Some(OpCode::GetPacketStatus) => {
+                let packet_status = self.packet_status;
+                self.set_response(&packet_status);
+            }
+            Some(OpCode::GetRssiInst) => self.set_response(&[self.packet_status[0]]),
+            Some(OpCode::SetRx) => {
+                self.mode = RadioMode::Receive;
+                self.operation = Operation::Rx { timeout };
+            }
+            Some(OpCode::SetTx) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::Tx;
+            }
+            Some(OpCode::SetRfFrequency) => {
+                self.frequency_in_hz = u32::from_be_bytes([param(0), param(1), param(2), param(3)]);
+            }
+            Some(OpCode::SetPacketType) => self.packet_type = param(0),
+            Some(OpCode::SetModulationParams) => {
+                for (i, byte) in self.modulation_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+            }
+            Some(OpCode::SetPacketParams) => {
+                for (i, byte) in self.packet_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+            }
+            Some(OpCode::SetTxParams) => self.tx_params = [param(0), param(1)],
+            Some(OpCode::SetRxDutyCycle) => {
+                self.mode = RadioMode::ReceiveDutyCycle;
+                self.operation = Operation::Rx { timeout: 0 };
+            }
+            Some(OpCode::SetPaConfig) => self.pa_config = [param(0), param(1), param(2), param(3)],
+            Some(OpCode::SetCad) => {
+                self.mode = RadioMode::ChannelActivityDetection;
+                self.operation = Operation::Cad;
+            }
+            Some(OpCode::SetTxCw) | Some(OpCode::SetTxInfinitePreamble) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetRxBoosted) => self.rx_boosted = param(0) != 0,
+            Some(OpCode::SetLoRaSyncWord) => self.sync_word = param(0),
+            Some(_) => {}
+        }
+    }
+
+    fn raise_irq(&mut self, irq: u32) {
+        self.irq_status |= irq & self.irq_mask;
+    }
+
+    fn dio9(&self) -> bool {
+        (self.irq_status & self.irq_mask) != 0
+    }
+
+    // Complete the pending operation, as the chip would once the corresponding on-air event occurs
+    fn complete_operation(&mut self) {
+        match self.operation {
+            Operation::None => {}
+            Operation::Tx => {
+                let payload_len = self.packet_params[3] as usize;
+                self.tx_packet = Some(SimPacket::new(&self.tx_buffer[..payload_len]));
+                self.raise_irq(IrqMask::TxDone.value());
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+            Operation::Rx { timeout } => {
+                let continuous = timeout == RX_CONTINUOUS_TIMEOUT;
+                if let Some(packet) = self.rx_packet.take() {
+                    let implicit_header = self.packet_params[2] != 0;
+                    let payload_len = match implicit_header {
+                        true => self.packet_params[3] as usize,
+                        false => packet.len,
+                    };
+                    self.rx_buffer[..payload_len].copy_from_slice(&packet.data[..payload_len]);
+                    self.rx_payload_len = payload_len as u8;
+                    self.stats[0] = self.stats[0].wrapping_add(1);
+
+                    let mut irq = IrqMask::PreambleDetected.value() | IrqMask::RxDone.value();
+                    if !implicit_header {
+                        irq |= IrqMask::HeaderValid.value();
+                    }
+                    if self.rx_crc_error {
+                        irq |= IrqMask::CRCError.value();
+                        self.stats[1] = self.stats[1].wrapping_add(1);
+                    }
+                    self.raise_irq(irq);
+                } else if timeout != 0 && !continuous {
+                    self.raise_irq(IrqMask::RxTxTimeout.value());
+                } else {
+                    return;
+                }
+                if !continuous {
+                    self.mode = RadioMode::Standby;
+                    self.operation = Operation::None;
+                }
+            }
+            Operation::Cad => {
+                let mut irq = IrqMask::CADDone.value();
+                if self.channel_activity {
+                    irq |= IrqMask::CADActivityDetected.value();
+                }
+                self.raise_irq(irq);
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+        }
+    }
+
+    // xorshift32, providing the values returned by the GetRandomNumber command
+    fn generate_random_number(&mut self) {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 17;
+        self.random ^= self.random << 5;
+    }
+}
+
+fn decode(op_code_1: u8, op_code_2: u8) -> Option<OpCode> {
+    ALL_OP_CODES.into_iter().find(|o| o.bytes() == [op_code_1, op_code_2])
+}
+
+/// A simulated Lr1110/20/21 chip, decoding the command stream sent over its SPI bus and raising IRQs on DIO9 as
+/// operations complete.  Operations complete as soon as the radio kind waits on DIO9.
+pub struct SimLR11xx {
+    state: RefCell<State>,
+}
+
+impl Default for SimLR11xx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimLR11xx {
+    /// Create a simulated chip in its power-on state
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(State::new()),
+        }
+    }
+
+    /// Get the SPI bus connected to the simulated chip
+    pub fn spi(&self) -> SimSpi<'_> {
+        SimSpi { state: &self.state }
+    }
+
+    /// Get the interface variant (NSS, reset, BUSY, DIO9, and RF switch lines) connected to the simulated chip
+    pub fn iv(&self) -> SimInterfaceVariant<'_> {
+        SimInterfaceVariant { state: &self.state }
+    }
+
+    /// Provide a packet for the next receive operation
+    pub fn receive_packet(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = false;
+    }
+
+    /// Provide a packet with an invalid CRC for the next receive operation
+    pub fn receive_packet_with_crc_error(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = true;
+    }
+
+    /// Set the signal conditions reported for received packets (dBm and dB)
+    pub fn set_packet_status(&self, rssi: i16, snr: i16, signal_rssi: i16) {
+        let mut state = self.state.borrow_mut();
+        state.packet_status = [(-rssi * 2) as u8, (snr * 4) as u8, (-signal_rssi * 2) as u8];
+    }
+
+    /// Set whether channel activity detection operations detect activity
+    pub fn set_channel_activity(&self, detected: bool) {
+        self.state.borrow_mut().channel_activity = detected;
+    }
+
+    /// Take the packet sent by the last send operation
+    pub fn take_transmitted_packet(&self) -> Option<SimPacket> {
+        self.state.borrow_mut().tx_packet.take()
+    }
+
+    /// Get the board type set through the interface variant
+    pub fn board_type(&self) -> Option<BoardType> {
+        self.state.borrow().board_type
+    }
+
+    /// Get the operating mode of the chip
+    pub fn mode(&self) -> RadioMode {
+        self.state.borrow().mode
+    }
+
+    /// Get whether the chip will retain its configuration when woken up from sleep
+    pub fn warm_start(&self) -> bool {
+        self.state.borrow().warm_start
+    }
+
+    /// Get the channel frequency set on the chip
+    pub fn frequency_in_hz(&self) -> u32 {
+        self.state.borrow().frequency_in_hz
+    }
+
+    /// Get the packet type (0x01 for GFSK, 0x02 for LoRa) set on the chip
+    pub fn packet_type(&self) -> u8 {
+        self.state.borrow().packet_type
+    }
+
+    /// Get the LoRa syncword set on the chip
+    pub fn sync_word(&self) -> u8 {
+        self.state.borrow().sync_word
+    }
+
+    /// Get the parameters of the last SetTcxoMode command
+    pub fn tcxo_mode(&self) -> [u8; 4] {
+        self.state.borrow().tcxo_mode
+    }
+
+    /// Get the blocks calibrated by the last Calibrate command
+    pub fn calibration(&self) -> u8 {
+        self.state.borrow().calibration
+    }
+
+    /// Get the regulator mode (0x00 for LDO, 0x01 for DC-DC) set on the chip
+    pub fn regulator_mode(&self) -> u8 {
+        self.state.borrow().regulator_mode
+    }
+
+    /// Get the parameters of the last SetModulationParams command
+    pub fn modulation_params(&self) -> [u8; 4] {
+        self.state.borrow().modulation_params
+    }
+
+    /// Get the parameters of the last SetPacketParams command
+    pub fn packet_params(&self) -> [u8; 6] {
+        self.state.borrow().packet_params
+    }
+
+    /// Get the frequency band parameters of the last CalibImage command
+    pub fn image_calibration(&self) -> [u8; 2] {
+        self.state.borrow().image_calibration
+    }
+
+    /// Get the parameters of the last SetPaConfig command
+    pub fn pa_config(&self) -> [u8; 4] {
+        self.state.borrow().pa_config
+    }
+
+    /// Get the power parameter of the last SetTxParams command
+    pub fn tx_power(&self) -> i8 {
+        self.state.borrow().tx_params[0] as i8
+    }
+
+    /// Get whether the receiver is set to boosted gain
+    pub fn rx_boosted(&self) -> bool {
+        self.state.borrow().rx_boosted
+    }
+}
+
+/// The SPI bus of a simulated Lr1110/20/21 chip
+pub struct SimSpi<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl ErrorType for SimSpi<'_> {
+    type Error = Infallible;
+}
+
+impl SpiBusFlush for SimSpi<'_> {
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl SpiBusRead<u8> for SimSpi<'_> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(0x00u8);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBusWrite<u8> for SimSpi<'_> {
+    async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBus<u8> for SimSpi<'_> {
+    async fn transfer<'b>(&'b mut self, read: &'b mut [u8], write: &'b [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for i in 0..core::cmp::max(read.len(), write.len()) {
+            let miso = state.exchange(write.get(i).copied().unwrap_or(0x00u8));
+            if let Some(word) = read.get_mut(i) {
+                *word = miso;
+            }
+        }
+        Ok(())
+    }
+
+    async fn transfer_in_place<'b>(&'b mut self, words: &'b mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+/// The interface variant of a simulated Lr1110/20/21 chip.  BUSY is never asserted, and waiting on DIO9 completes the
+/// pending operation; it fails with [`RadioError::Irq`] if DIO9 would never rise.
+pub struct SimInterfaceVariant<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl InterfaceVariant for SimInterfaceVariant<'_> {
+    fn set_board_type(&mut self, board_type: BoardType) {
+        self.state.borrow_mut().board_type = Some(board_type);
+    }
+
+    async fn set_nss_low(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().select();
+        Ok(())
+    }
+
+    async fn set_nss_high(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().deselect();
+        Ok(())
+    }
+
+    async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        self.state.borrow_mut().reset();
+        delay.delay_ms(10).await;
+        Ok(())
+    }
+
+    async fn wait_on_busy(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn await_irq(&mut self) -> Result<(), RadioError> {
+        let mut state = self.state.borrow_mut();
+        if !state.dio9() {
+            state.complete_operation();
+        }
+        match state.dio9() {
+            true => Ok(()),
+            false => Err(RadioError::Irq),
+        }
+    }
+
+    async fn enable_rf_switch_rx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn enable_rf_switch_tx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn disable_rf_switch(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lr11xx::LR11xx;
+    use crate::mod_traits::AsyncRng;
+    use crate::sim::{block_on, SimDelay};
+    use crate::LoRa;
+
+    fn new_lora(sim: &SimLR11xx, board_type: BoardType) -> LoRa<LR11xx<SimSpi<'_>, SimInterfaceVariant<'_>>> {
+        let radio_kind = LR11xx::new(board_type, sim.spi(), sim.iv());
+        block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap()
+    }
+
+    #[test]
+    fn init() {
+        let sim = SimLR11xx::new();
+        let _lora = new_lora(&sim, BoardType::GenericLr1110);
+        assert!(sim.board_type() == Some(BoardType::GenericLr1110));
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.packet_type(), PacketType::LoRa.value());
+        assert_eq!(sim.sync_word(), 0x34);
+        // 1.8 V TCXO, 5 ms in 30.52 us steps
+        assert_eq!(sim.tcxo_mode(), [0x02, 0x00, 0x00, 0xa3]);
+        assert_eq!(sim.calibration(), 0x3f);
+        assert_eq!(sim.regulator_mode(), 0x01);
+    }
+
+    #[test]
+    fn board_config() {
+        let sim = SimLR11xx::new();
+        let board_config = BoardConfig {
+            oscillator: Oscillator::Xtal,
+            regulator: Regulator::Ldo,
+            ..BoardConfig::from(BoardType::Custom(ChipType::Lr1110))
+        };
+        let radio_kind = LR11xx::new(board_config, sim.spi(), sim.iv());
+        let _lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert!(sim.board_type() == Some(BoardType::Custom(ChipType::Lr1110)));
+        assert_eq!(sim.tcxo_mode(), [0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(sim.regulator_mode(), 0x00);
+
+        let sim = SimLR11xx::new();
+        let board_config = BoardConfig {
+            oscillator: Oscillator::Tcxo {
+                voltage: TcxoVoltage::_3V3,
+                startup_time_in_ms: 10,
+            },
+            ..BoardConfig::from(BoardType::GenericLr1120)
+        };
+        let radio_kind = LR11xx::new(board_config, sim.spi(), sim.iv());
+        let _lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        // 3.3 V TCXO, 10 ms in 30.52 us steps
+        assert_eq!(sim.tcxo_mode(), [0x07, 0x00, 0x01, 0x47]);
+        assert_eq!(sim.calibration(), 0x3f);
+        assert_eq!(sim.regulator_mode(), 0x01);
+    }
+    #[test]
+    fn tx() {
+        let sim = SimLR11xx::new();
+        let mut lora = new_lora(&sim, BoardType::GenericLr1110);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_10, Bandwidth::_250KHz, CodingRate::_4_8, 903_900_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 20, true)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, &[0x01, 0x02, 0x03], 0xffffff)).unwrap();
+
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), &[0x01, 0x02, 0x03]);
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.frequency_in_hz(), 903_900_000);
+        assert_eq!(sim.image_calibration(), [0xe1, 0xe9]);
+        // high power PA supplied from VBAT
+        assert_eq!(sim.pa_config(), [0x01, 0x01, 0x03, 0x05]);
+        assert_eq!(sim.tx_power(), 22);
+        assert_eq!(sim.modulation_params(), [0x0a, 0x05, 0x04, 0x00]);
+        assert_eq!(sim.packet_params(), [0x00, 0x08, 0x00, 0x03, 0x01, 0x00]);
+
+        // low power PA
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(sim.pa_config(), [0x00, 0x00, 0x04, 0x00]);
+        assert_eq!(sim.tx_power(), 14);
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, 20, false)).err(),
+            Some(RadioError::InvalidOutputPower)
+        );
+    }
+
+    #[test]
+    fn rx() {
+        let sim = SimLR11xx::new();
+        let mut lora = new_lora(&sim, BoardType::GenericLr1110);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, true, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        sim.receive_packet(b"hello");
+        sim.set_packet_status(-80, 7, -81);
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, true, 0, 1000)).unwrap();
+        assert!(sim.rx_boosted());
+        let (len, pkt_status) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"hello");
+        assert_eq!(pkt_status.rssi, -80);
+        assert_eq!(pkt_status.snr, 7);
+        assert_eq!(pkt_status.signal_rssi, Some(-81));
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // nothing received before the timeout
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 0, 1000)).unwrap();
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::ReceiveTimeout)
+        );
+
+        // in continuous mode, the radio keeps receiving packets
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        sim.receive_packet(b"one");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 3);
+        sim.receive_packet(b"three");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 5);
+        assert!(sim.mode() == RadioMode::Receive);
+    }
+
+    #[test]
+    fn cad() {
+        let sim = SimLR11xx::new();
+        let mut lora = new_lora(&sim, BoardType::GenericLr1110);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+
+        let cad_params = CadParams::recommended_for(SpreadingFactor::_9);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(sim.mode() == RadioMode::ChannelActivityDetection);
+        assert!(!block_on(lora.cad()).unwrap());
+
+        sim.set_channel_activity(true);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(block_on(lora.cad()).unwrap());
+    }
+
+    #[test]
+    fn sleep_and_warm_start() {
+        let sim = SimLR11xx::new();
+        let mut lora = new_lora(&sim, BoardType::GenericLr1110);
+        let mut delay = SimDelay::default();
+        block_on(lora.sleep(&mut delay)).unwrap();
+        assert!(sim.mode() == RadioMode::Sleep);
+        assert!(sim.warm_start());
+        assert_eq!(delay.elapsed_in_us, 2000);
+
+        // waking up for the next operation retains the configuration
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.sync_word(), 0x34);
+        assert_eq!(sim.packet_type(), PacketType::LoRa.value());
+    }
+
+    #[test]
+    fn rng() {
+        let sim = SimLR11xx::new();
+        let mut lora = new_lora(&sim, BoardType::GenericLr1110);
+        let first = block_on(lora.get_random_number()).unwrap();
+        let second = block_on(lora.get_random_number()).unwrap();
+        assert_ne!(first, second);
+        assert!(sim.mode() == RadioMode::Standby);
+    }
+
+    #[test]
+    fn lr1120_2_4_ghz() {
+        let sim = SimLR11xx::new();
+        let mut lora = new_lora(&sim, BoardType::GenericLr1120);
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_62KHz, CodingRate::_4_5, 2_450_000_000)
+                .err(),
+            Some(RadioError::InvalidBandwidthForFrequency)
+        );
+        assert_eq!(
+            lora.create_fsk_modulation_params(50_000, 25_000, PulseShape::GaussianBt1_0, 100_000, 868_800_000)
+                .err(),
+            Some(RadioError::FskUnsupported)
+        );
+        // the Sx128x compatible bandwidths are only available in the 2.4 GHz band
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_812KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::InvalidBandwidthForFrequency)
+        );
+        let mdltn_params = lora
+            .create_modulation_params(
+                SpreadingFactor::_7,
+                Bandwidth::_812KHz,
+                CodingRate::_4_8Li,
+                2_450_000_000,
+            )
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 13, false)).unwrap();
+        assert_eq!(sim.modulation_params(), [0x07, 0x0f, 0x07, 0x00]);
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_500KHz, CodingRate::_4_5, 2_450_000_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 13, false)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"2.4", 0)).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"2.4");
+        assert_eq!(sim.frequency_in_hz(), 2_450_000_000);
+        // no image calibration outside of the sub-GHz band, and the high frequency PA
+        assert_eq!(sim.image_calibration(), [0x00, 0x00]);
+        assert_eq!(sim.pa_config(), [0x02, 0x00, 0x00, 0x00]);
+        assert_eq!(sim.tx_power(), 13);
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).err(),
+            Some(RadioError::InvalidOutputPowerForFrequency)
+        );
+
+        // the Lr1110 supports the sub-GHz band only
+        let mut lora = new_lora(&sim, BoardType::GenericLr1110);
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_500KHz, CodingRate::_4_5, 2_450_000_000)
+                .err(),
+            Some(RadioError::InvalidFrequency)
+        );
+    }
+}
@@ -0,0 +1,86 @@
+/// Simulation of the Semtech Lr1110/20/21 chips
+pub mod lr11xx;
+/// Simulation of the Semtech Sx1261/2 chips
+pub mod sx1261_2;
+/// Simulation of the Semtech Sx1276/7/8/9 chips
+pub mod sx1276_7_8_9;
+/// Simulation of the Semtech Sx1280/1 chips
+pub mod sx1280;
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use embedded_hal_async::delay::DelayUs;
+
+// Size of the data buffer (Sx126x) or FIFO (Sx127x) of the simulated chips
+pub(crate) const BUFFER_SIZE: usize = 256;
+
+// Number of polls after which a future is considered to never complete
+const MAX_POLLS: u32 = 1_000_000;
+
+/// A delay which completes immediately, accumulating the requested time so tests can verify it
+#[derive(Default)]
+pub struct SimDelay {
+    /// Total time requested across all delays (microseconds)
+    pub elapsed_in_us: u64,
+}
+
+impl DelayUs for SimDelay {
+    async fn delay_us(&mut self, us: u32) {
+        self.elapsed_in_us += us as u64;
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        self.elapsed_in_us += (ms as u64) * 1000;
+    }
+}
+
+/// A packet sent or received by a simulated chip
+#[derive(Clone, Copy)]
+pub struct SimPacket {
+    pub(crate) data: [u8; BUFFER_SIZE],
+    pub(crate) len: usize,
+}
+
+impl SimPacket {
+    /// Create a packet from a payload of at most 255 bytes
+    pub fn new(payload: &[u8]) -> Self {
+        let len = core::cmp::min(payload.len(), BUFFER_SIZE - 1);
+        let mut data = [0x00u8; BUFFER_SIZE];
+        data[..len].copy_from_slice(&payload[..len]);
+        Self { data, len }
+    }
+
+    /// The packet payload
+    pub fn payload(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+/// Run a future to completion on the current thread.  The simulations never wait on real hardware, so this
+/// panics rather than hangs if the future stays pending.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = future;
+    // Safety: the future is shadowed, so it is never moved again after being pinned
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+    let waker = noop_waker();
+    let mut context = Context::from_waker(&waker);
+    for _ in 0..MAX_POLLS {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+    panic!("simulated operation did not complete");
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    // Safety: the vtable functions ignore the data pointer
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+}
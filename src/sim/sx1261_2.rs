@@ -0,0 +1,1573 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::spi::{ErrorType, SpiBus, SpiBusFlush, SpiBusRead, SpiBusWrite};
+
+use crate::mod_params::*;
+use crate::mod_traits::InterfaceVariant;
+use crate::sim::{SimPacket, BUFFER_SIZE};
+use crate::sx1261_2::radio_kind_params::{IrqMask, OpCode, PacketType, Register};
+
+const REGISTER_SPACE_SIZE: usize = 0x1000;
+const MAX_COMMAND_SIZE: usize = 2 + BUFFER_SIZE;
+
+// Internal frequency of the radio and the PLL step shift used to convert PLL steps to a frequency
+const SX126X_XTAL_FREQ: u64 = 32_000_000;
+const SX126X_PLL_STEP_SHIFT: u32 = 25;
+
+// Timeout value requesting continuous receive
+const RX_CONTINUOUS_TIMEOUT: u32 = 0x00ff_ffff;
+
+const ALL_OP_CODES: [OpCode; 41] = [
+    OpCode::GetStatus,
+    OpCode::WriteRegister,
+    OpCode::ReadRegister,
+    OpCode::WriteBuffer,
+    OpCode::ReadBuffer,
+    OpCode::SetSleep,
+    OpCode::SetStandby,
+    OpCode::SetFS,
+    OpCode::SetTx,
+    OpCode::SetRx,
+    OpCode::SetRxDutyCycle,
+    OpCode::SetCAD,
+    OpCode::SetTxContinuousWave,
+    OpCode::SetTxContinuousPremable,
+    OpCode::SetPacketType,
+    OpCode::GetPacketType,
+    OpCode::SetRFFrequency,
+    OpCode::SetTxParams,
+    OpCode::SetPAConfig,
+    OpCode::SetCADParams,
+    OpCode::SetBufferBaseAddress,
+    OpCode::SetModulationParams,
+    OpCode::SetPacketParams,
+    OpCode::GetRxBufferStatus,
+    OpCode::GetPacketStatus,
+    OpCode::GetRSSIInst,
+    OpCode::GetStats,
+    OpCode::ResetStats,
+    OpCode::CfgDIOIrq,
+    OpCode::GetIrqStatus,
+    OpCode::ClrIrqStatus,
+    OpCode::Calibrate,
+    OpCode::CalibrateImage,
+    OpCode::SetRegulatorMode,
+    OpCode::GetErrors,
+    OpCode::ClrErrors,
+    OpCode::SetTCXOMode,
+    OpCode::SetTxFallbackMode,
+    OpCode::SetRFSwitchMode,
+    OpCode::SetStopRxTimerOnPreamble,
+    OpCode::SetLoRaSymbTimeout,
+];
+
+// An operation started by a command, completed when the simulated chip raises the corresponding IRQ
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    None,
+    Tx,
+    Rx { timeout: u32 },
+    Cad,
+    ContinuousWave,
+    ContinuousPreamble,
+}
+
+struct State {
+    board_type: Option<BoardType>,
+    mode: RadioMode,
+    warm_start: bool,
+    nss_low: bool,
+    command: [u8; MAX_COMMAND_SIZE],
+    command_len: usize,
+    registers: [u8; REGISTER_SPACE_SIZE],
+    buffer: [u8; BUFFER_SIZE],
+    tx_base_addr: u8,
+    rx_base_addr: u8,
+    rx_payload_len: u8,
+    rx_start_addr: u8,
+    packet_type: u8,
+    pll_steps: u32,
+    tx_power: u8,
+    pa_config: [u8; 4],
+    tcxo_mode: Option<[u8; 4]>,
+    regulator_mode: u8,
+    rf_switch: bool,
+    image_calibration: [u8; 2],
+    modulation_params: [u8; 8],
+    packet_params: [u8; 9],
+    cad_params: [u8; 7],
+    irq_mask: u16,
+    dio1_mask: u16,
+    irq_status: u16,
+    operation: Operation,
+    rx_packet: Option<SimPacket>,
+    rx_crc_error: bool,
+    tx_packet: Option<SimPacket>,
+    channel_activity: bool,
+    packet_status: [u8; 3],
+    stats: [u16; 3],
+    device_errors: u16,
+    persistent_device_errors: u16, // raised again by each reset and calibration
+    calibration: u8,
+    random: u32,
+}
+
+impl State {
+    fn new() -> Self {
+        let mut state = Self {
+            board_type: None,
+            mode: RadioMode::Standby,
+            warm_start: false,
+            nss_low: false,
+            command: [0x00u8; MAX_COMMAND_SIZE],
+            command_len: 0,
+            registers: [0x00u8; REGISTER_SPACE_SIZE],
+            buffer: [0x00u8; BUFFER_SIZE],
+            tx_base_addr: 0,
+            rx_base_addr: 0,
+            rx_payload_len: 0,
+            rx_start_addr: 0,
+            packet_type: PacketType::GFSK.value(),
+            pll_steps: 0,
+            tx_power: 0,
+            pa_config: [0x00u8; 4],
+            tcxo_mode: None,
+            regulator_mode: 0,
+            rf_switch: false,
+            image_calibration: [0x00u8; 2],
+            modulation_params: [0x00u8; 8],
+            packet_params: [0x00u8; 9],
+            cad_params: [0x00u8; 7],
+            irq_mask: IrqMask::None.value(),
+            dio1_mask: IrqMask::None.value(),
+            irq_status: IrqMask::None.value(),
+            operation: Operation::None,
+            rx_packet: None,
+            rx_crc_error: false,
+            tx_packet: None,
+            channel_activity: false,
+            packet_status: [0x00u8; 3],
+            stats: [0u16; 3],
+            device_errors: 0,
+            persistent_device_errors: 0,
+            calibration: 0,
+            random: 0x2545_f491,
+        };
+        state.reset();
+        state
+    }
+
+    // Restore the power-on configuration, keeping the packets and channel conditions supplied by a test
+    fn reset(&mut self) {
+        self.mode = RadioMode::Standby;
+        self.warm_start = false;
+        self.command_len = 0;
+        self.registers = [0x00u8; REGISTER_SPACE_SIZE];
+        self.write_registers(Register::LoRaSyncword as u16, &[0x14, 0x24]);
+        self.write_registers(Register::RxGain as u16, &[0x94]);
+        self.write_registers(Register::IQPolarity as u16, &[0x0d]);
+        self.write_registers(Register::TxClampCfg as u16, &[0xc8]);
+        self.buffer = [0x00u8; BUFFER_SIZE];
+        self.tx_base_addr = 0;
+        self.rx_base_addr = 0;
+        self.rx_payload_len = 0;
+        self.rx_start_addr = 0;
+        self.packet_type = PacketType::GFSK.value();
+        self.pll_steps = 0;
+        self.tx_power = 0;
+        self.pa_config = [0x00u8; 4];
+        self.tcxo_mode = None;
+        self.regulator_mode = 0;
+        self.rf_switch = false;
+        self.image_calibration = [0x00u8; 2];
+        self.modulation_params = [0x00u8; 8];
+        self.packet_params = [0x00u8; 9];
+        self.cad_params = [0x00u8; 7];
+        self.irq_mask = IrqMask::None.value();
+        self.dio1_mask = IrqMask::None.value();
+        self.irq_status = IrqMask::None.value();
+        self.operation = Operation::None;
+        self.device_errors = self.persistent_device_errors;
+    }
+
+    fn write_registers(&mut self, address: u16, data: &[u8]) {
+        for (i, byte) in data.iter().enumerate() {
+            self.registers[(address as usize + i) % REGISTER_SPACE_SIZE] = *byte;
+        }
+    }
+
+    fn register(&self, address: usize) -> u8 {
+        self.registers[address % REGISTER_SPACE_SIZE]
+    }
+
+    fn status(&self) -> u8 {
+        let chip_mode = match self.mode {
+            RadioMode::Sleep | RadioMode::Standby => 0x02,
+            RadioMode::FrequencySynthesis => 0x04,
+            RadioMode::Transmit | RadioMode::ContinuousTransmit => 0x06,
+            RadioMode::Receive | RadioMode::ReceiveDutyCycle | RadioMode::ChannelActivityDetection => 0x05,
+        };
+        chip_mode << 4
+    }
+
+    fn select(&mut self) {
+        if self.mode == RadioMode::Sleep {
+            // a falling edge on NSS wakes the chip up
+            if !self.warm_start {
+                self.reset();
+            }
+            self.mode = RadioMode::Standby;
+        }
+        self.nss_low = true;
+        self.command_len = 0;
+    }
+
+    fn deselect(&mut self) {
+        if self.nss_low {
+            self.nss_low = false;
+            self.execute();
+        }
+    }
+
+    // Shift one byte into the chip, returning the byte shifted out
+    fn exchange(&mut self, mosi: u8) -> u8 {
+        let index = self.command_len;
+        if index < MAX_COMMAND_SIZE {
+            self.command[index] = mosi;
+        }
+        self.command_len += 1;
+        if index == 0 {
+            return self.status();
+        }
+
+        match decode(self.command[0]) {
+            Some(OpCode::ReadRegister) => match index {
+                1 | 2 => 0x00,
+                3 => self.status(),
+                _ => {
+                    let address = ((self.command[1] as usize) << 8) | (self.command[2] as usize);
+                    self.register(address + index - 4)
+                }
+            },
+            Some(OpCode::ReadBuffer) => match index {
+                1 => 0x00,
+                2 => self.status(),
+                _ => self.buffer[(self.command[1] as usize + index - 3) % BUFFER_SIZE],
+            },
+            Some(op_code) if index >= 2 => self.response(op_code).get(index - 2).copied().unwrap_or(0x00),
+            _ => self.status(),
+        }
+    }
+
+    // Data returned by a get command, following the status byte
+    fn response(&self, op_code: OpCode) -> [u8; 6] {
+        match op_code {
+            OpCode::GetIrqStatus => [(self.irq_status >> 8) as u8, self.irq_status as u8, 0, 0, 0, 0],
+            OpCode::GetRxBufferStatus => [self.rx_payload_len, self.rx_start_addr, 0, 0, 0, 0],
+            // LoRa: RssiPkt, SnrPkt, SignalRssiPkt; GFSK: RxStatus, RssiSync, RssiAvg
+            OpCode::GetPacketStatus if self.packet_type == PacketType::GFSK.value() => {
+                [0x00, self.packet_status[0], self.packet_status[0], 0, 0, 0]
+            }
+            OpCode::GetPacketStatus => [
+                self.packet_status[0],
+                self.packet_status[1],
+                self.packet_status[2],
+                0,
+                0,
+                0,
+            ],
+            OpCode::GetRSSIInst => [self.packet_status[0], 0, 0, 0, 0, 0],
+            OpCode::GetPacketType => [self.packet_type, 0, 0, 0, 0, 0],
+            OpCode::GetErrors => [(self.device_errors >> 8) as u8, self.device_errors as u8, 0, 0, 0, 0],
+            OpCode::GetStats => [
+                (self.stats[0] >> 8) as u8,
+                self.stats[0] as u8,
+                (self.stats[1] >> 8) as u8,
+                self.stats[1] as u8,
+                (self.stats[2] >> 8) as u8,
+                self.stats[2] as u8,
+            ],
+            _ => [0x00u8; 6],
+        }
+    }
+
+    // Execute a command once NSS is released
+    fn execute(&mut self) {
+        let len = core::cmp::min(self.command_len, MAX_COMMAND_SIZE);
+        if len == 0 {
+            return;
+        }
+        let command = self.command;
+        let params = &command[1..len];
+        let param = |i: usize| params.get(i).copied().unwrap_or(0x00);
+        let timeout = ((param(0) as u32) << 16) | ((param(1) as u32) << 8) | (param(2) as u32);
+
+        match decode(command[0]) {
+            Some(OpCode::WriteRegister) if params.len() > 2 => {
+                let address = ((param(0) as u16) << 8) | (param(1) as u16);
+                self.write_registers(address, &params[2..]);
+            }
+            Some(OpCode::WriteBuffer) if !params.is_empty() => {
+                for (i, byte) in params[1..].iter().enumerate() {
+                    self.buffer[(param(0) as usize + i) % BUFFER_SIZE] = *byte;
+                }
+            }
+            Some(OpCode::SetSleep) => {
+                self.warm_start = (param(0) & 0x04) != 0;
+                self.mode = RadioMode::Sleep;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetStandby) => {
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetFS) => self.mode = RadioMode::FrequencySynthesis,
+            Some(OpCode::SetTx) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::Tx;
+            }
+            Some(OpCode::SetRx) => {
+                self.mode = RadioMode::Receive;
+                self.operation = Operation::Rx { timeout };
+                self.generate_random_number();
+            }
+            Some(OpCode::SetRxDutyCycle) => {
+                self.mode = RadioMode::ReceiveDutyCycle;
+                self.operation = Operation::Rx { timeout: 0 };
+            }
+            Some(OpCode::SetCAD) => {
+                self.mode = RadioMode::ChannelActivityDetection;
+                self.operation = Operation::Cad;
+            }
+            Some(OpCode::SetTxContinuousWave) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::ContinuousWave;
+            }
+            Some(OpCode::SetTxContinuousPremable) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::ContinuousPreamble;
+            }
+            Some(OpCode::SetPacketType) => self.packet_type = param(0),
+            Some(OpCode::SetRFFrequency) => {
+                self.pll_steps = u32::from_be_bytes([param(0), param(1), param(2), param(3)]);
+            }
+            Some(OpCode::SetTxParams) => self.tx_power = param(0),
+            Some(OpCode::SetPAConfig) => self.pa_config = [param(0), param(1), param(2), param(3)],
+            Some(OpCode::SetTCXOMode) => self.tcxo_mode = Some([param(0), param(1), param(2), param(3)]),
+            Some(OpCode::SetRegulatorMode) => self.regulator_mode = param(0),
+            Some(OpCode::SetRFSwitchMode) => self.rf_switch = param(0) != 0,
+            Some(OpCode::Calibrate) => {
+                self.calibration = param(0);
+                self.device_errors |= self.persistent_device_errors;
+            }
+            Some(OpCode::CalibrateImage) => self.image_calibration = [param(0), param(1)],
+            Some(OpCode::ClrErrors) => self.device_errors = 0,
+            Some(OpCode::SetBufferBaseAddress) => {
+                self.tx_base_addr = param(0);
+                self.rx_base_addr = param(1);
+            }
+            Some(OpCode::SetModulationParams) => {
+                for (i, byte) in self.modulation_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+            }
+            Some(OpCode::SetPacketParams) => {
+                for (i, byte) in self.packet_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+                if self.packet_type == PacketType::LoRa.value() {
+                    self.write_registers(Register::PayloadLength as u16, &[param(3)]);
+                }
+            }
+            Some(OpCode::SetCADParams) => {
+                for (i, byte) in self.cad_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+            }
+            Some(OpCode::CfgDIOIrq) => {
+                self.irq_mask = ((param(0) as u16) << 8) | (param(1) as u16);
+                self.dio1_mask = ((param(2) as u16) << 8) | (param(3) as u16);
+            }
+            Some(OpCode::ClrIrqStatus) => {
+                self.irq_status &= !(((param(0) as u16) << 8) | (param(1) as u16));
+            }
+            Some(OpCode::ResetStats) => self.stats = [0u16; 3],
+            _ => {}
+        }
+    }
+
+    // Payload length set by the last SetPacketParams command for the current packet type
+    fn payload_length(&self) -> usize {
+        match self.packet_type == PacketType::GFSK.value() {
+            true => self.packet_params[6] as usize,
+            false => self.packet_params[3] as usize,
+        }
+    }
+
+    fn raise_irq(&mut self, irq: u16) {
+        self.irq_status |= irq & self.irq_mask;
+    }
+
+    fn dio1(&self) -> bool {
+        (self.irq_status & self.dio1_mask) != 0
+    }
+
+    // Complete the pending operation, as the chip would once the corresponding on-air event occurs
+    fn complete_operation(&mut self) {
+        match self.operation {
+            Operation::None | Operation::ContinuousWave | Operation::ContinuousPreamble => {}
+            Operation::Tx => {
+                let payload_len = self.payload_length();
+                let mut payload = [0x00u8; BUFFER_SIZE];
+                for (i, byte) in payload.iter_mut().take(payload_len).enumerate() {
+                    *byte = self.buffer[(self.tx_base_addr as usize + i) % BUFFER_SIZE];
+                }
+                self.tx_packet = Some(SimPacket::new(&payload[..payload_len]));
+                self.raise_irq(IrqMask::TxDone.value());
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+            Operation::Rx { timeout } => {
+                let continuous = timeout == RX_CONTINUOUS_TIMEOUT;
+                if let Some(packet) = self.rx_packet.take() {
+                    let gfsk = self.packet_type == PacketType::GFSK.value();
+                    // implicit LoRa header or fixed GFSK packet length
+                    let implicit_header = match gfsk {
+                        true => self.packet_params[5] == 0,
+                        false => self.packet_params[2] != 0,
+                    };
+                    let payload_len = match implicit_header {
+                        true => self.payload_length(),
+                        false => packet.len,
+                    };
+                    for (i, byte) in packet.data.iter().take(payload_len).enumerate() {
+                        self.buffer[(self.rx_base_addr as usize + i) % BUFFER_SIZE] = *byte;
+                    }
+                    self.rx_payload_len = payload_len as u8;
+                    self.rx_start_addr = self.rx_base_addr;
+                    self.stats[0] = self.stats[0].wrapping_add(1);
+
+                    let mut irq = IrqMask::PreambleDetected.value() | IrqMask::RxDone.value();
+                    if gfsk {
+                        irq |= IrqMask::SyncwordValid.value();
+                    } else if !implicit_header {
+                        irq |= IrqMask::HeaderValid.value();
+                    }
+                    if self.rx_crc_error {
+                        irq |= IrqMask::CRCError.value();
+                        self.stats[1] = self.stats[1].wrapping_add(1);
+                    }
+                    self.raise_irq(irq);
+                } else if timeout != 0 && !continuous {
+                    self.raise_irq(IrqMask::RxTxTimeout.value());
+                } else {
+                    return;
+                }
+                if !continuous {
+                    self.mode = RadioMode::Standby;
+                    self.operation = Operation::None;
+                }
+            }
+            Operation::Cad => {
+                let mut irq = IrqMask::CADDone.value();
+                if self.channel_activity {
+                    irq |= IrqMask::CADActivityDetected.value();
+                }
+                self.raise_irq(irq);
+
+                // continue with the operation chained by the CAD exit mode
+                let timeout = ((self.cad_params[4] as u32) << 16)
+                    | ((self.cad_params[5] as u32) << 8)
+                    | (self.cad_params[6] as u32);
+                (self.mode, self.operation) = match (self.cad_params[3], self.channel_activity) {
+                    (0x01, true) => (RadioMode::Receive, Operation::Rx { timeout }),
+                    (0x10, false) => (RadioMode::Transmit, Operation::Tx),
+                    _ => (RadioMode::Standby, Operation::None),
+                };
+            }
+        }
+    }
+
+    // xorshift32, providing the value read from the random number register while receiving
+    fn generate_random_number(&mut self) {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 17;
+        self.random ^= self.random << 5;
+        self.write_registers(Register::GeneratedRandomNumber as u16, &self.random.to_be_bytes());
+    }
+}
+
+fn decode(op_code: u8) -> Option<OpCode> {
+    ALL_OP_CODES.into_iter().find(|o| o.value() == op_code)
+}
+
+/// A simulated Sx1261/2 chip, decoding the command stream sent over its SPI bus and raising IRQs on DIO1 as
+/// operations complete.  Operations complete as soon as the radio kind waits on DIO1.
+pub struct SimSX1261_2 {
+    state: RefCell<State>,
+}
+
+impl Default for SimSX1261_2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimSX1261_2 {
+    /// Create a simulated chip in its power-on state
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(State::new()),
+        }
+    }
+
+    /// Get the SPI bus connected to the simulated chip
+    pub fn spi(&self) -> SimSpi<'_> {
+        SimSpi { state: &self.state }
+    }
+
+    /// Get the interface variant (NSS, reset, BUSY, DIO1, and RF switch lines) connected to the simulated chip
+    pub fn iv(&self) -> SimInterfaceVariant<'_> {
+        SimInterfaceVariant { state: &self.state }
+    }
+
+    /// Provide a packet for the next receive operation
+    pub fn receive_packet(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = false;
+    }
+
+    /// Provide a packet with an invalid CRC for the next receive operation
+    pub fn receive_packet_with_crc_error(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = true;
+    }
+
+    /// Set the signal conditions reported for received packets (dBm and dB).  GFSK packets report the RSSI only.
+    pub fn set_packet_status(&self, rssi: i16, snr: i16, signal_rssi: i16) {
+        let mut state = self.state.borrow_mut();
+        state.packet_status = [(-rssi * 2) as u8, (snr * 4) as u8, (-signal_rssi * 2) as u8];
+    }
+
+    /// Set whether channel activity detection operations detect activity
+    pub fn set_channel_activity(&self, detected: bool) {
+        self.state.borrow_mut().channel_activity = detected;
+    }
+
+    /// Raise device errors (OpError bits), which are raised again by each reset and calibration if persistent
+    pub fn set_device_errors(&self, device_errors: u16, persistent: bool) {
+        let mut state = self.state.borrow_mut();
+        state.device_errors = device_errors;
+        state.persistent_device_errors = if persistent { device_errors } else { 0 };
+    }
+
+    /// Take the packet sent by the last send operation
+    pub fn take_transmitted_packet(&self) -> Option<SimPacket> {
+        self.state.borrow_mut().tx_packet.take()
+    }
+
+    /// Get the board type set through the interface variant
+    pub fn board_type(&self) -> Option<BoardType> {
+        self.state.borrow().board_type
+    }
+
+    /// Get the operating mode of the chip
+    pub fn mode(&self) -> RadioMode {
+        self.state.borrow().mode
+    }
+
+    /// Get whether the chip is transmitting an unmodulated carrier
+    pub fn continuous_wave(&self) -> bool {
+        self.state.borrow().operation == Operation::ContinuousWave
+    }
+
+    /// Get whether the chip is transmitting an endless preamble
+    pub fn continuous_preamble(&self) -> bool {
+        self.state.borrow().operation == Operation::ContinuousPreamble
+    }
+
+    /// Get whether the chip will retain its configuration when woken up from sleep
+    pub fn warm_start(&self) -> bool {
+        self.state.borrow().warm_start
+    }
+
+    /// Get the channel frequency set on the chip, rounded to the nearest Hz
+    pub fn frequency_in_hz(&self) -> u32 {
+        let frequency = self.state.borrow().pll_steps as u64 * SX126X_XTAL_FREQ;
+        ((frequency + (1 << (SX126X_PLL_STEP_SHIFT - 1))) >> SX126X_PLL_STEP_SHIFT) as u32
+    }
+
+    /// Get the packet type (0x00 for GFSK, 0x01 for LoRa) set on the chip
+    pub fn packet_type(&self) -> u8 {
+        self.state.borrow().packet_type
+    }
+
+    /// Get the parameters of the last SetModulationParams command
+    pub fn modulation_params(&self) -> [u8; 8] {
+        self.state.borrow().modulation_params
+    }
+
+    /// Get the parameters of the last SetPacketParams command
+    pub fn packet_params(&self) -> [u8; 9] {
+        self.state.borrow().packet_params
+    }
+
+    /// Get the parameters of the last SetCADParams command
+    pub fn cad_params(&self) -> [u8; 7] {
+        self.state.borrow().cad_params
+    }
+
+    /// Get the frequency band parameters of the last CalibrateImage command
+    pub fn image_calibration(&self) -> [u8; 2] {
+        self.state.borrow().image_calibration
+    }
+
+    /// Get the blocks calibrated by the last Calibrate command
+    pub fn calibration(&self) -> u8 {
+        self.state.borrow().calibration
+    }
+
+    /// Get the power parameter of the last SetTxParams command
+    pub fn tx_power(&self) -> i8 {
+        self.state.borrow().tx_power as i8
+    }
+
+    /// Get the parameters of the last SetPaConfig command
+    pub fn pa_config(&self) -> [u8; 4] {
+        self.state.borrow().pa_config
+    }
+
+    /// Get the parameters of the last SetDIO3AsTCXOCtrl command, if the chip was told to power a TCXO
+    pub fn tcxo_mode(&self) -> Option<[u8; 4]> {
+        self.state.borrow().tcxo_mode
+    }
+
+    /// Get the regulator mode (0x00 for LDO, 0x01 for DC-DC) set on the chip
+    pub fn regulator_mode(&self) -> u8 {
+        self.state.borrow().regulator_mode
+    }
+
+    /// Get whether DIO2 controls the RF switch
+    pub fn rf_switch(&self) -> bool {
+        self.state.borrow().rf_switch
+    }
+
+    /// Get the value of a register
+    pub fn register(&self, address: u16) -> u8 {
+        self.state.borrow().register(address as usize)
+    }
+}
+
+/// The SPI bus of a simulated Sx1261/2 chip
+pub struct SimSpi<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl ErrorType for SimSpi<'_> {
+    type Error = Infallible;
+}
+
+impl SpiBusFlush for SimSpi<'_> {
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl SpiBusRead<u8> for SimSpi<'_> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(0x00u8);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBusWrite<u8> for SimSpi<'_> {
+    async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBus<u8> for SimSpi<'_> {
+    async fn transfer<'b>(&'b mut self, read: &'b mut [u8], write: &'b [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for i in 0..core::cmp::max(read.len(), write.len()) {
+            let miso = state.exchange(write.get(i).copied().unwrap_or(0x00u8));
+            if let Some(word) = read.get_mut(i) {
+                *word = miso;
+            }
+        }
+        Ok(())
+    }
+
+    async fn transfer_in_place<'b>(&'b mut self, words: &'b mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+/// The interface variant of a simulated Sx1261/2 chip.  BUSY is never asserted, and waiting on DIO1 completes the
+/// pending operation; it fails with [`RadioError::Irq`] if DIO1 would never rise.
+pub struct SimInterfaceVariant<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl InterfaceVariant for SimInterfaceVariant<'_> {
+    fn set_board_type(&mut self, board_type: BoardType) {
+        self.state.borrow_mut().board_type = Some(board_type);
+    }
+
+    async fn set_nss_low(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().select();
+        Ok(())
+    }
+
+    async fn set_nss_high(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().deselect();
+        Ok(())
+    }
+
+    async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        self.state.borrow_mut().reset();
+        delay.delay_ms(10).await;
+        Ok(())
+    }
+
+    async fn wait_on_busy(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn await_irq(&mut self) -> Result<(), RadioError> {
+        let mut state = self.state.borrow_mut();
+        if !state.dio1() {
+            state.complete_operation();
+        }
+        match state.dio1() {
+            true => Ok(()),
+            false => Err(RadioError::Irq),
+        }
+    }
+
+    async fn enable_rf_switch_rx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn enable_rf_switch_tx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn disable_rf_switch(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duty_cycle::{DutyCycleEnforcer, SubBand};
+    use crate::mod_traits::{AsyncRng, Clock};
+    use crate::sim::{block_on, SimDelay};
+    use crate::sx1261_2::SX1261_2;
+    use crate::LoRa;
+
+    fn new_lora(sim: &SimSX1261_2) -> LoRa<SX1261_2<SimSpi<'_>, SimInterfaceVariant<'_>>> {
+        let radio_kind = SX1261_2::new(BoardType::Rak4631Sx1262, sim.spi(), sim.iv());
+        block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap()
+    }
+
+    #[test]
+    fn init() {
+        let sim = SimSX1261_2::new();
+        let _lora = new_lora(&sim);
+        assert!(sim.board_type() == Some(BoardType::Rak4631Sx1262));
+        assert!(sim.mode() == RadioMode::Standby);
+        // 1.7 V TCXO allowed 10 ms to start, DC-DC regulator, and RF switch on DIO2
+        assert_eq!(sim.tcxo_mode(), Some([0x01, 0x00, 0x02, 0x80]));
+        assert_eq!(sim.regulator_mode(), 0x01);
+        assert!(sim.rf_switch());
+        assert_eq!(sim.packet_type(), PacketType::LoRa.value());
+        assert_eq!(sim.register(Register::LoRaSyncword as u16), 0x34);
+        assert_eq!(sim.register(Register::LoRaSyncword as u16 + 1), 0x44);
+        // RxGain and TxModulation in the retention list
+        assert_eq!(sim.register(Register::RetentionList as u16), 2);
+    }
+
+    #[test]
+    fn device_errors() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        assert!(!block_on(lora.device_errors()).unwrap().any());
+
+        // a transient failure of the TCXO to start, recovered by clearing the errors and calibrating all blocks
+        sim.set_device_errors(0x0020, false);
+        assert_eq!(
+            block_on(lora.device_errors()).unwrap(),
+            DeviceErrors {
+                xosc_start: true,
+                ..Default::default()
+            }
+        );
+        block_on(lora.recover_from_device_errors()).unwrap();
+        assert_eq!(sim.calibration(), 0x7f);
+        assert!(!block_on(lora.device_errors()).unwrap().any());
+
+        // a PLL lock and PA ramp failure persisting after recovery
+        sim.set_device_errors(0x0140, true);
+        assert_eq!(
+            block_on(lora.recover_from_device_errors()).err(),
+            Some(RadioError::DeviceError(DeviceErrors {
+                pll_lock: true,
+                pa_ramp: true,
+                ..Default::default()
+            }))
+        );
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // transient errors are cleared by the reset done by init, which fails if errors persist after recovery
+        sim.set_device_errors(0x0001, false);
+        block_on(lora.init(true, &mut SimDelay::default())).unwrap();
+        sim.set_device_errors(0x0004, true);
+        assert_eq!(
+            block_on(lora.init(true, &mut SimDelay::default())).err(),
+            Some(RadioError::DeviceError(DeviceErrors {
+                pll_calibration: true,
+                ..Default::default()
+            }))
+        );
+    }
+    #[test]
+    fn tx() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_10, Bandwidth::_250KHz, CodingRate::_4_8, 903_900_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 20, false)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, &[0x01, 0x02, 0x03], 0xffffff)).unwrap();
+
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), &[0x01, 0x02, 0x03]);
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.frequency_in_hz(), 903_900_000);
+        assert_eq!(sim.tx_power(), 22);
+        assert_eq!(sim.modulation_params()[..4], [0x0a, 0x05, 0x04, 0x00]);
+        assert_eq!(sim.packet_params()[..6], [0x00, 0x08, 0x00, 0x03, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn start_tx() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        assert_eq!(block_on(lora.tx_complete()).err(), Some(RadioError::InvalidRadioMode));
+
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        block_on(lora.start_tx(&mdltn_params, &mut tx_pkt_params, b"ping", 0xffffff)).unwrap();
+        assert!(sim.mode() == RadioMode::Transmit);
+        assert!(sim.take_transmitted_packet().is_none());
+
+        // the pending send operation is not discarded by a receive operation
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut buffer = [0u8; 255];
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut buffer)).err(),
+            Some(RadioError::InvalidRadioMode)
+        );
+
+        block_on(lora.tx_complete()).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"ping");
+        assert!(sim.mode() == RadioMode::Standby);
+        // the operation is only completed once
+        assert_eq!(block_on(lora.tx_complete()).err(), Some(RadioError::InvalidRadioMode));
+    }
+
+    #[test]
+    fn continuous_tx() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 868_300_000)
+            .unwrap();
+
+        block_on(lora.start_continuous_wave(868_100_000, 14)).unwrap();
+        assert!(sim.continuous_wave());
+        assert!(sim.mode() == RadioMode::Transmit);
+        assert_eq!(sim.frequency_in_hz(), 868_100_000);
+        // no packet is sent, so there is no send operation to complete
+        assert_eq!(block_on(lora.tx_complete()).err(), Some(RadioError::InvalidRadioMode));
+        block_on(lora.stop_continuous_tx()).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(block_on(lora.stop_continuous_tx()), Err(RadioError::InvalidRadioMode));
+
+        block_on(lora.start_continuous_preamble(&mdltn_params, 10)).unwrap();
+        assert!(sim.continuous_preamble());
+        assert_eq!(sim.frequency_in_hz(), 868_300_000);
+        assert_eq!(sim.modulation_params()[..2], [0x09, 0x04]);
+        block_on(lora.stop_continuous_tx()).unwrap();
+        assert!(!sim.continuous_preamble());
+        assert!(sim.take_transmitted_packet().is_none());
+
+        // a pending send operation is not aborted
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        block_on(lora.start_tx(&mdltn_params, &mut tx_pkt_params, b"ping", 0xffffff)).unwrap();
+        assert_eq!(block_on(lora.stop_continuous_tx()), Err(RadioError::InvalidRadioMode));
+        block_on(lora.tx_complete()).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"ping");
+    }
+
+    #[test]
+    fn rx() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, true, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        sim.receive_packet(b"hello");
+        sim.set_packet_status(-80, 7, -81);
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 0, 1000)).unwrap();
+        let (len, pkt_status) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"hello");
+        assert_eq!(pkt_status.rssi, -80);
+        assert_eq!(pkt_status.snr, 7);
+        assert_eq!(pkt_status.signal_rssi, Some(-81));
+        assert_eq!(pkt_status.frequency_error_in_hz, None);
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // nothing received before the timeout
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 0, 1000)).unwrap();
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::ReceiveTimeout)
+        );
+
+        // in continuous mode, the radio keeps receiving packets
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        sim.receive_packet(b"one");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 3);
+        sim.receive_packet(b"three");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 5);
+        assert!(sim.mode() == RadioMode::Receive);
+    }
+
+    #[test]
+    fn rx_stats() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        sim.receive_packet(b"one");
+        block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        sim.receive_packet_with_crc_error(b"two");
+        block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(
+            block_on(lora.get_stats()).unwrap(),
+            RxStats {
+                packets_received: 2,
+                crc_errors: 1,
+                header_errors: Some(0),
+            }
+        );
+
+        block_on(lora.reset_stats()).unwrap();
+        assert_eq!(
+            block_on(lora.get_stats()).unwrap(),
+            RxStats {
+                header_errors: Some(0),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn tx_with_lbt() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 920_600_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        let lbt_params = LbtParams {
+            cad: true,
+            rssi_threshold: Some(-80),
+            listen_time_in_us: 5000,
+            min_backoff_in_ms: 10,
+            max_backoff_in_ms: 20,
+            max_attempts: 3,
+            deadline_in_ms: None,
+        };
+        let mut delay = SimDelay::default();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+
+        // LoRa activity on the channel, with two backoffs between the three attempts
+        sim.set_channel_activity(true);
+        assert_eq!(
+            block_on(lora.tx_with_lbt(
+                &mdltn_params,
+                &mut tx_pkt_params,
+                b"lbt",
+                0xffffff,
+                &lbt_params,
+                &mut delay
+            ))
+            .err(),
+            Some(RadioError::ChannelBusy)
+        );
+        assert!(sim.take_transmitted_packet().is_none());
+        assert!(sim.mode() == RadioMode::Standby);
+        assert!((20_000..=40_000).contains(&delay.elapsed_in_us));
+
+        // energy on the channel above the threshold
+        sim.set_channel_activity(false);
+        sim.set_packet_status(-70, 0, -70);
+        assert_eq!(
+            block_on(lora.tx_with_lbt(
+                &mdltn_params,
+                &mut tx_pkt_params,
+                b"lbt",
+                0xffffff,
+                &lbt_params,
+                &mut delay
+            ))
+            .err(),
+            Some(RadioError::ChannelBusy)
+        );
+
+        // free channel
+        sim.set_packet_status(-110, 0, -110);
+        delay.elapsed_in_us = 0;
+        block_on(lora.tx_with_lbt(
+            &mdltn_params,
+            &mut tx_pkt_params,
+            b"lbt",
+            0xffffff,
+            &lbt_params,
+            &mut delay,
+        ))
+        .unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"lbt");
+        assert_eq!(delay.elapsed_in_us, 5000);
+
+        // the 131 ms CAD operation at SF12 exceeds the deadline, so the operation gives up without backing off
+        let sf12_mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_12, Bandwidth::_125KHz, CodingRate::_4_5, 920_600_000)
+            .unwrap();
+        let deadline_lbt_params = LbtParams {
+            deadline_in_ms: Some(100),
+            ..lbt_params
+        };
+        sim.set_channel_activity(true);
+        delay.elapsed_in_us = 0;
+        assert_eq!(
+            block_on(lora.tx_with_lbt(
+                &sf12_mdltn_params,
+                &mut tx_pkt_params,
+                b"lbt",
+                0xffffff,
+                &deadline_lbt_params,
+                &mut delay
+            ))
+            .err(),
+            Some(RadioError::ChannelBusy)
+        );
+        assert_eq!(delay.elapsed_in_us, 0);
+        sim.set_channel_activity(false);
+
+        let invalid_lbt_params = LbtParams {
+            min_backoff_in_ms: 30,
+            ..lbt_params
+        };
+        assert_eq!(
+            block_on(lora.tx_with_lbt(
+                &mdltn_params,
+                &mut tx_pkt_params,
+                b"lbt",
+                0xffffff,
+                &invalid_lbt_params,
+                &mut delay
+            ))
+            .err(),
+            Some(RadioError::InvalidLbtParams)
+        );
+    }
+
+    #[test]
+    fn tx_with_duty_cycle() {
+        struct TestClock<'a>(&'a core::cell::Cell<u64>);
+
+        impl Clock for TestClock<'_> {
+            fn now_in_ms(&self) -> u64 {
+                self.0.get()
+            }
+        }
+
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_12, Bandwidth::_125KHz, CodingRate::_4_5, 869_525_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        // 10% over a 10 s window
+        let sub_bands = [SubBand {
+            min_frequency_in_hz: 869_400_000,
+            max_frequency_in_hz: 869_650_000,
+            max_duty_cycle_in_ppm: 100_000,
+        }];
+        let now = core::cell::Cell::new(0);
+        let mut enforcer = DutyCycleEnforcer::<_, 1, 4>::new(TestClock(&now), sub_bands, 10_000);
+        let mut delay = SimDelay::default();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+
+        // 828 ms on air, so a second packet would exceed the 1 s allowed in the window
+        block_on(lora.tx_with_duty_cycle(
+            &mdltn_params,
+            &mut tx_pkt_params,
+            b"duty",
+            0xffffff,
+            &mut enforcer,
+            false,
+            &mut delay,
+        ))
+        .unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"duty");
+        assert_eq!(
+            block_on(lora.tx_with_duty_cycle(
+                &mdltn_params,
+                &mut tx_pkt_params,
+                b"duty",
+                0xffffff,
+                &mut enforcer,
+                false,
+                &mut delay
+            ))
+            .err(),
+            Some(RadioError::DutyCycleLimitReached(10_828))
+        );
+        assert!(sim.take_transmitted_packet().is_none());
+
+        now.set(10_828);
+        block_on(lora.tx_with_duty_cycle(
+            &mdltn_params,
+            &mut tx_pkt_params,
+            b"duty",
+            0xffffff,
+            &mut enforcer,
+            false,
+            &mut delay,
+        ))
+        .unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"duty");
+    }
+    #[test]
+    fn current_rssi() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        assert_eq!(block_on(lora.current_rssi()).err(), Some(RadioError::InvalidRadioMode));
+
+        block_on(lora.prepare_for_rssi(&mdltn_params, false)).unwrap();
+        assert!(sim.mode() == RadioMode::Receive);
+        assert_eq!(sim.frequency_in_hz(), 868_100_000);
+        sim.set_packet_status(-95, 0, -95);
+        assert_eq!(block_on(lora.current_rssi()).unwrap(), -95);
+        assert!(sim.mode() == RadioMode::Receive);
+    }
+
+    #[test]
+    fn sync_word() {
+        let sim = SimSX1261_2::new();
+        let radio_kind = SX1261_2::new(BoardType::Rak4631Sx1262, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, SyncWord::new(0x2b), &mut SimDelay::default())).unwrap();
+        assert_eq!(sim.register(Register::LoRaSyncword as u16), 0x24);
+        assert_eq!(sim.register(Register::LoRaSyncword as u16 + 1), 0xb4);
+
+        // a change while receiving returns the radio to standby
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        block_on(lora.set_sync_word(SyncWord::PRIVATE)).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.register(Register::LoRaSyncword as u16), 0x14);
+        assert_eq!(sim.register(Register::LoRaSyncword as u16 + 1), 0x24);
+
+        // the sync word is kept when switching back from GFSK
+        let fsk_mdltn_params = lora
+            .create_fsk_modulation_params(50_000, 25_000, PulseShape::GaussianBt1_0, 100_000, 868_800_000)
+            .unwrap();
+        block_on(lora.prepare_for_fsk_tx(&fsk_mdltn_params, 14, false)).unwrap();
+        block_on(lora.set_sync_word(SyncWord::new(0x2b))).unwrap();
+        assert_eq!(sim.register(Register::LoRaSyncword as u16), 0x14);
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(sim.register(Register::LoRaSyncword as u16), 0x24);
+        assert_eq!(sim.register(Register::LoRaSyncword as u16 + 1), 0xb4);
+    }
+
+    #[test]
+    fn board_config() {
+        let sim = SimSX1261_2::new();
+        let board_config = BoardConfig {
+            board_type: BoardType::Custom(ChipType::Sx1262),
+            oscillator: Oscillator::Xtal,
+            dio2_rf_switch: false,
+            regulator: Regulator::Ldo,
+            pa_wiring: PaWiring::LowAndHighPower,
+            max_tx_power: 14,
+            rng_supported: false,
+        };
+        let radio_kind = SX1261_2::new(board_config, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert!(sim.board_type() == Some(BoardType::Custom(ChipType::Sx1262)));
+        assert_eq!(sim.tcxo_mode(), None);
+        assert_eq!(sim.regulator_mode(), 0x00);
+        assert!(!sim.rf_switch());
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, 20, true)).err(),
+            Some(RadioError::InvalidOutputPower)
+        );
+
+        // the boost request selects the high power PA
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, true)).unwrap();
+        assert_eq!(sim.pa_config(), [0x02, 0x02, 0x00, 0x01]);
+        assert_eq!(sim.tx_power(), 22);
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(sim.pa_config(), [0x04, 0x00, 0x01, 0x01]);
+        assert_eq!(sim.tx_power(), 14);
+
+        assert_eq!(
+            block_on(lora.get_random_number()).err(),
+            Some(RadioError::RngUnsupported)
+        );
+    }
+
+    #[test]
+    fn fsk() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_fsk_modulation_params(50_000, 25_000, PulseShape::GaussianBt1_0, 100_000, 868_800_000)
+            .unwrap();
+        let sync_word = [0xc1, 0x94, 0xc1];
+        let mut tx_pkt_params = lora
+            .create_fsk_tx_packet_params(5, &sync_word, true, FskCrcType::Ccitt, true, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_fsk_tx(&mdltn_params, 14, false)).unwrap();
+        block_on(lora.fsk_tx(&mdltn_params, &mut tx_pkt_params, &[0x01, 0x02], 0)).unwrap();
+
+        assert_eq!(sim.packet_type(), PacketType::GFSK.value());
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), &[0x01, 0x02]);
+        assert_eq!(sim.frequency_in_hz(), 868_800_000);
+        // 32 * 32 MHz / 50 kbps, Gaussian BT 1.0, 117.3 kHz receive bandwidth, 25 kHz * 2^25 / 32 MHz
+        assert_eq!(
+            sim.modulation_params(),
+            [0x00, 0x50, 0x00, 0x0b, 0x0b, 0x00, 0x66, 0x66]
+        );
+        // 40 bit preamble, 16 bit detector, 24 bit sync word, variable length of 2 bytes, CCITT CRC, whitening
+        assert_eq!(
+            sim.packet_params(),
+            [0x00, 0x28, 0x05, 0x18, 0x00, 0x01, 0x02, 0x06, 0x01]
+        );
+        assert_eq!(sim.register(Register::Syncword as u16 + 2), 0xc1);
+        assert_eq!(sim.register(Register::CrcInitialValue as u16), 0x1d);
+        assert_eq!(sim.register(Register::CrcPolynomial as u16), 0x10);
+
+        let rx_pkt_params = lora
+            .create_fsk_rx_packet_params(5, &sync_word, true, 255, FskCrcType::Ccitt, true, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+        sim.receive_packet(b"sensor");
+        sim.set_packet_status(-90, 0, -90);
+        block_on(lora.prepare_for_fsk_rx(&mdltn_params, &rx_pkt_params, false, false, 1000)).unwrap();
+        let (len, pkt_status) = block_on(lora.fsk_rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"sensor");
+        assert_eq!(pkt_status.rssi, -90);
+
+        // the radio switches back to LoRa for LoRa operations
+        let lora_mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&lora_mdltn_params, 14, false)).unwrap();
+        assert_eq!(sim.packet_type(), PacketType::LoRa.value());
+
+        assert_eq!(
+            lora.create_fsk_modulation_params(50_000, 25_000, PulseShape::None, 500_000, 868_800_000)
+                .err(),
+            Some(RadioError::UnavailableRxBandwidth)
+        );
+        assert_eq!(
+            lora.create_fsk_tx_packet_params(5, &[0x00; 9], true, FskCrcType::None, false, &mdltn_params)
+                .err(),
+            Some(RadioError::InvalidSyncWordLength)
+        );
+    }
+
+    #[test]
+    fn cad() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+
+        let cad_params = CadParams::recommended_for(SpreadingFactor::_9);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(sim.mode() == RadioMode::ChannelActivityDetection);
+        assert!(!block_on(lora.cad()).unwrap());
+
+        sim.set_channel_activity(true);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(block_on(lora.cad()).unwrap());
+    }
+
+    #[test]
+    fn cad_params_and_exit_modes() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_10, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0u8; 255];
+
+        let mut cad_params = CadParams::recommended_for(SpreadingFactor::_10);
+        assert_eq!(cad_params.symbols, CadSymbols::_4);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert_eq!(sim.cad_params(), [0x02, 24, 10, 0x00, 0x00, 0x00, 0x00]);
+        assert!(!block_on(lora.cad()).unwrap());
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // receive once activity is detected
+        cad_params.exit_mode = CadExitMode::CadRx;
+        cad_params.timeout_in_ms = 1000;
+        sim.set_channel_activity(true);
+        sim.receive_packet(b"hello");
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert_eq!(sim.cad_params(), [0x02, 24, 10, 0x01, 0x00, 0xfa, 0x00]);
+        assert!(block_on(lora.cad()).unwrap());
+        assert!(sim.mode() == RadioMode::Receive);
+        let (received_len, _) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..received_len as usize], b"hello");
+
+        // no activity, so no receive operation
+        sim.set_channel_activity(false);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(!block_on(lora.cad()).unwrap());
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // send the loaded packet once the channel is found free
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        block_on(lora.prepare_for_cad_lbt(&mdltn_params, &mut tx_pkt_params, b"free", &cad_params, false)).unwrap();
+        assert_eq!(sim.cad_params(), [0x02, 24, 10, 0x10, 0x00, 0xfa, 0x00]);
+        assert!(!block_on(lora.cad()).unwrap());
+        assert!(sim.mode() == RadioMode::Transmit);
+        block_on(lora.tx_complete()).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"free");
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // activity, so no send operation
+        sim.set_channel_activity(true);
+        block_on(lora.prepare_for_cad_lbt(&mdltn_params, &mut tx_pkt_params, b"busy", &cad_params, false)).unwrap();
+        assert!(block_on(lora.cad()).unwrap());
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(block_on(lora.tx_complete()).err(), Some(RadioError::InvalidRadioMode));
+        assert!(sim.take_transmitted_packet().is_none());
+    }
+
+    #[test]
+    fn cad_rx() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut cad_params = CadParams::recommended_for(SpreadingFactor::_9);
+        cad_params.timeout_in_ms = 100;
+        let mut receiving_buffer = [0u8; 255];
+
+        sim.receive_packet(b"wake");
+        block_on(lora.prepare_for_cad_rx(&mdltn_params, &rx_pkt_params, &cad_params, false, 10)).unwrap();
+        assert_eq!(sim.cad_params()[3], 0x01);
+        assert!(block_on(lora.cad_rx(&rx_pkt_params, &mut receiving_buffer))
+            .unwrap()
+            .is_none());
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // the chip chains the receive operation once activity is detected
+        sim.set_channel_activity(true);
+        sim.set_packet_status(-60, 8, -62);
+        block_on(lora.prepare_for_cad_rx(&mdltn_params, &rx_pkt_params, &cad_params, false, 10)).unwrap();
+        let (received_len, packet_status) = block_on(lora.cad_rx(&rx_pkt_params, &mut receiving_buffer))
+            .unwrap()
+            .unwrap();
+        assert_eq!(&receiving_buffer[..received_len as usize], b"wake");
+        assert_eq!(packet_status.snr, 8);
+
+        // without a packet, the receive operation times out
+        block_on(lora.prepare_for_cad_rx(&mdltn_params, &rx_pkt_params, &cad_params, false, 10)).unwrap();
+        assert_eq!(
+            block_on(lora.cad_rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::ReceiveTimeout)
+        );
+        assert!(sim.mode() == RadioMode::Standby);
+    }
+    #[test]
+    fn sleep_and_warm_start() {
+        let sim = SimSX1261_2::new();
+        let mut lora = new_lora(&sim);
+        let mut delay = SimDelay::default();
+        block_on(lora.sleep(&mut delay)).unwrap();
+        assert!(sim.mode() == RadioMode::Sleep);
+        assert!(sim.warm_start());
+        assert_eq!(delay.elapsed_in_us, 2000);
+
+        // waking up for the next operation retains the configuration
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.register(Register::LoRaSyncword as u16), 0x34);
+    }
+
+    #[test]
+    fn llcc68() {
+        let sim = SimSX1261_2::new();
+        let radio_kind = SX1261_2::new(BoardType::GenericLlcc68, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_10, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::UnavailableSpreadingFactor)
+        );
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_11, Bandwidth::_250KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::UnavailableSpreadingFactor)
+        );
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_12, Bandwidth::_500KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::UnavailableSpreadingFactor)
+        );
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_62KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::UnavailableBandwidth)
+        );
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_11, Bandwidth::_500KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 22, false)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"llcc68", 0xffffff)).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"llcc68");
+        assert_eq!(sim.modulation_params()[..4], [0x0b, 0x06, 0x01, 0x00]);
+        assert_eq!(sim.tx_power(), 22);
+    }
+
+    #[test]
+    fn sx1268() {
+        let sim = SimSX1261_2::new();
+        let radio_kind = SX1261_2::new(BoardType::GenericSx1268, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::InvalidFrequency)
+        );
+        assert_eq!(
+            lora.create_fsk_modulation_params(50_000, 25_000, PulseShape::GaussianBt1_0, 100_000, 400_000_000)
+                .err(),
+            Some(RadioError::InvalidFrequency)
+        );
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 433_175_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 22, false)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"sx1268", 0xffffff)).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"sx1268");
+        assert_eq!(sim.image_calibration(), [0x6b, 0x6f]);
+        assert_eq!(sim.frequency_in_hz(), 433_175_000);
+        assert_eq!(sim.tx_power(), 22);
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, 23, false)).err(),
+            Some(RadioError::InvalidOutputPower)
+        );
+
+        // the lower end of the band is calibrated after a reset
+        let radio_kind = SX1261_2::new(BoardType::GenericSx1268, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 415_000_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"415", 0xffffff)).unwrap();
+        assert_eq!(sim.image_calibration(), [0x66, 0x6b]);
+    }
+}
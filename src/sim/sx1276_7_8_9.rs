@@ -0,0 +1,1698 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::spi::{ErrorType, SpiBus, SpiBusFlush, SpiBusRead, SpiBusWrite};
+
+use crate::mod_params::*;
+use crate::mod_traits::InterfaceVariant;
+use crate::sim::{SimPacket, BUFFER_SIZE};
+use crate::sx1276_7_8_9::radio_kind_params::{
+    DioMapping1Dio0, FskDioMapping1Dio0, FskIrqFlags1, FskIrqFlags2, FskMode, FskRegister, IrqMask, LoRaMode, Register,
+};
+
+const REGISTER_SPACE_SIZE: usize = 0x80;
+
+// Registers at these addresses differ between the LoRa and FSK/OOK pages
+const PAGED_REGISTERS_START: u8 = 0x0d;
+const PAGED_REGISTERS_END: u8 = 0x3f;
+
+// Size of the FIFO in FSK/OOK mode
+const FSK_FIFO_SIZE: usize = 64;
+
+// Flags of RegOpMode
+const LONG_RANGE_MODE: u8 = 0x80;
+const MODE_MASK: u8 = 0x07;
+
+// Frequency synthesizer step for frequency calculation (Hz), as FXOSC (32 MHz) / 2^19
+const FXOSC: u64 = 32_000_000;
+const FREQUENCY_SYNTHESIZER_SHIFT: u32 = 19;
+
+// Registers which can only be read, ignoring writes
+const READ_ONLY_REGISTERS: [Register; 11] = [
+    Register::RegFifoRxCurrentAddr,
+    Register::RegRxNbBytes,
+    Register::RegModemStat,
+    Register::RegPktSnrValue,
+    Register::RegPktRssiValue,
+    Register::RegRssiValue,
+    Register::RegHopChannel,
+    Register::RegFreqErrorMsb,
+    Register::RegFreqErrorMid,
+    Register::RegFreqErrorLsb,
+    Register::RegVersion,
+];
+
+// Register values following a reset, with the chip in FSK/OOK standby mode
+const RESET_REGISTERS: [(Register, u8); 26] = [
+    (Register::RegOpMode, 0x01),
+    (Register::RegFrfMsb, 0x6c),
+    (Register::RegFrfMid, 0x80),
+    (Register::RegFrfLsb, 0x00),
+    (Register::RegPaConfig, 0x4f),
+    (Register::RegPaRamp, 0x09),
+    (Register::RegOcp, 0x2b),
+    (Register::RegLna, 0x20),
+    (Register::RegFifoTxBaseAddr, 0x80),
+    (Register::RegModemConfig1, 0x72),
+    (Register::RegModemConfig2, 0x70),
+    (Register::RegSymbTimeoutLsb, 0x64),
+    (Register::RegPreambleLsb, 0x08),
+    (Register::RegPayloadLength, 0x01),
+    (Register::RegMaxPayloadLength, 0xff),
+    (Register::RegModemConfig3, 0x04),
+    (Register::RegDetectionOptimize, 0xc3),
+    (Register::RegInvertiq, 0x27),
+    (Register::RegDetectionThreshold, 0x0a),
+    (Register::RegSyncWord, 0x12),
+    (Register::RegInvertiq2, 0x1d),
+    (Register::RegVersion, 0x12),
+    (Register::RegTcxo, 0x09),
+    (Register::RegPaDac, 0x84),
+    (Register::RegIrqFlagsMask, 0x00),
+    (Register::RegDioMapping1, 0x00),
+];
+
+// FSK/OOK page register values following a reset
+const FSK_RESET_REGISTERS: [(FskRegister, u8); 17] = [
+    (FskRegister::RegBitrateMsb, 0x1a),
+    (FskRegister::RegBitrateLsb, 0x0b),
+    (FskRegister::RegFdevMsb, 0x00),
+    (FskRegister::RegFdevLsb, 0x52),
+    (FskRegister::RegRxConfig, 0x0e),
+    (FskRegister::RegRxBw, 0x15),
+    (FskRegister::RegAfcBw, 0x0b),
+    (FskRegister::RegPreambleDetect, 0xaa),
+    (FskRegister::RegPreambleLsb, 0x03),
+    (FskRegister::RegSyncConfig, 0x93),
+    (FskRegister::RegSyncValue1, 0x01),
+    (FskRegister::RegPacketConfig1, 0x90),
+    (FskRegister::RegPacketConfig2, 0x40),
+    (FskRegister::RegPayloadLength, 0x40),
+    (FskRegister::RegFifoThresh, 0x0f),
+    (FskRegister::RegIrqFlags1, 0x80),
+    (FskRegister::RegIrqFlags2, 0x40),
+];
+
+// An operation started by a RegOpMode write, completed when the simulated chip raises the corresponding IRQ
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    None,
+    Tx,
+    Rx { continuous: bool },
+    Cad,
+    FskTx,
+    FskRx,
+}
+
+struct State {
+    board_type: Option<BoardType>,
+    nss_low: bool,
+    address: Option<u8>,
+    write: bool,
+    registers: [u8; REGISTER_SPACE_SIZE],
+    fsk_registers: [u8; REGISTER_SPACE_SIZE],
+    irq_flags: u8,
+    fifo: [u8; BUFFER_SIZE],
+    fsk_fifo: [u8; FSK_FIFO_SIZE],
+    fsk_fifo_read: usize,
+    fsk_fifo_write: usize,
+    rx_write_addr: u8,
+    operation: Operation,
+    rx_packet: Option<SimPacket>,
+    rx_crc_error: bool,
+    tx_packet: Option<SimPacket>,
+    channel_activity: bool,
+    dio0_lost: bool,            // events are never signalled on DIO0
+    rx_preamble_detected: bool, // the preamble of the packet of the pending single receive operation was detected
+    false_preamble: bool,       // the next single receive window detects a preamble, then times out
+    pkt_rssi_value: u8,
+    pkt_snr_value: u8,
+    modem_stat: u8,
+    hop_channel: u8,
+    freq_error: i32,
+}
+
+impl State {
+    fn new() -> Self {
+        let mut state = Self {
+            board_type: None,
+            nss_low: false,
+            address: None,
+            write: false,
+            registers: [0x00u8; REGISTER_SPACE_SIZE],
+            fsk_registers: [0x00u8; REGISTER_SPACE_SIZE],
+            irq_flags: 0x00,
+            fifo: [0x00u8; BUFFER_SIZE],
+            fsk_fifo: [0x00u8; FSK_FIFO_SIZE],
+            fsk_fifo_read: 0,
+            fsk_fifo_write: 0,
+            rx_write_addr: 0x00,
+            operation: Operation::None,
+            rx_packet: None,
+            rx_crc_error: false,
+            tx_packet: None,
+            channel_activity: false,
+            dio0_lost: false,
+            rx_preamble_detected: false,
+            false_preamble: false,
+            pkt_rssi_value: 0x00,
+            pkt_snr_value: 0x00,
+            modem_stat: 0x00,
+            hop_channel: 0x00,
+            freq_error: 0,
+        };
+        state.reset();
+        state
+    }
+
+    // Restore the power-on configuration, keeping the packets and channel conditions supplied by a test
+    fn reset(&mut self) {
+        self.address = None;
+        self.registers = [0x00u8; REGISTER_SPACE_SIZE];
+        self.fsk_registers = [0x00u8; REGISTER_SPACE_SIZE];
+        for (register, value) in RESET_REGISTERS {
+            self.registers[register as usize] = value;
+        }
+        for (register, value) in FSK_RESET_REGISTERS {
+            self.set_fsk_reg(register, value);
+        }
+        self.irq_flags = 0x00;
+        self.fifo = [0x00u8; BUFFER_SIZE];
+        self.clear_fsk_fifo();
+        self.rx_write_addr = 0x00;
+        self.operation = Operation::None;
+    }
+
+    fn reg(&self, register: Register) -> u8 {
+        self.registers[register as usize]
+    }
+
+    fn set_reg(&mut self, register: Register, value: u8) {
+        self.registers[register as usize] = value;
+    }
+
+    // Registers of the FSK/OOK page outside the paged addresses are shared with the LoRa page
+    fn fsk_reg(&self, register: FskRegister) -> u8 {
+        match Self::paged(register as u8) {
+            true => self.fsk_registers[register as usize],
+            false => self.registers[register as usize],
+        }
+    }
+
+    fn set_fsk_reg(&mut self, register: FskRegister, value: u8) {
+        match Self::paged(register as u8) {
+            true => self.fsk_registers[register as usize] = value,
+            false => self.registers[register as usize] = value,
+        }
+    }
+
+    fn paged(address: u8) -> bool {
+        (PAGED_REGISTERS_START..=PAGED_REGISTERS_END).contains(&address)
+    }
+
+    fn lora_mode(&self) -> bool {
+        (self.reg(Register::RegOpMode) & LONG_RANGE_MODE) != 0
+    }
+
+    // The sx1272/3 implicit header flag is bit 2 of RegModemConfig1, rather than bit 0
+    fn implicit_header(&self) -> bool {
+        let chip_type = self.board_type.map(ChipType::from);
+        let flag = match chip_type {
+            Some(ChipType::Sx1272 | ChipType::Sx1273) => 0x04,
+            _ => 0x01,
+        };
+        (self.reg(Register::RegModemConfig1) & flag) != 0
+    }
+
+    fn clear_fsk_fifo(&mut self) {
+        self.fsk_fifo_read = 0;
+        self.fsk_fifo_write = 0;
+        let flags = self.fsk_reg(FskRegister::RegIrqFlags2);
+        let flags = (flags & !(FskIrqFlags2::PayloadReady.value() | FskIrqFlags2::CrcOk.value()))
+            | FskIrqFlags2::FifoEmpty.value();
+        self.set_fsk_reg(FskRegister::RegIrqFlags2, flags);
+    }
+
+    fn push_fsk_fifo(&mut self, value: u8) {
+        let flags = self.fsk_reg(FskRegister::RegIrqFlags2);
+        if self.fsk_fifo_write == FSK_FIFO_SIZE {
+            self.set_fsk_reg(FskRegister::RegIrqFlags2, flags | FskIrqFlags2::FifoOverrun.value());
+            return;
+        }
+        self.fsk_fifo[self.fsk_fifo_write] = value;
+        self.fsk_fifo_write += 1;
+        self.set_fsk_reg(FskRegister::RegIrqFlags2, flags & !FskIrqFlags2::FifoEmpty.value());
+    }
+
+    // Reading the last byte of the FIFO clears PayloadReady
+    fn pop_fsk_fifo(&mut self) -> u8 {
+        if self.fsk_fifo_read == self.fsk_fifo_write {
+            return 0x00;
+        }
+        let value = self.fsk_fifo[self.fsk_fifo_read];
+        self.fsk_fifo_read += 1;
+        if self.fsk_fifo_read == self.fsk_fifo_write {
+            self.clear_fsk_fifo();
+        }
+        value
+    }
+
+    fn raise_fsk_irq(&mut self, irq_flags_1: u8, irq_flags_2: u8) {
+        let flags = self.fsk_reg(FskRegister::RegIrqFlags1);
+        self.set_fsk_reg(FskRegister::RegIrqFlags1, flags | irq_flags_1);
+        let flags = self.fsk_reg(FskRegister::RegIrqFlags2);
+        self.set_fsk_reg(FskRegister::RegIrqFlags2, flags | irq_flags_2);
+    }
+
+    fn select(&mut self) {
+        self.nss_low = true;
+        self.address = None;
+    }
+
+    fn deselect(&mut self) {
+        self.nss_low = false;
+        self.address = None;
+    }
+
+    // Shift one byte into the chip, returning the byte shifted out.  The first byte of an access holds the wnr bit and
+    // the register address, which is incremented by burst accesses except when accessing the FIFO.
+    fn exchange(&mut self, mosi: u8) -> u8 {
+        let address = match self.address {
+            None => {
+                self.write = (mosi & 0x80) != 0;
+                self.address = Some(mosi & 0x7f);
+                return 0x00;
+            }
+            Some(address) => address,
+        };
+
+        let miso = match self.write {
+            true => {
+                self.write_register(address, mosi);
+                0x00
+            }
+            false => self.read_register(address),
+        };
+        if address != Register::RegFifo as u8 {
+            self.address = Some((address + 1) % REGISTER_SPACE_SIZE as u8);
+        }
+        miso
+    }
+
+    fn read_register(&mut self, address: u8) -> u8 {
+        if !self.lora_mode() {
+            return match address == Register::RegFifo as u8 {
+                true => self.pop_fsk_fifo(),
+                false if Self::paged(address) => self.fsk_registers[address as usize],
+                false => self.registers[address as usize],
+            };
+        }
+
+        if address == Register::RegFifo as u8 {
+            let fifo_addr = self.reg(Register::RegFifoAddrPtr);
+            self.set_reg(Register::RegFifoAddrPtr, fifo_addr.wrapping_add(1));
+            self.fifo[fifo_addr as usize]
+        } else if address == Register::RegIrqFlags as u8 {
+            // a packet whose preamble was detected has been received by the time the flags are read
+            if self.rx_preamble_detected && self.operation == (Operation::Rx { continuous: false }) {
+                self.complete_operation();
+            }
+            self.irq_flags
+        } else if address == Register::RegModemStat as u8 && self.operation == (Operation::Rx { continuous: false }) {
+            self.rx_window_modem_stat()
+        } else {
+            self.registers[address as usize]
+        }
+    }
+
+    // The modem status once the window of a single receive operation has elapsed, where the preamble of a provided
+    // packet is detected (signal detected and synchronized, header info valid), otherwise the operation times out,
+    // possibly after a false preamble detection
+    fn rx_window_modem_stat(&mut self) -> u8 {
+        if self.false_preamble {
+            self.false_preamble = false;
+            self.complete_operation();
+            return 0x0b;
+        }
+        match self.rx_packet.is_some() {
+            true => {
+                self.rx_preamble_detected = true;
+                0x0b
+            }
+            false => {
+                self.complete_operation();
+                0x00
+            }
+        }
+    }
+
+    fn write_register(&mut self, address: u8, value: u8) {
+        if !self.lora_mode() && address != Register::RegOpMode as u8 {
+            self.write_fsk_register(address, value);
+            return;
+        }
+
+        if address == Register::RegFifo as u8 {
+            let fifo_addr = self.reg(Register::RegFifoAddrPtr);
+            self.set_reg(Register::RegFifoAddrPtr, fifo_addr.wrapping_add(1));
+            self.fifo[fifo_addr as usize] = value;
+        } else if address == Register::RegOpMode as u8 {
+            self.set_op_mode(value);
+        } else if address == Register::RegIrqFlags as u8 {
+            // flags are cleared by writing a one
+            self.irq_flags &= !value;
+        } else if !READ_ONLY_REGISTERS.iter().any(|r| *r as u8 == address) {
+            self.registers[address as usize] = value;
+        }
+    }
+
+    fn write_fsk_register(&mut self, address: u8, value: u8) {
+        if address == Register::RegFifo as u8 {
+            self.push_fsk_fifo(value);
+        } else if address == FskRegister::RegIrqFlags2 as u8 {
+            // only a FIFO overrun is cleared by writing a one, which also clears the FIFO
+            if (value & FskIrqFlags2::FifoOverrun.value()) != 0 {
+                let flags = self.fsk_reg(FskRegister::RegIrqFlags2);
+                self.set_fsk_reg(FskRegister::RegIrqFlags2, flags & !FskIrqFlags2::FifoOverrun.value());
+                self.clear_fsk_fifo();
+            }
+        } else if address != FskRegister::RegIrqFlags1 as u8 && address != FskRegister::RegRssiValue as u8 {
+            match Self::paged(address) {
+                true => self.fsk_registers[address as usize] = value,
+                false => self.registers[address as usize] = value,
+            }
+        }
+    }
+
+    // Change the operating mode, starting the operation it requests.  The LongRangeMode bit can only be changed
+    // when entering or in sleep mode.
+    fn set_op_mode(&mut self, value: u8) {
+        let current = self.reg(Register::RegOpMode);
+        let mut op_mode = value;
+        let sleeping = (current & MODE_MASK) == LoRaMode::Sleep as u8 || (value & MODE_MASK) == LoRaMode::Sleep as u8;
+        if !sleeping {
+            op_mode = (op_mode & !LONG_RANGE_MODE) | (current & LONG_RANGE_MODE);
+        }
+        self.set_reg(Register::RegOpMode, op_mode);
+
+        self.operation = Operation::None;
+        self.rx_preamble_detected = false;
+        let mode = op_mode & MODE_MASK;
+        if !self.lora_mode() {
+            self.set_fsk_op_mode(current & MODE_MASK, mode);
+            return;
+        }
+        if mode == LoRaMode::Tx as u8 {
+            self.operation = Operation::Tx;
+        } else if mode == LoRaMode::RxContinuous as u8 || mode == LoRaMode::RxSingle as u8 {
+            self.operation = Operation::Rx {
+                continuous: mode == LoRaMode::RxContinuous as u8,
+            };
+            self.rx_write_addr = self.reg(Register::RegFifoRxBaseAddr);
+        } else if mode == LoRaMode::Cad as u8 {
+            self.operation = Operation::Cad;
+        }
+    }
+
+    // The transmitter stays on after sending a packet, and the FIFO is cleared in sleep mode
+    fn set_fsk_op_mode(&mut self, current_mode: u8, mode: u8) {
+        if current_mode == FskMode::Tx as u8 && mode != FskMode::Tx as u8 {
+            let flags = self.fsk_reg(FskRegister::RegIrqFlags2);
+            self.set_fsk_reg(FskRegister::RegIrqFlags2, flags & !FskIrqFlags2::PacketSent.value());
+        }
+        if mode == FskMode::Sleep as u8 {
+            self.clear_fsk_fifo();
+        } else if mode == FskMode::Tx as u8 {
+            self.operation = Operation::FskTx;
+        } else if mode == FskMode::Rx as u8 {
+            self.operation = Operation::FskRx;
+        }
+    }
+
+    fn set_standby(&mut self) {
+        let op_mode = (self.reg(Register::RegOpMode) & !MODE_MASK) | LoRaMode::Standby as u8;
+        self.set_reg(Register::RegOpMode, op_mode);
+        self.operation = Operation::None;
+    }
+
+    fn raise_irq(&mut self, irq: u8) {
+        self.irq_flags |= irq & !self.reg(Register::RegIrqFlagsMask);
+    }
+
+    fn dio0(&self) -> bool {
+        if !self.lora_mode() {
+            return self.fsk_dio0();
+        }
+
+        let mapping = self.reg(Register::RegDioMapping1) & !DioMapping1Dio0::Mask.value();
+        let irq = if mapping == DioMapping1Dio0::RxDone.value() {
+            IrqMask::RxDone.value()
+        } else if mapping == DioMapping1Dio0::TxDone.value() {
+            IrqMask::TxDone.value()
+        } else if mapping == DioMapping1Dio0::CadDone.value() {
+            IrqMask::CADDone.value()
+        } else {
+            IrqMask::None.value()
+        };
+        (self.irq_flags & irq) != 0
+    }
+
+    // DIO0 signals PacketSent in transmit mode, and PayloadReady otherwise
+    fn fsk_dio0(&self) -> bool {
+        let mapping = self.reg(Register::RegDioMapping1) & !FskDioMapping1Dio0::Mask.value();
+        let irq = if mapping == FskDioMapping1Dio0::PacketSentPayloadReady.value() {
+            match (self.reg(Register::RegOpMode) & MODE_MASK) == FskMode::Tx as u8 {
+                true => FskIrqFlags2::PacketSent.value(),
+                false => FskIrqFlags2::PayloadReady.value(),
+            }
+        } else if mapping == FskDioMapping1Dio0::CrcOk.value() {
+            FskIrqFlags2::CrcOk.value()
+        } else {
+            0x00
+        };
+        (self.fsk_reg(FskRegister::RegIrqFlags2) & irq) != 0
+    }
+
+    fn fsk_variable_length(&self) -> bool {
+        (self.fsk_reg(FskRegister::RegPacketConfig1) & 0x80) != 0
+    }
+
+    fn fsk_crc_on(&self) -> bool {
+        (self.fsk_reg(FskRegister::RegPacketConfig1) & 0x10) != 0
+    }
+
+    // Complete the pending operation, as the chip would once the corresponding on-air event occurs
+    fn complete_operation(&mut self) {
+        match self.operation {
+            Operation::None => {}
+            Operation::Tx => {
+                let tx_base_addr = self.reg(Register::RegFifoTxBaseAddr);
+                let payload_len = self.reg(Register::RegPayloadLength) as usize;
+                let mut payload = [0x00u8; BUFFER_SIZE];
+                for (i, byte) in payload.iter_mut().take(payload_len).enumerate() {
+                    *byte = self.fifo[tx_base_addr.wrapping_add(i as u8) as usize];
+                }
+                self.tx_packet = Some(SimPacket::new(&payload[..payload_len]));
+                self.raise_irq(IrqMask::TxDone.value());
+                self.set_standby();
+            }
+            Operation::Rx { continuous } => {
+                if let Some(packet) = self.rx_packet.take() {
+                    let implicit_header = self.implicit_header();
+                    let payload_len = match implicit_header {
+                        true => self.reg(Register::RegPayloadLength) as usize,
+                        false => packet.len,
+                    };
+                    let start_addr = self.rx_write_addr;
+                    for byte in packet.data.iter().take(payload_len) {
+                        self.fifo[self.rx_write_addr as usize] = *byte;
+                        self.rx_write_addr = self.rx_write_addr.wrapping_add(1);
+                    }
+                    self.set_reg(Register::RegFifoRxCurrentAddr, start_addr);
+                    self.set_reg(Register::RegRxNbBytes, payload_len as u8);
+                    self.set_reg(Register::RegPktRssiValue, self.pkt_rssi_value);
+                    self.set_reg(Register::RegPktSnrValue, self.pkt_snr_value);
+                    self.set_reg(Register::RegModemStat, self.modem_stat);
+                    self.set_reg(Register::RegHopChannel, self.hop_channel);
+                    let freq_error = (self.freq_error & 0x000f_ffff).to_be_bytes();
+                    self.set_reg(Register::RegFreqErrorMsb, freq_error[1]);
+                    self.set_reg(Register::RegFreqErrorMid, freq_error[2]);
+                    self.set_reg(Register::RegFreqErrorLsb, freq_error[3]);
+
+                    let mut irq = IrqMask::RxDone.value();
+                    if !implicit_header {
+                        irq |= IrqMask::HeaderValid.value();
+                    }
+                    if self.rx_crc_error {
+                        irq |= IrqMask::CRCError.value();
+                    }
+                    self.raise_irq(irq);
+                } else if !continuous {
+                    self.raise_irq(IrqMask::RxTimeout.value());
+                } else {
+                    return;
+                }
+                if !continuous {
+                    self.set_standby();
+                }
+            }
+            Operation::Cad => {
+                let mut irq = IrqMask::CADDone.value();
+                if self.channel_activity {
+                    irq |= IrqMask::CADActivityDetected.value();
+                }
+                self.raise_irq(irq);
+                self.set_standby();
+            }
+            Operation::FskTx => {
+                let payload_len = match self.fsk_variable_length() {
+                    true => self.pop_fsk_fifo() as usize,
+                    false => self.fsk_reg(FskRegister::RegPayloadLength) as usize,
+                };
+                let mut payload = [0x00u8; BUFFER_SIZE];
+                for byte in payload.iter_mut().take(payload_len) {
+                    *byte = self.pop_fsk_fifo();
+                }
+                self.tx_packet = Some(SimPacket::new(&payload[..payload_len]));
+                self.raise_fsk_irq(FskIrqFlags1::TxReady.value(), FskIrqFlags2::PacketSent.value());
+                self.operation = Operation::None;
+            }
+            Operation::FskRx => {
+                let Some(packet) = self.rx_packet.take() else {
+                    return;
+                };
+                self.raise_fsk_irq(
+                    FskIrqFlags1::PreambleDetect.value() | FskIrqFlags1::SyncAddressMatch.value(),
+                    0x00,
+                );
+                // packets failing the CRC check are discarded
+                if self.fsk_crc_on() && self.rx_crc_error {
+                    return;
+                }
+                self.clear_fsk_fifo();
+                let payload_len = match self.fsk_variable_length() {
+                    true => {
+                        self.push_fsk_fifo(packet.len as u8);
+                        packet.len
+                    }
+                    false => self.fsk_reg(FskRegister::RegPayloadLength) as usize,
+                };
+                for byte in packet.data.iter().take(payload_len) {
+                    self.push_fsk_fifo(*byte);
+                }
+                self.fsk_registers[FskRegister::RegRssiValue as usize] = self.pkt_rssi_value;
+
+                let mut irq = FskIrqFlags2::PayloadReady.value();
+                if self.fsk_crc_on() {
+                    irq |= FskIrqFlags2::CrcOk.value();
+                }
+                self.raise_fsk_irq(0x00, irq);
+            }
+        }
+    }
+}
+
+/// A simulated Sx1276/7/8/9 chip, modelling the LoRa register map accessed over its SPI bus and raising IRQs on DIO0
+/// as operations complete.  Operations complete as soon as the radio kind waits on DIO0.  Setting an Sx1272/3 board
+/// type selects the Sx1272/3 modem configuration layout.
+pub struct SimSX1276_7_8_9 {
+    state: RefCell<State>,
+}
+
+impl Default for SimSX1276_7_8_9 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimSX1276_7_8_9 {
+    /// Create a simulated chip in its power-on state
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(State::new()),
+        }
+    }
+
+    /// Get the SPI bus connected to the simulated chip
+    pub fn spi(&self) -> SimSpi<'_> {
+        SimSpi { state: &self.state }
+    }
+
+    /// Get the interface variant (NSS, reset, DIO0, and RF switch lines) connected to the simulated chip
+    pub fn iv(&self) -> SimInterfaceVariant<'_> {
+        SimInterfaceVariant { state: &self.state }
+    }
+
+    /// Provide a packet for the next receive operation
+    pub fn receive_packet(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = false;
+    }
+
+    /// Provide a packet with an invalid CRC for the next receive operation
+    pub fn receive_packet_with_crc_error(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = true;
+    }
+
+    /// Set the RegPktRssiValue (or, in FSK/OOK mode, RegRssiValue) and RegPktSnrValue register values reported for
+    /// received packets
+    pub fn set_packet_status(&self, pkt_rssi_value: u8, pkt_snr_value: u8) {
+        let mut state = self.state.borrow_mut();
+        state.pkt_rssi_value = pkt_rssi_value;
+        state.pkt_snr_value = pkt_snr_value;
+    }
+
+    /// Set the RegRssiValue register value, giving the current RSSI in LoRa mode
+    pub fn set_rssi_value(&self, rssi_value: u8) {
+        self.state.borrow_mut().set_reg(Register::RegRssiValue, rssi_value);
+    }
+
+    /// Set the RegModemStat and RegHopChannel register values and the 20 bit frequency error reported for received
+    /// packets
+    pub fn set_packet_metadata(&self, modem_stat: u8, hop_channel: u8, freq_error: i32) {
+        let mut state = self.state.borrow_mut();
+        state.modem_stat = modem_stat;
+        state.hop_channel = hop_channel;
+        state.freq_error = freq_error;
+    }
+
+    /// Set whether channel activity detection operations detect activity
+    pub fn set_channel_activity(&self, detected: bool) {
+        self.state.borrow_mut().channel_activity = detected;
+    }
+
+    /// Set whether the events of operations are lost, as with a stuck PA or a missing DIO0 edge, so waiting for them
+    /// never completes
+    pub fn set_dio0_lost(&self, lost: bool) {
+        self.state.borrow_mut().dio0_lost = lost;
+    }
+
+    /// Set the next window of a duty-cycled receive operation to detect a preamble, then time out without a packet
+    pub fn set_false_preamble(&self) {
+        self.state.borrow_mut().false_preamble = true;
+    }
+
+    /// Take the packet sent by the last send operation
+    pub fn take_transmitted_packet(&self) -> Option<SimPacket> {
+        self.state.borrow_mut().tx_packet.take()
+    }
+
+    /// Get the board type set through the interface variant
+    pub fn board_type(&self) -> Option<BoardType> {
+        self.state.borrow().board_type
+    }
+
+    /// Get the operating mode of the chip
+    pub fn mode(&self) -> RadioMode {
+        let mode = self.state.borrow().reg(Register::RegOpMode) & MODE_MASK;
+        if mode == LoRaMode::Sleep as u8 {
+            RadioMode::Sleep
+        } else if mode == LoRaMode::Tx as u8 {
+            RadioMode::Transmit
+        } else if mode == LoRaMode::RxContinuous as u8 || mode == LoRaMode::RxSingle as u8 {
+            RadioMode::Receive
+        } else if mode == LoRaMode::Cad as u8 {
+            RadioMode::ChannelActivityDetection
+        } else if mode == LoRaMode::Standby as u8 {
+            RadioMode::Standby
+        } else {
+            RadioMode::FrequencySynthesis
+        }
+    }
+
+    /// Get whether the chip is in LoRa mode (rather than FSK/OOK mode)
+    pub fn lora_mode(&self) -> bool {
+        self.state.borrow().lora_mode()
+    }
+
+    /// Get the channel frequency set on the chip, rounded to the nearest Hz
+    pub fn frequency_in_hz(&self) -> u32 {
+        let state = self.state.borrow();
+        let frf = ((state.reg(Register::RegFrfMsb) as u64) << 16)
+            | ((state.reg(Register::RegFrfMid) as u64) << 8)
+            | (state.reg(Register::RegFrfLsb) as u64);
+        ((frf * FXOSC + (1 << (FREQUENCY_SYNTHESIZER_SHIFT - 1))) >> FREQUENCY_SYNTHESIZER_SHIFT) as u32
+    }
+
+    /// Get the value of a register, in the page of the current (LoRa or FSK/OOK) mode
+    pub fn register(&self, address: u8) -> u8 {
+        let state = self.state.borrow();
+        let address = address as usize % REGISTER_SPACE_SIZE;
+        if !state.lora_mode() && State::paged(address as u8) {
+            state.fsk_registers[address]
+        } else if address == Register::RegIrqFlags as usize {
+            state.irq_flags
+        } else {
+            state.registers[address]
+        }
+    }
+
+    /// Get the value at an address of the FIFO
+    pub fn fifo(&self, address: u8) -> u8 {
+        self.state.borrow().fifo[address as usize]
+    }
+}
+
+/// The SPI bus of a simulated Sx1276/7/8/9 chip
+pub struct SimSpi<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl ErrorType for SimSpi<'_> {
+    type Error = Infallible;
+}
+
+impl SpiBusFlush for SimSpi<'_> {
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl SpiBusRead<u8> for SimSpi<'_> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(0x00u8);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBusWrite<u8> for SimSpi<'_> {
+    async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBus<u8> for SimSpi<'_> {
+    async fn transfer<'b>(&'b mut self, read: &'b mut [u8], write: &'b [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for i in 0..core::cmp::max(read.len(), write.len()) {
+            let miso = state.exchange(write.get(i).copied().unwrap_or(0x00u8));
+            if let Some(word) = read.get_mut(i) {
+                *word = miso;
+            }
+        }
+        Ok(())
+    }
+
+    async fn transfer_in_place<'b>(&'b mut self, words: &'b mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+/// The interface variant of a simulated Sx1276/7/8/9 chip.  Waiting on DIO0 completes the pending operation; it fails
+/// with [`RadioError::Irq`] if DIO0 would never rise.
+pub struct SimInterfaceVariant<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl InterfaceVariant for SimInterfaceVariant<'_> {
+    fn set_board_type(&mut self, board_type: BoardType) {
+        self.state.borrow_mut().board_type = Some(board_type);
+    }
+
+    async fn set_nss_low(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().select();
+        Ok(())
+    }
+
+    async fn set_nss_high(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().deselect();
+        Ok(())
+    }
+
+    async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        self.state.borrow_mut().reset();
+        delay.delay_ms(10).await;
+        Ok(())
+    }
+
+    async fn wait_on_busy(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn await_irq(&mut self) -> Result<(), RadioError> {
+        if self.state.borrow().dio0_lost {
+            return core::future::pending().await;
+        }
+        let mut state = self.state.borrow_mut();
+        if !state.dio0() {
+            state.complete_operation();
+        }
+        match state.dio0() {
+            true => Ok(()),
+            false => Err(RadioError::Irq),
+        }
+    }
+
+    async fn enable_rf_switch_rx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn enable_rf_switch_tx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn disable_rf_switch(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::sim::{block_on, SimDelay};
+    use crate::sx1276_7_8_9::radio_kind_params::PaDac;
+    use crate::sx1276_7_8_9::SX1276_7_8_9;
+    use crate::LoRa;
+
+    fn new_lora(sim: &SimSX1276_7_8_9) -> LoRa<SX1276_7_8_9<SimSpi<'_>, SimInterfaceVariant<'_>>> {
+        let radio_kind = SX1276_7_8_9::new(BoardType::Stm32l0Sx1276, sim.spi(), sim.iv());
+        block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap()
+    }
+
+    fn reg(sim: &SimSX1276_7_8_9, register: Register) -> u8 {
+        sim.register(register as u8)
+    }
+
+    fn fsk_reg(sim: &SimSX1276_7_8_9, register: FskRegister) -> u8 {
+        sim.register(register as u8)
+    }
+
+    // EU868 DR7 (50 kbps GFSK)
+    const FSK_SYNC_WORD: [u8; 3] = [0xc1, 0x94, 0xc1];
+
+    fn new_fsk_params(lora: &mut LoRa<SX1276_7_8_9<SimSpi<'_>, SimInterfaceVariant<'_>>>) -> FskModulationParams {
+        lora.create_fsk_modulation_params(50_000, 25_000, PulseShape::GaussianBt1_0, 100_000, 868_800_000)
+            .unwrap()
+    }
+
+    #[test]
+    fn init() {
+        let sim = SimSX1276_7_8_9::new();
+        let _lora = new_lora(&sim);
+        assert!(sim.board_type() == Some(BoardType::Stm32l0Sx1276));
+        assert!(sim.lora_mode());
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(reg(&sim, Register::RegSyncWord), 0x34);
+        assert_eq!(reg(&sim, Register::RegIrqFlagsMask), IrqMask::All.value());
+        assert_eq!(reg(&sim, Register::RegIrqFlags), 0x00);
+    }
+
+    #[test]
+    fn sync_word() {
+        let sim = SimSX1276_7_8_9::new();
+        let radio_kind = SX1276_7_8_9::new(BoardType::Stm32l0Sx1276, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, SyncWord::new(0x2b), &mut SimDelay::default())).unwrap();
+        assert_eq!(reg(&sim, Register::RegSyncWord), 0x2b);
+
+        block_on(lora.set_sync_word(SyncWord::PRIVATE)).unwrap();
+        assert_eq!(reg(&sim, Register::RegSyncWord), 0x12);
+        assert!(lora.get_sync_word() == SyncWord::PRIVATE);
+    }
+
+    #[test]
+    fn tx_with_lbt() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        let lbt_params = LbtParams {
+            cad: false,
+            rssi_threshold: Some(-90),
+            listen_time_in_us: 160,
+            min_backoff_in_ms: 100,
+            max_backoff_in_ms: 1000,
+            max_attempts: 100,
+            deadline_in_ms: Some(2000),
+        };
+        let mut delay = SimDelay::default();
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+
+        // the chip has no random number generator, so the backoffs are generated in software until the deadline
+        sim.set_rssi_value(80);
+        assert_eq!(
+            block_on(lora.tx_with_lbt(
+                &mdltn_params,
+                &mut tx_pkt_params,
+                b"lbt",
+                0xffffff,
+                &lbt_params,
+                &mut delay
+            ))
+            .err(),
+            Some(RadioError::ChannelBusy)
+        );
+        assert!(delay.elapsed_in_us <= 2_000_000);
+        assert!(sim.mode() == RadioMode::Standby);
+
+        sim.set_rssi_value(40);
+        block_on(lora.tx_with_lbt(
+            &mdltn_params,
+            &mut tx_pkt_params,
+            b"lbt",
+            0xffffff,
+            &lbt_params,
+            &mut delay,
+        ))
+        .unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"lbt");
+    }
+
+    #[test]
+    fn current_rssi() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        assert_eq!(block_on(lora.current_rssi()).err(), Some(RadioError::InvalidRadioMode));
+
+        block_on(lora.prepare_for_rssi(&mdltn_params, false)).unwrap();
+        assert!(sim.mode() == RadioMode::Receive);
+        sim.set_rssi_value(60);
+        assert_eq!(block_on(lora.current_rssi()).unwrap(), -97);
+
+        // the low frequency port has a different offset
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 433_175_000)
+            .unwrap();
+        block_on(lora.prepare_for_rssi(&mdltn_params, false)).unwrap();
+        assert_eq!(block_on(lora.current_rssi()).unwrap(), -104);
+
+        // spreading factor 6 is measured although it only supports the implicit header mode
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_6, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        block_on(lora.prepare_for_rssi(&mdltn_params, false)).unwrap();
+        assert_eq!(block_on(lora.current_rssi()).unwrap(), -97);
+    }
+
+    #[test]
+    fn board_config() {
+        let sim = SimSX1276_7_8_9::new();
+        let board_config = BoardConfig {
+            board_type: BoardType::Custom(ChipType::Sx1276),
+            oscillator: Oscillator::Xtal,
+            pa_wiring: PaWiring::HighPower,
+            max_tx_power: 17,
+            ..BoardType::Stm32l0Sx1276.into()
+        };
+        let radio_kind = SX1276_7_8_9::new(board_config, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert!(sim.board_type() == Some(BoardType::Custom(ChipType::Sx1276)));
+        assert_eq!(reg(&sim, Register::RegTcxo), 0x09);
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 915_000_000)
+            .unwrap();
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, 20, true)).err(),
+            Some(RadioError::InvalidOutputPower)
+        );
+        // only PA_BOOST is wired
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(reg(&sim, Register::RegPaConfig), 0x8c);
+    }
+
+    #[test]
+    fn set_packet_params() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_6, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(0x0123, true, 20, false, true, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+
+        assert_eq!(reg(&sim, Register::RegPreambleMsb), 0x01);
+        assert_eq!(reg(&sim, Register::RegPreambleLsb), 0x23);
+        assert_eq!(reg(&sim, Register::RegModemConfig1), 0x73);
+        assert_eq!(reg(&sim, Register::RegModemConfig2) & 0xf4, 0x60);
+        assert_eq!(reg(&sim, Register::RegInvertiq), 0x66);
+        assert_eq!(reg(&sim, Register::RegInvertiq2), 0x19);
+        assert_eq!(reg(&sim, Register::RegDetectionOptimize), 0xc5);
+        assert_eq!(reg(&sim, Register::RegDetectionThreshold), 0x0c);
+        assert!(868_100_000 - sim.frequency_in_hz() < 62);
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        assert_eq!(reg(&sim, Register::RegPreambleMsb), 0x00);
+        assert_eq!(reg(&sim, Register::RegPreambleLsb), 0x08);
+        assert_eq!(reg(&sim, Register::RegModemConfig1), 0x72);
+        assert_eq!(reg(&sim, Register::RegModemConfig2) & 0xf4, 0x74);
+        assert_eq!(reg(&sim, Register::RegInvertiq), 0x27);
+        assert_eq!(reg(&sim, Register::RegInvertiq2), 0x1d);
+    }
+
+    #[test]
+    fn tx() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 915_000_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 17, true)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, &[0x10, 0x20, 0x30, 0x40], 0xffffff)).unwrap();
+
+        assert_eq!(
+            sim.take_transmitted_packet().unwrap().payload(),
+            &[0x10, 0x20, 0x30, 0x40]
+        );
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(reg(&sim, Register::RegPaConfig), 0x8f);
+        assert_eq!(reg(&sim, Register::RegModemConfig2) & 0xf0, 0x90);
+        assert_eq!(reg(&sim, Register::RegIrqFlags), 0x00);
+    }
+
+    #[test]
+    fn continuous_tx() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_125KHz, CodingRate::_4_5, 868_300_000)
+            .unwrap();
+
+        // an unmodulated carrier from the FSK modem in continuous mode
+        block_on(lora.start_continuous_wave(868_100_000, 14)).unwrap();
+        assert!(!sim.lora_mode());
+        assert!(sim.mode() == RadioMode::Transmit);
+        assert!(868_100_000 - sim.frequency_in_hz() < 62);
+        assert_eq!(sim.register(FskRegister::RegFdevMsb as u8), 0x00);
+        assert_eq!(sim.register(FskRegister::RegFdevLsb as u8), 0x00);
+        assert_eq!(sim.register(FskRegister::RegPacketConfig2 as u8), 0x00);
+        block_on(lora.stop_continuous_tx()).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // packets with the longest preamble, sent back to back
+        block_on(lora.start_continuous_preamble(&mdltn_params, 14)).unwrap();
+        assert!(sim.lora_mode());
+        assert!(sim.mode() == RadioMode::Transmit);
+        assert_eq!(reg(&sim, Register::RegPreambleMsb), 0xff);
+        assert_eq!(reg(&sim, Register::RegPreambleLsb), 0xff);
+        assert_eq!(reg(&sim, Register::RegModemConfig2) & 0x08, 0x08);
+        block_on(lora.stop_continuous_tx()).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // stopping leaves TxContinuousMode and restores the preamble length (8 symbols after reset), so a later send
+        // operation is not endless
+        assert_eq!(reg(&sim, Register::RegModemConfig2) & 0x08, 0x00);
+        assert_eq!(reg(&sim, Register::RegPreambleMsb), 0x00);
+        assert_eq!(reg(&sim, Register::RegPreambleLsb), 0x08);
+    }
+
+    #[test]
+    fn set_irq_params() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        assert_eq!(
+            reg(&sim, Register::RegDioMapping1) & 0xc0,
+            DioMapping1Dio0::Other.value()
+        );
+
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        assert_eq!(reg(&sim, Register::RegIrqFlagsMask), 0x1f);
+        assert_eq!(
+            reg(&sim, Register::RegDioMapping1) & 0xc0,
+            DioMapping1Dio0::RxDone.value()
+        );
+
+        let cad_params = CadParams::recommended_for(SpreadingFactor::_7);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert_eq!(reg(&sim, Register::RegIrqFlagsMask), 0xfa);
+        assert_eq!(
+            reg(&sim, Register::RegDioMapping1) & 0xc0,
+            DioMapping1Dio0::CadDone.value()
+        );
+
+        // masked interrupts are not raised
+        sim.set_channel_activity(true);
+        assert!(block_on(lora.cad()).unwrap());
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, &[0x01], 0xffffff)).unwrap();
+        assert_eq!(reg(&sim, Register::RegIrqFlagsMask), 0xf7);
+        assert_eq!(
+            reg(&sim, Register::RegDioMapping1) & 0xc0,
+            DioMapping1Dio0::TxDone.value()
+        );
+    }
+
+    #[test]
+    fn get_rx_payload() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        sim.receive_packet(b"hello");
+        sim.set_packet_status(100, 0x20);
+        // 4/5 coding rate, CRC on, -1000 frequency error
+        sim.set_packet_metadata(0x20, 0x40, -1000);
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        let (len, pkt_status) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"hello");
+        assert_eq!(pkt_status.rssi, -51);
+        assert_eq!(pkt_status.snr, 8);
+        assert_eq!(pkt_status.signal_rssi, None);
+        assert_eq!(pkt_status.frequency_error_in_hz, Some(-131));
+        assert!(pkt_status.coding_rate == Some(CodingRate::_4_5));
+        assert_eq!(pkt_status.crc_present, Some(true));
+        assert_eq!(pkt_status.header_payload_length, Some(5));
+        assert_eq!(reg(&sim, Register::RegFifoAddrPtr), 0x00);
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // in continuous mode, packets are read from where they were written in the FIFO
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        sim.receive_packet(b"one");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 3);
+        sim.receive_packet(b"two!");
+        let (len, _) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"two!");
+        assert_eq!(reg(&sim, Register::RegFifoRxCurrentAddr), 3);
+        assert_eq!(sim.fifo(0), b'o');
+        assert!(sim.mode() == RadioMode::Receive);
+
+        // a packet too large for the receiving buffer
+        let mut small_buffer = [0x00u8; 2];
+        sim.receive_packet(b"three");
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut small_buffer)).err(),
+            Some(RadioError::PayloadSizeMismatch(5, 2))
+        );
+    }
+
+    #[test]
+    fn process_irq() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        // nothing received before the symbol timeout, which is signalled on DIO1 rather than DIO0
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::Irq)
+        );
+        assert_eq!(reg(&sim, Register::RegIrqFlags), IrqMask::RxTimeout.value());
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // CRC errors are not reported as failures
+        sim.receive_packet_with_crc_error(b"bad");
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 3);
+
+        // no event occurs in continuous mode without a packet
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::Irq)
+        );
+
+        let cad_params = CadParams::recommended_for(SpreadingFactor::_7);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(sim.mode() == RadioMode::ChannelActivityDetection);
+        assert!(!block_on(lora.cad()).unwrap());
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // the chip chains no send operation to a CAD operation
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        assert_eq!(
+            block_on(lora.prepare_for_cad_lbt(&mdltn_params, &mut tx_pkt_params, b"free", &cad_params, false)).err(),
+            Some(RadioError::CadExitModeUnsupported)
+        );
+    }
+
+    // A delay which provides a packet to the simulated chip at the given call, accumulating the requested time
+    struct PacketArrivalDelay<'a> {
+        sim: &'a SimSX1276_7_8_9,
+        calls_before_packet: &'a Cell<u32>,
+        elapsed_in_us: &'a Cell<u64>,
+    }
+
+    impl DelayUs for PacketArrivalDelay<'_> {
+        async fn delay_us(&mut self, us: u32) {
+            self.elapsed_in_us.set(self.elapsed_in_us.get() + us as u64);
+            if self.calls_before_packet.get() == 0 {
+                self.sim.receive_packet(b"sniffed");
+            }
+            self.calls_before_packet
+                .set(self.calls_before_packet.get().wrapping_sub(1));
+        }
+
+        async fn delay_ms(&mut self, ms: u32) {
+            self.delay_us(ms * 1000).await;
+        }
+    }
+
+    #[test]
+    fn rx_duty_cycle() {
+        let sim = SimSX1276_7_8_9::new();
+        let calls_before_packet = Cell::new(3);
+        let elapsed_in_us = Cell::new(0);
+        let delay = PacketArrivalDelay {
+            sim: &sim,
+            calls_before_packet: &calls_before_packet,
+            elapsed_in_us: &elapsed_in_us,
+        };
+        let radio_kind = SX1276_7_8_9::new_with_delay(BoardType::Stm32l0Sx1276, sim.spi(), sim.iv(), delay);
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0u8; 255];
+
+        // 10 ms receive windows (10 symbols) and 1 s sleep periods, in 15.625 us steps
+        let duty_cycle_params = DutyCycleParams {
+            rx_time: 640,
+            sleep_time: 64_000,
+        };
+        block_on(lora.prepare_for_rx(
+            &mdltn_params,
+            &rx_pkt_params,
+            Some(&duty_cycle_params),
+            false,
+            false,
+            0,
+            0,
+        ))
+        .unwrap();
+        assert_eq!(reg(&sim, Register::RegSymbTimeoutLsb), 10);
+
+        // the packet arrives during the second sleep period, so is detected in the third window
+        let (received_len, _) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..received_len as usize], b"sniffed");
+        assert_eq!(elapsed_in_us.get(), 3 * 10_000 + 2 * 1_000_000);
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // a false preamble detection times out and resumes the duty cycle, rather than waiting for the packet
+        sim.set_false_preamble();
+        calls_before_packet.set(3);
+        elapsed_in_us.set(0);
+        block_on(lora.prepare_for_rx(
+            &mdltn_params,
+            &rx_pkt_params,
+            Some(&duty_cycle_params),
+            false,
+            false,
+            0,
+            0,
+        ))
+        .unwrap();
+        let (received_len, _) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..received_len as usize], b"sniffed");
+        assert_eq!(elapsed_in_us.get(), 3 * 10_000 + 2 * 1_000_000);
+
+        // a receive window is needed to detect a preamble
+        let no_window_params = DutyCycleParams {
+            rx_time: 0,
+            sleep_time: 64_000,
+        };
+        assert_eq!(
+            block_on(lora.prepare_for_rx(
+                &mdltn_params,
+                &rx_pkt_params,
+                Some(&no_window_params),
+                false,
+                false,
+                0,
+                0
+            ))
+            .err(),
+            Some(RadioError::InvalidDutyCycleParams)
+        );
+
+        // duty-cycled receive operations are timed with the delay of the radio kind
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        assert_eq!(
+            block_on(lora.prepare_for_rx(
+                &mdltn_params,
+                &rx_pkt_params,
+                Some(&duty_cycle_params),
+                false,
+                false,
+                0,
+                0
+            ))
+            .err(),
+            Some(RadioError::DutyCycleUnsupported)
+        );
+    }
+
+    #[test]
+    fn timeouts() {
+        let sim = SimSX1276_7_8_9::new();
+        let radio_kind =
+            SX1276_7_8_9::new_with_delay(BoardType::Stm32l0Sx1276, sim.spi(), sim.iv(), SimDelay::default());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0u8; 255];
+
+        // the operations end in standby mode once their timeouts elapse without an event
+        sim.set_dio0_lost(true);
+        block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(
+            block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"hello", 1000)).err(),
+            Some(RadioError::TransmitTimeout)
+        );
+        assert!(sim.mode() == RadioMode::Standby);
+
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 500)).unwrap();
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::ReceiveTimeout)
+        );
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // events signalled before the timeouts complete the operations
+        sim.set_dio0_lost(false);
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"hello", 1000)).unwrap();
+        sim.receive_packet(b"world");
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 500)).unwrap();
+        let (received_len, _) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..received_len as usize], b"world");
+    }
+    #[test]
+    fn cad_rx() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let cad_params = CadParams::recommended_for(SpreadingFactor::_7);
+        let mut receiving_buffer = [0u8; 255];
+
+        sim.receive_packet(b"wake");
+        block_on(lora.prepare_for_cad_rx(&mdltn_params, &rx_pkt_params, &cad_params, false, 10)).unwrap();
+        assert!(block_on(lora.cad_rx(&rx_pkt_params, &mut receiving_buffer))
+            .unwrap()
+            .is_none());
+        assert!(sim.mode() == RadioMode::Standby);
+
+        // the receive operation is started in software once activity is detected
+        sim.set_channel_activity(true);
+        block_on(lora.prepare_for_cad_rx(&mdltn_params, &rx_pkt_params, &cad_params, false, 10)).unwrap();
+        let (received_len, _) = block_on(lora.cad_rx(&rx_pkt_params, &mut receiving_buffer))
+            .unwrap()
+            .unwrap();
+        assert_eq!(&receiving_buffer[..received_len as usize], b"wake");
+    }
+    #[test]
+    fn rx_stats() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        // counted by the driver, which cannot detect header errors
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        sim.receive_packet(b"one");
+        block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        sim.receive_packet_with_crc_error(b"two");
+        block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(
+            block_on(lora.get_stats()).unwrap(),
+            RxStats {
+                packets_received: 2,
+                crc_errors: 1,
+                header_errors: None,
+            }
+        );
+
+        block_on(lora.reset_stats()).unwrap();
+        assert_eq!(block_on(lora.get_stats()).unwrap(), RxStats::default());
+    }
+    #[test]
+    fn fsk_tx() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = new_fsk_params(&mut lora);
+        let mut tx_pkt_params = lora
+            .create_fsk_tx_packet_params(5, &FSK_SYNC_WORD, true, FskCrcType::Ccitt, true, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_fsk_tx(&mdltn_params, 14, false)).unwrap();
+        assert!(!sim.lora_mode());
+        block_on(lora.fsk_tx(&mdltn_params, &mut tx_pkt_params, b"fsk", 0xffffff)).unwrap();
+
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"fsk");
+        assert!(sim.mode() == RadioMode::Standby);
+        // the send operation is complete, so no send operation is left pending to refuse a receive operation
+        assert_eq!(block_on(lora.tx_complete()).err(), Some(RadioError::InvalidRadioMode));
+        let rx_pkt_params = lora
+            .create_fsk_rx_packet_params(5, &FSK_SYNC_WORD, true, 32, FskCrcType::Ccitt, true, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_fsk_rx(&mdltn_params, &rx_pkt_params, false, false, 1000)).unwrap();
+        assert!(sim.mode() == RadioMode::Receive);
+        block_on(lora.prepare_for_fsk_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(fsk_reg(&sim, FskRegister::RegBitrateMsb), 0x02);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegBitrateLsb), 0x80);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegFdevMsb), 0x01);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegFdevLsb), 0x99);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegRxBw), 0x0a);
+        assert_eq!(reg(&sim, Register::RegPaRamp), 0x20 | 0x09);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegPreambleLsb), 0x05);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegSyncConfig), 0x52);
+        assert_eq!(sim.register(FskRegister::RegSyncValue1 as u8 + 2), 0xc1);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegPacketConfig1), 0xd0);
+        assert_eq!(
+            fsk_reg(&sim, FskRegister::RegIrqFlags2) & FskIrqFlags2::PacketSent.value(),
+            0x00
+        );
+
+        // packets must fit in the FIFO, together with their length byte
+        assert_eq!(
+            block_on(lora.fsk_tx(&mdltn_params, &mut tx_pkt_params, &[0x00; 64], 0xffffff)).err(),
+            Some(RadioError::PayloadSizeUnexpected(64))
+        );
+        assert_eq!(
+            lora.create_fsk_rx_packet_params(5, &FSK_SYNC_WORD, false, 65, FskCrcType::None, false, &mdltn_params)
+                .err(),
+            Some(RadioError::PayloadSizeUnexpected(65))
+        );
+
+        // switching back to LoRa mode
+        let lora_mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let mut lora_tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &lora_mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&lora_mdltn_params, 14, false)).unwrap();
+        assert!(sim.lora_mode());
+        assert_eq!(reg(&sim, Register::RegSyncWord), 0x34);
+        block_on(lora.tx(&lora_mdltn_params, &mut lora_tx_pkt_params, b"lora", 0xffffff)).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"lora");
+    }
+
+    #[test]
+    fn fsk_rx() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = new_fsk_params(&mut lora);
+        let rx_pkt_params = lora
+            .create_fsk_rx_packet_params(5, &FSK_SYNC_WORD, true, 63, FskCrcType::Ccitt, true, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 64];
+
+        sim.receive_packet(b"hello");
+        sim.set_packet_status(180, 0);
+        block_on(lora.prepare_for_fsk_rx(&mdltn_params, &rx_pkt_params, false, false, 0)).unwrap();
+        assert!(sim.mode() == RadioMode::Receive);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegPayloadLength), 63);
+        let (len, pkt_status) = block_on(lora.fsk_rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"hello");
+        assert_eq!(pkt_status.rssi, -90);
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(
+            fsk_reg(&sim, FskRegister::RegIrqFlags2) & FskIrqFlags2::PayloadReady.value(),
+            0x00
+        );
+
+        // packets failing the CRC check are discarded by the chip
+        sim.receive_packet_with_crc_error(b"bad");
+        block_on(lora.prepare_for_fsk_rx(&mdltn_params, &rx_pkt_params, false, false, 0)).unwrap();
+        assert_eq!(
+            block_on(lora.fsk_rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::Irq)
+        );
+
+        // fixed length packets in continuous mode
+        let rx_pkt_params = lora
+            .create_fsk_rx_packet_params(5, &FSK_SYNC_WORD, false, 4, FskCrcType::None, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_fsk_rx(&mdltn_params, &rx_pkt_params, true, false, 0)).unwrap();
+        sim.receive_packet(b"one!");
+        assert_eq!(
+            block_on(lora.fsk_rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0,
+            4
+        );
+        sim.receive_packet(b"two!");
+        let (len, _) = block_on(lora.fsk_rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"two!");
+        assert!(sim.mode() == RadioMode::Receive);
+    }
+
+    #[test]
+    fn ook_modulation_params() {
+        let sim = SimSX1276_7_8_9::new();
+        let mut lora = new_lora(&sim);
+        assert_eq!(
+            lora.create_fsk_modulation_params(50_000, 0, PulseShape::Ook, 100_000, 868_800_000)
+                .err(),
+            Some(RadioError::UnavailableBitrate)
+        );
+        assert_eq!(
+            lora.create_fsk_modulation_params(4_800, 5_000, PulseShape::GaussianBt0_7, 100_000, 868_800_000)
+                .err(),
+            Some(RadioError::UnavailablePulseShape)
+        );
+        assert_eq!(
+            lora.create_fsk_modulation_params(250_000, 200_000, PulseShape::None, 250_000, 868_800_000)
+                .err(),
+            Some(RadioError::UnavailableFrequencyDeviation)
+        );
+
+        let mdltn_params = lora
+            .create_fsk_modulation_params(4_800, 0, PulseShape::OokCutoffBitrate, 100_000, 868_800_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_fsk_tx_packet_params(5, &FSK_SYNC_WORD, true, FskCrcType::Ibm, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_fsk_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(reg(&sim, Register::RegOpMode), FskMode::Standby.value(true));
+        assert_eq!(fsk_reg(&sim, FskRegister::RegRxBw), 0x09);
+        block_on(lora.fsk_tx(&mdltn_params, &mut tx_pkt_params, b"ook", 0xffffff)).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"ook");
+        assert_eq!(fsk_reg(&sim, FskRegister::RegPacketConfig1), 0x91);
+
+        // the unmodulated carrier is sent with FSK modulation and no deviation, rather than OOK
+        block_on(lora.start_continuous_wave(868_100_000, 14)).unwrap();
+        assert_eq!(reg(&sim, Register::RegOpMode), FskMode::Tx.value(false));
+        assert_eq!(fsk_reg(&sim, FskRegister::RegFdevMsb), 0x00);
+        assert_eq!(fsk_reg(&sim, FskRegister::RegFdevLsb), 0x00);
+        block_on(lora.stop_continuous_tx()).unwrap();
+        block_on(lora.prepare_for_fsk_tx(&mdltn_params, 14, false)).unwrap();
+        assert_eq!(reg(&sim, Register::RegOpMode), FskMode::Standby.value(true));
+    }
+
+    #[test]
+    fn sx1272() {
+        let sim = SimSX1276_7_8_9::new();
+        let radio_kind = SX1276_7_8_9::new(BoardType::GenericSx1272, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_62KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::UnavailableBandwidth)
+        );
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_250KHz, CodingRate::_4_6, 868_100_000)
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(8, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 20, true)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, b"sx1272", 0xffffff)).unwrap();
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), b"sx1272");
+        assert_eq!(reg(&sim, Register::RegModemConfig1), 0x52);
+        assert_eq!(reg(&sim, Register::RegModemConfig2) & 0xf0, 0x90);
+        assert_eq!(reg(&sim, Register::RegPaDacSx1272), PaDac::_20DbmOn.value());
+        assert_eq!(reg(&sim, Register::RegPaConfig), 0x8f);
+        assert_eq!(reg(&sim, Register::RegTcxoSx1272), 0x10);
+
+        block_on(lora.prepare_for_tx(&mdltn_params, 10, false)).unwrap();
+        assert_eq!(reg(&sim, Register::RegPaConfig), 0x0b);
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, -2, false)).err(),
+            Some(RadioError::InvalidOutputPower)
+        );
+
+        // low data rate optimization and implicit header
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_12, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, true, 4, false, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        assert_eq!(reg(&sim, Register::RegModemConfig1), 0x0d);
+
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, false, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+        sim.receive_packet(b"explicit");
+        sim.set_packet_status(100, 0);
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 10, 0)).unwrap();
+        assert_eq!(reg(&sim, Register::RegModemConfig1), 0x0b);
+        let (len, pkt_status) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"explicit");
+        // without the RSSI slope correction of the Sx1276/7/8/9
+        assert_eq!(pkt_status.rssi, -39);
+    }
+
+    #[test]
+    fn sx1273() {
+        let sim = SimSX1276_7_8_9::new();
+        let radio_kind = SX1276_7_8_9::new(BoardType::GenericSx1273, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap();
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_10, Bandwidth::_125KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::UnavailableSpreadingFactor)
+        );
+        assert!(lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_500KHz, CodingRate::_4_5, 868_100_000)
+            .is_ok());
+    }
+}
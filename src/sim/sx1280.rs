@@ -0,0 +1,804 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::spi::{ErrorType, SpiBus, SpiBusFlush, SpiBusRead, SpiBusWrite};
+
+use crate::mod_params::*;
+use crate::mod_traits::InterfaceVariant;
+use crate::sim::{SimPacket, BUFFER_SIZE};
+use crate::sx1280::radio_kind_params::{IrqMask, OpCode, PacketType, Register};
+
+const REGISTER_SPACE_SIZE: usize = 0x1000;
+const MAX_COMMAND_SIZE: usize = 2 + BUFFER_SIZE;
+
+// Internal frequency of the radio and the PLL step shift used to convert PLL steps to a frequency
+const SX1280_XTAL_FREQ: u64 = 52_000_000;
+const SX1280_PLL_STEP_SHIFT: u32 = 18;
+
+// Timeout count requesting continuous receive
+const RX_CONTINUOUS_TIMEOUT: u16 = 0xffff;
+
+const ALL_OP_CODES: [OpCode; 31] = [
+    OpCode::GetStatus,
+    OpCode::WriteRegister,
+    OpCode::ReadRegister,
+    OpCode::WriteBuffer,
+    OpCode::ReadBuffer,
+    OpCode::SetSleep,
+    OpCode::SetStandby,
+    OpCode::SetFS,
+    OpCode::SetTx,
+    OpCode::SetRx,
+    OpCode::SetRxDutyCycle,
+    OpCode::SetCAD,
+    OpCode::SetTxContinuousWave,
+    OpCode::SetTxContinuousPremable,
+    OpCode::SetPacketType,
+    OpCode::GetPacketType,
+    OpCode::SetRFFrequency,
+    OpCode::SetTxParams,
+    OpCode::SetCADParams,
+    OpCode::SetBufferBaseAddress,
+    OpCode::SetModulationParams,
+    OpCode::SetPacketParams,
+    OpCode::GetRxBufferStatus,
+    OpCode::GetPacketStatus,
+    OpCode::GetRSSIInst,
+    OpCode::CfgDIOIrq,
+    OpCode::GetIrqStatus,
+    OpCode::ClrIrqStatus,
+    OpCode::SetRegulatorMode,
+    OpCode::SetSaveContext,
+    OpCode::SetAutoFs,
+];
+
+// An operation started by a command, completed when the simulated chip raises the corresponding IRQ
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    None,
+    Tx,
+    Rx { timeout: u16 },
+    Cad,
+}
+
+struct State {
+    board_type: Option<BoardType>,
+    mode: RadioMode,
+    warm_start: bool,
+    nss_low: bool,
+    command: [u8; MAX_COMMAND_SIZE],
+    command_len: usize,
+    registers: [u8; REGISTER_SPACE_SIZE],
+    buffer: [u8; BUFFER_SIZE],
+    tx_base_addr: u8,
+    rx_base_addr: u8,
+    rx_payload_len: u8,
+    rx_start_addr: u8,
+    packet_type: u8,
+    pll_steps: u32,
+    tx_power: u8,
+    regulator_mode: u8,
+    cad_params: u8,
+    modulation_params: [u8; 3],
+    packet_params: [u8; 7],
+    irq_mask: u16,
+    dio1_mask: u16,
+    irq_status: u16,
+    operation: Operation,
+    rx_packet: Option<SimPacket>,
+    rx_crc_error: bool,
+    tx_packet: Option<SimPacket>,
+    channel_activity: bool,
+    packet_status: [u8; 2],
+}
+
+impl State {
+    fn new() -> Self {
+        let mut state = Self {
+            board_type: None,
+            mode: RadioMode::Standby,
+            warm_start: false,
+            nss_low: false,
+            command: [0x00u8; MAX_COMMAND_SIZE],
+            command_len: 0,
+            registers: [0x00u8; REGISTER_SPACE_SIZE],
+            buffer: [0x00u8; BUFFER_SIZE],
+            tx_base_addr: 0,
+            rx_base_addr: 0,
+            rx_payload_len: 0,
+            rx_start_addr: 0,
+            packet_type: PacketType::GFSK.value(),
+            pll_steps: 0,
+            tx_power: 0,
+            regulator_mode: 0,
+            cad_params: 0,
+            modulation_params: [0x00u8; 3],
+            packet_params: [0x00u8; 7],
+            irq_mask: IrqMask::None.value(),
+            dio1_mask: IrqMask::None.value(),
+            irq_status: IrqMask::None.value(),
+            operation: Operation::None,
+            rx_packet: None,
+            rx_crc_error: false,
+            tx_packet: None,
+            channel_activity: false,
+            packet_status: [0x00u8; 2],
+        };
+        state.reset();
+        state
+    }
+
+    // Restore the power-on configuration, keeping the packets and channel conditions supplied by a test
+    fn reset(&mut self) {
+        self.mode = RadioMode::Standby;
+        self.warm_start = false;
+        self.command_len = 0;
+        self.registers = [0x00u8; REGISTER_SPACE_SIZE];
+        self.write_registers(Register::LoRaSyncwordMsb as u16, &[0x14, 0x24]);
+        self.buffer = [0x00u8; BUFFER_SIZE];
+        self.tx_base_addr = 0;
+        self.rx_base_addr = 0;
+        self.rx_payload_len = 0;
+        self.rx_start_addr = 0;
+        self.packet_type = PacketType::GFSK.value();
+        self.pll_steps = 0;
+        self.tx_power = 0;
+        self.regulator_mode = 0;
+        self.cad_params = 0;
+        self.modulation_params = [0x00u8; 3];
+        self.packet_params = [0x00u8; 7];
+        self.irq_mask = IrqMask::None.value();
+        self.dio1_mask = IrqMask::None.value();
+        self.irq_status = IrqMask::None.value();
+        self.operation = Operation::None;
+    }
+
+    fn write_registers(&mut self, address: u16, data: &[u8]) {
+        for (i, byte) in data.iter().enumerate() {
+            self.registers[(address as usize + i) % REGISTER_SPACE_SIZE] = *byte;
+        }
+    }
+
+    fn register(&self, address: usize) -> u8 {
+        self.registers[address % REGISTER_SPACE_SIZE]
+    }
+
+    // The circuit mode is given in bits 7:5 of the status, the command status in bits 4:2
+    fn status(&self) -> u8 {
+        let circuit_mode = match self.mode {
+            RadioMode::Sleep | RadioMode::Standby => 0x02,
+            RadioMode::FrequencySynthesis => 0x04,
+            RadioMode::Transmit | RadioMode::ContinuousTransmit => 0x06,
+            RadioMode::Receive | RadioMode::ReceiveDutyCycle | RadioMode::ChannelActivityDetection => 0x05,
+        };
+        circuit_mode << 5
+    }
+
+    fn select(&mut self) {
+        if self.mode == RadioMode::Sleep {
+            // a falling edge on NSS wakes the chip up
+            if !self.warm_start {
+                self.reset();
+            }
+            self.mode = RadioMode::Standby;
+        }
+        self.nss_low = true;
+        self.command_len = 0;
+    }
+
+    fn deselect(&mut self) {
+        if self.nss_low {
+            self.nss_low = false;
+            self.execute();
+        }
+    }
+
+    // Shift one byte into the chip, returning the byte shifted out
+    fn exchange(&mut self, mosi: u8) -> u8 {
+        let index = self.command_len;
+        if index < MAX_COMMAND_SIZE {
+            self.command[index] = mosi;
+        }
+        self.command_len += 1;
+        if index == 0 {
+            return self.status();
+        }
+
+        match decode(self.command[0]) {
+            Some(OpCode::ReadRegister) => match index {
+                1 | 2 => 0x00,
+                3 => self.status(),
+                _ => {
+                    let address = ((self.command[1] as usize) << 8) | (self.command[2] as usize);
+                    self.register(address + index - 4)
+                }
+            },
+            Some(OpCode::ReadBuffer) => match index {
+                1 => 0x00,
+                2 => self.status(),
+                _ => self.buffer[(self.command[1] as usize + index - 3) % BUFFER_SIZE],
+            },
+            Some(op_code) if index >= 2 => self.response(op_code).get(index - 2).copied().unwrap_or(0x00),
+            _ => self.status(),
+        }
+    }
+
+    // Data returned by a get command, following the status byte
+    fn response(&self, op_code: OpCode) -> [u8; 5] {
+        match op_code {
+            OpCode::GetIrqStatus => [(self.irq_status >> 8) as u8, self.irq_status as u8, 0, 0, 0],
+            OpCode::GetRxBufferStatus => [self.rx_payload_len, self.rx_start_addr, 0, 0, 0],
+            // LoRa: RssiSync, Snr
+            OpCode::GetPacketStatus => [self.packet_status[0], self.packet_status[1], 0, 0, 0],
+            OpCode::GetRSSIInst => [self.packet_status[0], 0, 0, 0, 0],
+            OpCode::GetPacketType => [self.packet_type, 0, 0, 0, 0],
+            _ => [0x00u8; 5],
+        }
+    }
+
+    // Execute a command once NSS is released
+    fn execute(&mut self) {
+        let len = core::cmp::min(self.command_len, MAX_COMMAND_SIZE);
+        if len == 0 {
+            return;
+        }
+        let command = self.command;
+        let params = &command[1..len];
+        let param = |i: usize| params.get(i).copied().unwrap_or(0x00);
+        // period base, then a 16 bit count of periods
+        let timeout = ((param(1) as u16) << 8) | (param(2) as u16);
+
+        match decode(command[0]) {
+            Some(OpCode::WriteRegister) if params.len() > 2 => {
+                let address = ((param(0) as u16) << 8) | (param(1) as u16);
+                self.write_registers(address, &params[2..]);
+            }
+            Some(OpCode::WriteBuffer) if !params.is_empty() => {
+                for (i, byte) in params[1..].iter().enumerate() {
+                    self.buffer[(param(0) as usize + i) % BUFFER_SIZE] = *byte;
+                }
+            }
+            Some(OpCode::SetSleep) => {
+                self.warm_start = (param(0) & 0x01) != 0;
+                self.mode = RadioMode::Sleep;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetStandby) => {
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetFS) => self.mode = RadioMode::FrequencySynthesis,
+            Some(OpCode::SetTx) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::Tx;
+            }
+            Some(OpCode::SetRx) => {
+                self.mode = RadioMode::Receive;
+                self.operation = Operation::Rx { timeout };
+            }
+            Some(OpCode::SetRxDutyCycle) => {
+                self.mode = RadioMode::ReceiveDutyCycle;
+                self.operation = Operation::Rx { timeout: 0 };
+            }
+            Some(OpCode::SetCAD) => {
+                self.mode = RadioMode::ChannelActivityDetection;
+                self.operation = Operation::Cad;
+            }
+            Some(OpCode::SetTxContinuousWave) | Some(OpCode::SetTxContinuousPremable) => {
+                self.mode = RadioMode::Transmit;
+                self.operation = Operation::None;
+            }
+            Some(OpCode::SetPacketType) => self.packet_type = param(0),
+            Some(OpCode::SetRFFrequency) => {
+                self.pll_steps = u32::from_be_bytes([0x00, param(0), param(1), param(2)]);
+            }
+            Some(OpCode::SetTxParams) => self.tx_power = param(0),
+            Some(OpCode::SetRegulatorMode) => self.regulator_mode = param(0),
+            Some(OpCode::SetCADParams) => self.cad_params = param(0),
+            Some(OpCode::SetBufferBaseAddress) => {
+                self.tx_base_addr = param(0);
+                self.rx_base_addr = param(1);
+            }
+            Some(OpCode::SetModulationParams) => {
+                for (i, byte) in self.modulation_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+            }
+            Some(OpCode::SetPacketParams) => {
+                for (i, byte) in self.packet_params.iter_mut().enumerate() {
+                    *byte = param(i);
+                }
+                if self.packet_type == PacketType::LoRa.value() {
+                    self.write_registers(Register::PayloadLength as u16, &[param(2)]);
+                }
+            }
+            Some(OpCode::CfgDIOIrq) => {
+                self.irq_mask = ((param(0) as u16) << 8) | (param(1) as u16);
+                self.dio1_mask = ((param(2) as u16) << 8) | (param(3) as u16);
+            }
+            Some(OpCode::ClrIrqStatus) => {
+                self.irq_status &= !(((param(0) as u16) << 8) | (param(1) as u16));
+            }
+            _ => {}
+        }
+    }
+
+    fn raise_irq(&mut self, irq: u16) {
+        self.irq_status |= irq & self.irq_mask;
+    }
+
+    fn dio1(&self) -> bool {
+        (self.irq_status & self.dio1_mask) != 0
+    }
+
+    // Complete the pending operation, as the chip would once the corresponding on-air event occurs
+    fn complete_operation(&mut self) {
+        match self.operation {
+            Operation::None => {}
+            Operation::Tx => {
+                let payload_len = self.packet_params[2] as usize;
+                let mut payload = [0x00u8; BUFFER_SIZE];
+                for (i, byte) in payload.iter_mut().take(payload_len).enumerate() {
+                    *byte = self.buffer[(self.tx_base_addr as usize + i) % BUFFER_SIZE];
+                }
+                self.tx_packet = Some(SimPacket::new(&payload[..payload_len]));
+                self.raise_irq(IrqMask::TxDone.value());
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+            Operation::Rx { timeout } => {
+                let continuous = timeout == RX_CONTINUOUS_TIMEOUT;
+                if let Some(packet) = self.rx_packet.take() {
+                    let implicit_header = self.packet_params[1] == 0x80;
+                    let payload_len = match implicit_header {
+                        true => self.packet_params[2] as usize,
+                        false => packet.len,
+                    };
+                    for (i, byte) in packet.data.iter().take(payload_len).enumerate() {
+                        self.buffer[(self.rx_base_addr as usize + i) % BUFFER_SIZE] = *byte;
+                    }
+                    self.rx_payload_len = payload_len as u8;
+                    self.rx_start_addr = self.rx_base_addr;
+
+                    let mut irq =
+                        IrqMask::PreambleDetected.value() | IrqMask::SyncwordValid.value() | IrqMask::RxDone.value();
+                    if !implicit_header {
+                        irq |= IrqMask::HeaderValid.value();
+                    }
+                    if self.rx_crc_error {
+                        irq |= IrqMask::CRCError.value();
+                    }
+                    self.raise_irq(irq);
+                } else if timeout != 0 && !continuous {
+                    self.raise_irq(IrqMask::RxTxTimeout.value());
+                } else {
+                    return;
+                }
+                if !continuous {
+                    self.mode = RadioMode::Standby;
+                    self.operation = Operation::None;
+                }
+            }
+            Operation::Cad => {
+                let mut irq = IrqMask::CADDone.value();
+                if self.channel_activity {
+                    irq |= IrqMask::CADActivityDetected.value();
+                }
+                self.raise_irq(irq);
+                self.mode = RadioMode::Standby;
+                self.operation = Operation::None;
+            }
+        }
+    }
+}
+
+fn decode(op_code: u8) -> Option<OpCode> {
+    ALL_OP_CODES.into_iter().find(|o| o.value() == op_code)
+}
+
+/// A simulated Sx1280/1 chip, decoding the command stream sent over its SPI bus and raising IRQs on DIO1 as
+/// operations complete.  Operations complete as soon as the radio kind waits on DIO1.
+pub struct SimSX1280 {
+    state: RefCell<State>,
+}
+
+impl Default for SimSX1280 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimSX1280 {
+    /// Create a simulated chip in its power-on state
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(State::new()),
+        }
+    }
+
+    /// Get the SPI bus connected to the simulated chip
+    pub fn spi(&self) -> SimSpi<'_> {
+        SimSpi { state: &self.state }
+    }
+
+    /// Get the interface variant (NSS, reset, BUSY, DIO1, and RF switch lines) connected to the simulated chip
+    pub fn iv(&self) -> SimInterfaceVariant<'_> {
+        SimInterfaceVariant { state: &self.state }
+    }
+
+    /// Provide a packet for the next receive operation
+    pub fn receive_packet(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = false;
+    }
+
+    /// Provide a packet with an invalid CRC for the next receive operation
+    pub fn receive_packet_with_crc_error(&self, payload: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.rx_packet = Some(SimPacket::new(payload));
+        state.rx_crc_error = true;
+    }
+
+    /// Set the signal conditions reported for received packets: the RSSI at syncword detection (dBm) and the SNR (dB)
+    pub fn set_packet_status(&self, rssi_sync: i16, snr: i16) {
+        let mut state = self.state.borrow_mut();
+        state.packet_status = [(-rssi_sync * 2) as u8, (snr * 4) as u8];
+    }
+
+    /// Set whether channel activity detection operations detect activity
+    pub fn set_channel_activity(&self, detected: bool) {
+        self.state.borrow_mut().channel_activity = detected;
+    }
+
+    /// Take the packet sent by the last send operation
+    pub fn take_transmitted_packet(&self) -> Option<SimPacket> {
+        self.state.borrow_mut().tx_packet.take()
+    }
+
+    /// Get the board type set through the interface variant
+    pub fn board_type(&self) -> Option<BoardType> {
+        self.state.borrow().board_type
+    }
+
+    /// Get the operating mode of the chip
+    pub fn mode(&self) -> RadioMode {
+        self.state.borrow().mode
+    }
+
+    /// Get whether the chip will retain its configuration when woken up from sleep
+    pub fn warm_start(&self) -> bool {
+        self.state.borrow().warm_start
+    }
+
+    /// Get the channel frequency set on the chip, rounded to the nearest Hz
+    pub fn frequency_in_hz(&self) -> u32 {
+        let frequency = self.state.borrow().pll_steps as u64 * SX1280_XTAL_FREQ;
+        ((frequency + (1 << (SX1280_PLL_STEP_SHIFT - 1))) >> SX1280_PLL_STEP_SHIFT) as u32
+    }
+
+    /// Get the packet type (0x01 for LoRa) set on the chip
+    pub fn packet_type(&self) -> u8 {
+        self.state.borrow().packet_type
+    }
+
+    /// Get the parameter of the last SetRegulatorMode command (0x01 for DC-DC)
+    pub fn regulator_mode(&self) -> u8 {
+        self.state.borrow().regulator_mode
+    }
+
+    /// Get the parameter of the last SetCadParams command
+    pub fn cad_params(&self) -> u8 {
+        self.state.borrow().cad_params
+    }
+
+    /// Get the parameters of the last SetModulationParams command
+    pub fn modulation_params(&self) -> [u8; 3] {
+        self.state.borrow().modulation_params
+    }
+
+    /// Get the parameters of the last SetPacketParams command
+    pub fn packet_params(&self) -> [u8; 7] {
+        self.state.borrow().packet_params
+    }
+
+    /// Get the power parameter of the last SetTxParams command, as an offset from -18 dBm
+    pub fn tx_power(&self) -> u8 {
+        self.state.borrow().tx_power
+    }
+
+    /// Get the value of a register
+    pub fn register(&self, address: u16) -> u8 {
+        self.state.borrow().register(address as usize)
+    }
+}
+
+/// The SPI bus of a simulated Sx1280/1 chip
+pub struct SimSpi<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl ErrorType for SimSpi<'_> {
+    type Error = Infallible;
+}
+
+impl SpiBusFlush for SimSpi<'_> {
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl SpiBusRead<u8> for SimSpi<'_> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(0x00u8);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBusWrite<u8> for SimSpi<'_> {
+    async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+impl SpiBus<u8> for SimSpi<'_> {
+    async fn transfer<'b>(&'b mut self, read: &'b mut [u8], write: &'b [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for i in 0..core::cmp::max(read.len(), write.len()) {
+            let miso = state.exchange(write.get(i).copied().unwrap_or(0x00u8));
+            if let Some(word) = read.get_mut(i) {
+                *word = miso;
+            }
+        }
+        Ok(())
+    }
+
+    async fn transfer_in_place<'b>(&'b mut self, words: &'b mut [u8]) -> Result<(), Infallible> {
+        let mut state = self.state.borrow_mut();
+        for word in words {
+            *word = state.exchange(*word);
+        }
+        Ok(())
+    }
+}
+
+/// The interface variant of a simulated Sx1280/1 chip.  BUSY is never asserted, and waiting on DIO1 completes the
+/// pending operation; it fails with [`RadioError::Irq`] if DIO1 would never rise.
+pub struct SimInterfaceVariant<'a> {
+    state: &'a RefCell<State>,
+}
+
+impl InterfaceVariant for SimInterfaceVariant<'_> {
+    fn set_board_type(&mut self, board_type: BoardType) {
+        self.state.borrow_mut().board_type = Some(board_type);
+    }
+
+    async fn set_nss_low(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().select();
+        Ok(())
+    }
+
+    async fn set_nss_high(&mut self) -> Result<(), RadioError> {
+        self.state.borrow_mut().deselect();
+        Ok(())
+    }
+
+    async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        self.state.borrow_mut().reset();
+        delay.delay_ms(10).await;
+        Ok(())
+    }
+
+    async fn wait_on_busy(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn await_irq(&mut self) -> Result<(), RadioError> {
+        let mut state = self.state.borrow_mut();
+        if !state.dio1() {
+            state.complete_operation();
+        }
+        match state.dio1() {
+            true => Ok(()),
+            false => Err(RadioError::Irq),
+        }
+    }
+
+    async fn enable_rf_switch_rx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn enable_rf_switch_tx(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn disable_rf_switch(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{block_on, SimDelay};
+    use crate::sx1280::SX1280;
+    use crate::LoRa;
+
+    fn new_lora(sim: &SimSX1280) -> LoRa<SX1280<SimSpi<'_>, SimInterfaceVariant<'_>>> {
+        let radio_kind = SX1280::new(BoardType::GenericSx1280, sim.spi(), sim.iv());
+        block_on(LoRa::new(radio_kind, true, &mut SimDelay::default())).unwrap()
+    }
+
+    #[test]
+    fn init() {
+        let sim = SimSX1280::new();
+        let _lora = new_lora(&sim);
+        assert!(sim.board_type() == Some(BoardType::GenericSx1280));
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.packet_type(), PacketType::LoRa.value());
+        assert_eq!(sim.regulator_mode(), 0x01);
+        // the public syncword 0x34 split across the upper nibbles of the two registers
+        assert_eq!(sim.register(Register::LoRaSyncwordMsb as u16), 0x34);
+        assert_eq!(sim.register(Register::LoRaSyncwordLsb as u16), 0x44);
+    }
+
+    #[test]
+    fn tx() {
+        let sim = SimSX1280::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(
+                SpreadingFactor::_7,
+                Bandwidth::_812KHz,
+                CodingRate::_4_5Li,
+                2_425_000_000,
+            )
+            .unwrap();
+        let mut tx_pkt_params = lora
+            .create_tx_packet_params(20, false, true, false, &mdltn_params)
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 13, false)).unwrap();
+        block_on(lora.tx(&mdltn_params, &mut tx_pkt_params, &[0x01, 0x02, 0x03], 1000)).unwrap();
+
+        assert_eq!(sim.take_transmitted_packet().unwrap().payload(), &[0x01, 0x02, 0x03]);
+        assert!(sim.mode() == RadioMode::Standby);
+        // within half of the 198 Hz PLL step
+        assert!(sim.frequency_in_hz().abs_diff(2_425_000_000) <= 100);
+        assert_eq!(sim.tx_power(), 31);
+        assert_eq!(sim.modulation_params(), [0x70, 0x18, 0x05]);
+        assert_eq!(sim.register(Register::SfAdditionalConfiguration as u16), 0x37);
+        assert_eq!(sim.register(Register::FrequencyErrorCorrection as u16), 0x01);
+        // preamble of 10 * 2^1 symbols, explicit header, 3 bytes, CRC, standard IQ
+        assert_eq!(sim.packet_params()[..5], [0x1a, 0x00, 0x03, 0x20, 0x40]);
+
+        assert_eq!(
+            block_on(lora.prepare_for_tx(&mdltn_params, 14, false)).err(),
+            Some(RadioError::InvalidOutputPower)
+        );
+    }
+
+    #[test]
+    fn rx() {
+        let sim = SimSX1280::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_203KHz, CodingRate::_4_8, 2_450_000_000)
+            .unwrap();
+        let rx_pkt_params = lora
+            .create_rx_packet_params(8, false, 255, true, true, &mdltn_params)
+            .unwrap();
+        let mut receiving_buffer = [0x00u8; 255];
+
+        sim.receive_packet(b"hello");
+        sim.set_packet_status(-80, 7);
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, true, 0, 1000)).unwrap();
+        let (len, pkt_status) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(&receiving_buffer[..len as usize], b"hello");
+        assert_eq!(pkt_status.rssi, -80);
+        assert_eq!(pkt_status.snr, 7);
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.register(Register::LnaRegime as u16) & 0xc0, 0xc0);
+        assert_eq!(sim.register(Register::SfAdditionalConfiguration as u16), 0x32);
+        // inverted IQ
+        assert_eq!(sim.packet_params()[4], 0x00);
+
+        // below the noise floor, the RSSI of the signal is corrected by the SNR
+        sim.receive_packet(b"faint");
+        sim.set_packet_status(-110, -6);
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 0, 1000)).unwrap();
+        let (_, pkt_status) = block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap();
+        assert_eq!(pkt_status.rssi, -116);
+        assert_eq!(pkt_status.snr, -6);
+        assert_eq!(sim.register(Register::LnaRegime as u16) & 0xc0, 0x00);
+
+        // nothing received before the timeout
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, false, false, 0, 1000)).unwrap();
+        assert_eq!(
+            block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).err(),
+            Some(RadioError::ReceiveTimeout)
+        );
+
+        // in continuous mode, the radio keeps receiving packets
+        block_on(lora.prepare_for_rx(&mdltn_params, &rx_pkt_params, None, true, false, 0, 0)).unwrap();
+        sim.receive_packet(b"one");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 3);
+        sim.receive_packet(b"three");
+        assert_eq!(block_on(lora.rx(&rx_pkt_params, &mut receiving_buffer)).unwrap().0, 5);
+        assert!(sim.mode() == RadioMode::Receive);
+    }
+
+    #[test]
+    fn cad() {
+        let sim = SimSX1280::new();
+        let mut lora = new_lora(&sim);
+        let mdltn_params = lora
+            .create_modulation_params(SpreadingFactor::_9, Bandwidth::_406KHz, CodingRate::_4_5, 2_450_000_000)
+            .unwrap();
+
+        let cad_params = CadParams::recommended_for(SpreadingFactor::_9);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(sim.mode() == RadioMode::ChannelActivityDetection);
+        assert_eq!(sim.cad_params(), 0x40);
+        assert!(!block_on(lora.cad()).unwrap());
+
+        sim.set_channel_activity(true);
+        block_on(lora.prepare_for_cad(&mdltn_params, &cad_params, false)).unwrap();
+        assert!(block_on(lora.cad()).unwrap());
+    }
+
+    #[test]
+    fn sleep_and_warm_start() {
+        let sim = SimSX1280::new();
+        let mut lora = new_lora(&sim);
+        let mut delay = SimDelay::default();
+        block_on(lora.sleep(&mut delay)).unwrap();
+        assert!(sim.mode() == RadioMode::Sleep);
+        assert!(sim.warm_start());
+        assert_eq!(delay.elapsed_in_us, 2000);
+
+        // waking up for the next operation retains the configuration
+        let mdltn_params = lora
+            .create_modulation_params(
+                SpreadingFactor::_7,
+                Bandwidth::_1625KHz,
+                CodingRate::_4_5,
+                2_450_000_000,
+            )
+            .unwrap();
+        block_on(lora.prepare_for_tx(&mdltn_params, 10, false)).unwrap();
+        assert!(sim.mode() == RadioMode::Standby);
+        assert_eq!(sim.register(Register::LoRaSyncwordMsb as u16), 0x34);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let sim = SimSX1280::new();
+        let radio_kind = SX1280::new(BoardType::GenericSx1281, sim.spi(), sim.iv());
+        let mut lora = block_on(LoRa::new(radio_kind, false, &mut SimDelay::default())).unwrap();
+        assert_eq!(sim.register(Register::LoRaSyncwordMsb as u16), 0x14);
+        assert_eq!(sim.register(Register::LoRaSyncwordLsb as u16), 0x24);
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_812KHz, CodingRate::_4_5, 868_100_000)
+                .err(),
+            Some(RadioError::InvalidFrequency)
+        );
+        assert_eq!(
+            lora.create_modulation_params(SpreadingFactor::_7, Bandwidth::_125KHz, CodingRate::_4_5, 2_450_000_000)
+                .err(),
+            Some(RadioError::UnavailableBandwidth)
+        );
+        assert_eq!(
+            lora.create_fsk_modulation_params(50_000, 25_000, PulseShape::None, 100_000, 2_450_000_000)
+                .err(),
+            Some(RadioError::FskUnsupported)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-mod radio_kind_params;
+pub(crate) mod radio_kind_params;
 
 use defmt::debug;
 use embedded_hal_async::delay::DelayUs;
@@ -8,10 +8,6 @@ use radio_kind_params::*;
 use crate::mod_params::*;
 use crate::{InterfaceVariant, RadioKind, SpiInterface};
 
-// Syncwords for public and private networks
-const LORA_MAC_PUBLIC_SYNCWORD: u16 = 0x3444; // corresponds to sx127x 0x34
-const LORA_MAC_PRIVATE_SYNCWORD: u16 = 0x1424; // corresponds to sx127x 0x12
-
 // Maximum number of registers that can be added to the retention list
 const MAX_NUMBER_REGS_IN_RETENTION: u8 = 4;
 
@@ -27,8 +23,8 @@ const SX126X_PLL_STEP_SCALED: u32 = SX126X_XTAL_FREQ >> (25 - SX126X_PLL_STEP_SH
 // Maximum value for parameter symbNum
 const SX126X_MAX_LORA_SYMB_NUM_TIMEOUT: u8 = 248;
 
-// Time required for the TCXO to wakeup [ms].
-const BRD_TCXO_WAKEUP_TIME: u32 = 10;
+// Frequencies supported by the Sx1268 (Hz)
+const SX1268_FREQUENCY_RANGE_IN_HZ: core::ops::RangeInclusive<u32> = 410_000_000..=810_000_000;
 
 impl ModulationParams {
     /// Create modulation parameters specific to the LoRa chip kind and type
@@ -61,6 +57,39 @@ impl ModulationParams {
             frequency_in_hz,
         })
     }
+
+    /// Create modulation parameters specific to the Llcc68 chip, which supports a subset of the Sx1262 spreading factor
+    /// and bandwidth combinations (SF5 to SF9 at 125 kHz, SF5 to SF10 at 250 kHz, and SF5 to SF11 at 500 kHz)
+    pub fn new_for_llcc68(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        let max_spreading_factor = match bandwidth {
+            Bandwidth::_125KHz => SpreadingFactor::_9,
+            Bandwidth::_250KHz => SpreadingFactor::_10,
+            Bandwidth::_500KHz => SpreadingFactor::_11,
+            _ => return Err(RadioError::UnavailableBandwidth),
+        };
+        if spreading_factor_value(spreading_factor)? > spreading_factor_value(max_spreading_factor)? {
+            return Err(RadioError::UnavailableSpreadingFactor);
+        }
+        Self::new_for_sx1261_2(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+    }
+
+    /// Create modulation parameters specific to the Sx1268 chip, which supports frequencies from 410 to 810 MHz
+    pub fn new_for_sx1268(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        if !SX1268_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            return Err(RadioError::InvalidFrequency);
+        }
+        Self::new_for_sx1261_2(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+    }
 }
 
 impl PacketParams {
@@ -90,10 +119,85 @@ impl PacketParams {
     }
 }
 
+impl FskModulationParams {
+    /// Create FSK modulation parameters specific to the LoRa chip kind and type
+    pub fn new_for_sx1261_2(
+        bitrate_in_bps: u32,
+        frequency_deviation_in_hz: u32,
+        pulse_shape: PulseShape,
+        rx_bandwidth_in_hz: u32,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        // Parameter validation
+        if !(600..=300_000).contains(&bitrate_in_bps) {
+            return Err(RadioError::UnavailableBitrate);
+        }
+        if !(600..=200_000).contains(&frequency_deviation_in_hz) {
+            return Err(RadioError::UnavailableFrequencyDeviation);
+        }
+        pulse_shape_value(pulse_shape)?;
+
+        Ok(Self {
+            bitrate_in_bps,
+            frequency_deviation_in_hz,
+            pulse_shape,
+            rx_bandwidth_in_hz: supported_rx_bandwidth_in_hz(rx_bandwidth_in_hz)?,
+            frequency_in_hz,
+        })
+    }
+
+    /// Create FSK modulation parameters specific to the Sx1268 chip, which supports frequencies from 410 to 810 MHz
+    pub fn new_for_sx1268(
+        bitrate_in_bps: u32,
+        frequency_deviation_in_hz: u32,
+        pulse_shape: PulseShape,
+        rx_bandwidth_in_hz: u32,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        if !SX1268_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            return Err(RadioError::InvalidFrequency);
+        }
+        Self::new_for_sx1261_2(
+            bitrate_in_bps,
+            frequency_deviation_in_hz,
+            pulse_shape,
+            rx_bandwidth_in_hz,
+            frequency_in_hz,
+        )
+    }
+}
+
+impl FskPacketParams {
+    /// Create FSK packet parameters specific to the LoRa chip kind and type
+    pub fn new_for_sx1261_2(
+        preamble_length: u16,
+        sync_word: &[u8],
+        variable_length: bool,
+        payload_length: u8,
+        crc_type: FskCrcType,
+        whitening: bool,
+        _modulation_params: &FskModulationParams,
+    ) -> Result<Self, RadioError> {
+        // preamble lengths are given to the chip in bits
+        if preamble_length > (u16::MAX >> 3) {
+            return Err(RadioError::InvalidPreambleLength);
+        }
+        Self::new(
+            preamble_length,
+            sync_word,
+            variable_length,
+            payload_length,
+            crc_type,
+            whitening,
+        )
+    }
+}
+
 /// Base for the RadioKind implementation for the LoRa chip kind and board type
 pub struct SX1261_2<SPI, IV> {
-    board_type: BoardType,
+    board_config: BoardConfig,
     intf: SpiInterface<SPI, IV>,
+    packet_type: PacketType,
 }
 
 impl<SPI, IV> SX1261_2<SPI, IV>
@@ -101,11 +205,17 @@ where
     SPI: SpiBus<u8>,
     IV: InterfaceVariant,
 {
-    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board type
-    pub fn new(board_type: BoardType, spi: SPI, mut iv: IV) -> Self {
-        iv.set_board_type(board_type);
+    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board configuration, given as a
+    /// BoardConfig or as the BoardType of a preset board
+    pub fn new(board_config: impl Into<BoardConfig>, spi: SPI, mut iv: IV) -> Self {
+        let board_config = board_config.into();
+        iv.set_board_type(board_config.board_type);
         let intf = SpiInterface::new(spi, iv);
-        Self { board_type, intf }
+        Self {
+            board_config,
+            intf,
+            packet_type: PacketType::None,
+        }
     }
 
     // Utility functions
@@ -191,6 +301,20 @@ where
         self.intf.write(&[&op_code_and_pa_config], false).await
     }
 
+    // Set the GFSK CRC initial value and polynomial
+    async fn set_crc_registers(&mut self, initial_value: u16, polynomial: u16) -> Result<(), RadioError> {
+        let register_and_crc_registers = [
+            OpCode::WriteRegister.value(),
+            Register::CrcInitialValue.addr1(),
+            Register::CrcInitialValue.addr2(),
+            ((initial_value >> 8) & 0xFF) as u8,
+            (initial_value & 0xFF) as u8,
+            ((polynomial >> 8) & 0xFF) as u8,
+            (polynomial & 0xFF) as u8,
+        ];
+        self.intf.write(&[&register_and_crc_registers], false).await
+    }
+
     fn timeout_1(timeout: u32) -> u8 {
         ((timeout >> 16) & 0xFF) as u8
     }
@@ -217,7 +341,7 @@ where
     IV: InterfaceVariant,
 {
     fn get_board_type(&self) -> BoardType {
-        self.board_type
+        self.board_config.board_type
     }
 
     async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
@@ -235,9 +359,9 @@ where
         Ok(())
     }
 
-    // Use DIO2 to control an RF Switch, depending on the board type.
+    // Use DIO2 to control an RF Switch, depending on the board configuration.
     async fn init_rf_switch(&mut self) -> Result<(), RadioError> {
-        if self.board_type != BoardType::Stm32wlSx1262 {
+        if self.board_config.dio2_rf_switch {
             let op_code_and_indicator = [OpCode::SetRFSwitchMode.value(), true as u8];
             self.intf.write(&[&op_code_and_indicator], false).await?;
         }
@@ -265,48 +389,40 @@ where
         Ok(sleep_params.warm_start) // indicate if warm start enabled
     }
 
-    /// Configure the radio for LoRa and a public/private network.
-    async fn set_lora_modem(&mut self, enable_public_network: bool) -> Result<(), RadioError> {
+    /// Configure the radio for LoRa and the given sync word.
+    async fn set_lora_modem(&mut self, sync_word: SyncWord) -> Result<(), RadioError> {
         let op_code_and_packet_type = [OpCode::SetPacketType.value(), PacketType::LoRa.value()];
         self.intf.write(&[&op_code_and_packet_type], false).await?;
-        if enable_public_network {
-            let register_and_syncword = [
-                OpCode::WriteRegister.value(),
-                Register::LoRaSyncword.addr1(),
-                Register::LoRaSyncword.addr2(),
-                ((LORA_MAC_PUBLIC_SYNCWORD >> 8) & 0xFF) as u8,
-                (LORA_MAC_PUBLIC_SYNCWORD & 0xFF) as u8,
-            ];
-            self.intf.write(&[&register_and_syncword], false).await?;
-        } else {
-            let register_and_syncword = [
-                OpCode::WriteRegister.value(),
-                Register::LoRaSyncword.addr1(),
-                Register::LoRaSyncword.addr2(),
-                ((LORA_MAC_PRIVATE_SYNCWORD >> 8) & 0xFF) as u8,
-                (LORA_MAC_PRIVATE_SYNCWORD & 0xFF) as u8,
-            ];
-            self.intf.write(&[&register_and_syncword], false).await?;
-        }
+        self.packet_type = PacketType::LoRa;
+        let syncword = sync_word.sx126x_value();
+        let register_and_syncword = [
+            OpCode::WriteRegister.value(),
+            Register::LoRaSyncword.addr1(),
+            Register::LoRaSyncword.addr2(),
+            ((syncword >> 8) & 0xFF) as u8,
+            (syncword & 0xFF) as u8,
+        ];
+        self.intf.write(&[&register_and_syncword], false).await
+    }
 
+    /// Configure the radio for GFSK.
+    async fn set_fsk_modem(&mut self) -> Result<(), RadioError> {
+        let op_code_and_packet_type = [OpCode::SetPacketType.value(), PacketType::GFSK.value()];
+        self.intf.write(&[&op_code_and_packet_type], false).await?;
+        self.packet_type = PacketType::GFSK;
         Ok(())
     }
 
     async fn set_oscillator(&mut self) -> Result<(), RadioError> {
         // voltage used to control the TCXO on/off from DIO3
-        let voltage = match self.board_type {
-            BoardType::GenericSx1261
-            | BoardType::RpPicoWaveshareSx1262
-            | BoardType::Rak4631Sx1262
-            | BoardType::Stm32l0Sx1276
-            | BoardType::Stm32wlSx1262 => TcxoCtrlVoltage::Ctrl1V7,
-            BoardType::HeltecWifiLoraV31262 => TcxoCtrlVoltage::Ctrl1V8,
-            BoardType::Rak3172Sx1262 => {
-                // uses XTAL instead of TXCO
-                return Ok(());
-            }
+        let (voltage, startup_time_in_ms) = match self.board_config.oscillator {
+            Oscillator::Tcxo {
+                voltage,
+                startup_time_in_ms,
+            } => (TcxoCtrlVoltage::from(voltage), startup_time_in_ms),
+            Oscillator::Xtal => return Ok(()),
         };
-        let timeout = BRD_TCXO_WAKEUP_TIME << 6; // duration allowed for TCXO to reach 32MHz
+        let timeout = startup_time_in_ms << 6; // duration allowed for TCXO to reach 32MHz
         let op_code_and_tcxo_control = [
             OpCode::SetTCXOMode.value(),
             voltage.value() & 0x07,
@@ -317,9 +433,14 @@ where
         self.intf.write(&[&op_code_and_tcxo_control], false).await
     }
 
-    // Set the power regulators operating mode to DC_DC.  Using only LDO implies that the Rx/Tx current is doubled.
+    // Set the power regulators operating mode to DC_DC if the board allows it.  Using only LDO implies that the Rx/Tx
+    // current is doubled.
     async fn set_regulator_mode(&mut self) -> Result<(), RadioError> {
-        let op_code_and_regulator_mode = [OpCode::SetRegulatorMode.value(), RegulatorMode::UseDCDC.value()];
+        let regulator_mode = match self.board_config.regulator {
+            Regulator::Ldo => RegulatorMode::UseLDO,
+            Regulator::DcDc => RegulatorMode::UseDCDC,
+        };
+        let op_code_and_regulator_mode = [OpCode::SetRegulatorMode.value(), regulator_mode.value()];
         self.intf.write(&[&op_code_and_regulator_mode], false).await
     }
 
@@ -342,13 +463,13 @@ where
     // Set parameters associated with power for a send operation. Currently, over current protection (OCP) uses the default set automatically after set_pa_config()
     //   output_power            desired RF output power (dBm)
     //   mdltn_params            needed for a power vs channel frequency validation
-    //   tx_boosted_if_possible  not pertinent for sx126x
+    //   tx_boosted_if_possible  use the high power PA, if the board has both PAs wired
     //   is_tx_prep              indicates which ramp up time to use
     async fn set_tx_power_and_ramp_time(
         &mut self,
         output_power: i32,
         mdltn_params: Option<&ModulationParams>,
-        _tx_boosted_if_possible: bool,
+        tx_boosted_if_possible: bool,
         is_tx_prep: bool,
     ) -> Result<(), RadioError> {
         let tx_params_power;
@@ -357,8 +478,16 @@ where
             false => RampTime::Ramp200Us, // for instance, on initialization
         };
 
-        let chip_type: ChipType = self.board_type.into();
-        if chip_type == ChipType::Sx1261 {
+        if output_power > self.board_config.max_tx_power {
+            return Err(RadioError::InvalidOutputPower);
+        }
+        let low_power_pa = match self.board_config.pa_wiring {
+            PaWiring::LowPower => true,
+            PaWiring::HighPower => false,
+            PaWiring::LowAndHighPower => !tx_boosted_if_possible,
+        };
+        if low_power_pa {
+            // the Sx1261 low power PA
             if !(-17..=15).contains(&output_power) {
                 return Err(RadioError::InvalidOutputPower);
             }
@@ -389,6 +518,7 @@ where
                 }
             }
         } else {
+            // the Sx1262, Sx1268, and Llcc68 high power PA
             if !(-9..=22).contains(&output_power) {
                 return Err(RadioError::InvalidOutputPower);
             }
@@ -513,6 +643,74 @@ where
         self.intf.write(&[&op_code_and_pkt_params], false).await
     }
 
+    async fn set_fsk_modulation_params(&mut self, mdltn_params: &FskModulationParams) -> Result<(), RadioError> {
+        let bitrate_val = ((32 * SX126X_XTAL_FREQ as u64) / mdltn_params.bitrate_in_bps as u64) as u32;
+        let frequency_deviation_val =
+            (((mdltn_params.frequency_deviation_in_hz as u64) << 25) / SX126X_XTAL_FREQ as u64) as u32;
+        let pulse_shape_val = pulse_shape_value(mdltn_params.pulse_shape)?;
+        let rx_bandwidth_val = rx_bandwidth_value(mdltn_params.rx_bandwidth_in_hz)?;
+        debug!(
+            "br = {}, fdev = {}, rx bw = {}",
+            mdltn_params.bitrate_in_bps, mdltn_params.frequency_deviation_in_hz, mdltn_params.rx_bandwidth_in_hz
+        );
+        let op_code_and_mod_params = [
+            OpCode::SetModulationParams.value(),
+            ((bitrate_val >> 16) & 0xFF) as u8,
+            ((bitrate_val >> 8) & 0xFF) as u8,
+            (bitrate_val & 0xFF) as u8,
+            pulse_shape_val,
+            rx_bandwidth_val,
+            ((frequency_deviation_val >> 16) & 0xFF) as u8,
+            ((frequency_deviation_val >> 8) & 0xFF) as u8,
+            (frequency_deviation_val & 0xFF) as u8,
+        ];
+        self.intf.write(&[&op_code_and_mod_params], false).await
+    }
+
+    async fn set_fsk_packet_params(&mut self, pkt_params: &FskPacketParams) -> Result<(), RadioError> {
+        let crc_type = match pkt_params.crc_type {
+            FskCrcType::None => GfskCrcType::Off,
+            FskCrcType::Ccitt => {
+                self.set_crc_registers(0x1D0F, 0x1021).await?;
+                GfskCrcType::_2ByteInv
+            }
+            FskCrcType::Ibm => {
+                self.set_crc_registers(0xFFFF, 0x8005).await?;
+                GfskCrcType::_2Byte
+            }
+        };
+
+        let sync_word_length = pkt_params.sync_word_length as usize;
+        let register = [
+            OpCode::WriteRegister.value(),
+            Register::Syncword.addr1(),
+            Register::Syncword.addr2(),
+        ];
+        self.intf
+            .write(&[&register, &pkt_params.sync_word[..sync_word_length]], false)
+            .await?;
+
+        let preamble_length_in_bits = pkt_params.preamble_length << 3;
+        let preamble_detector_length = match pkt_params.preamble_length {
+            0 => PreambleDetectorLength::Off,
+            1 => PreambleDetectorLength::_8Bits,
+            _ => PreambleDetectorLength::_16Bits,
+        };
+        let op_code_and_pkt_params = [
+            OpCode::SetPacketParams.value(),
+            ((preamble_length_in_bits >> 8) & 0xFF) as u8,
+            (preamble_length_in_bits & 0xFF) as u8,
+            preamble_detector_length.value(),
+            pkt_params.sync_word_length << 3,
+            0x00u8, // no address filtering
+            pkt_params.variable_length as u8,
+            pkt_params.payload_length,
+            crc_type.value(),
+            pkt_params.whitening as u8,
+        ];
+        self.intf.write(&[&op_code_and_pkt_params], false).await
+    }
+
     // Calibrate the image rejection based on the given frequency
     async fn calibrate_image(&mut self, frequency_in_hz: u32) -> Result<(), RadioError> {
         let mut cal_freq = [0x00u8, 0x00u8];
@@ -532,6 +730,14 @@ where
         } else if frequency_in_hz > 425000000 {
             cal_freq[0] = 0x6B;
             cal_freq[1] = 0x6F;
+        } else if frequency_in_hz >= 410000000 {
+            cal_freq[0] = 0x66;
+            cal_freq[1] = 0x6B;
+        } else {
+            // calibrate over the 4 MHz steps surrounding the frequency
+            let step = (frequency_in_hz / 4000000) as u8;
+            cal_freq[0] = step;
+            cal_freq[1] = step + 1;
         }
 
         let op_code_and_cal_freq = [OpCode::CalibrateImage.value(), cal_freq[0], cal_freq[1]];
@@ -568,6 +774,18 @@ where
         self.intf.write(&[&op_code_and_timeout], false).await
     }
 
+    async fn do_tx_continuous_wave(&mut self) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.intf.write(&[&[OpCode::SetTxContinuousWave.value()]], false).await
+    }
+
+    async fn do_tx_continuous_preamble(&mut self) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.intf
+            .write(&[&[OpCode::SetTxContinuousPremable.value()]], false)
+            .await
+    }
+
     async fn do_rx(
         &mut self,
         rx_pkt_params: &PacketParams,
@@ -722,6 +940,92 @@ where
         }
     }
 
+    async fn do_fsk_rx(
+        &mut self,
+        rx_continuous: bool,
+        rx_boosted_if_supported: bool,
+        rx_timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_rx().await?;
+
+        let mut rx_timeout_in_ms_final = rx_timeout_in_ms << 6;
+        if rx_continuous {
+            rx_timeout_in_ms_final = 0x00ffffffu32;
+        }
+
+        let mut rx_gain_final = 0x94u8;
+        // if Rx boosted, set max LNA gain, increase current by ~2mA for around ~3dB in sensitivity
+        if rx_boosted_if_supported {
+            rx_gain_final = 0x96u8;
+        }
+
+        // stop the Rx timer on syncword detection rather than preamble detection
+        let op_code_and_false_flag = [OpCode::SetStopRxTimerOnPreamble.value(), 0x00u8];
+        self.intf.write(&[&op_code_and_false_flag], false).await?;
+
+        let register_and_rx_gain = [
+            OpCode::WriteRegister.value(),
+            Register::RxGain.addr1(),
+            Register::RxGain.addr2(),
+            rx_gain_final,
+        ];
+        self.intf.write(&[&register_and_rx_gain], false).await?;
+
+        let op_code_and_timeout = [
+            OpCode::SetRx.value(),
+            Self::timeout_1(rx_timeout_in_ms_final),
+            Self::timeout_2(rx_timeout_in_ms_final),
+            Self::timeout_3(rx_timeout_in_ms_final),
+        ];
+        self.intf.write(&[&op_code_and_timeout], false).await
+    }
+
+    // The chip provides the length of both fixed and variable length packets.
+    async fn get_fsk_rx_payload(
+        &mut self,
+        _rx_pkt_params: &FskPacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError> {
+        let op_code = [OpCode::GetRxBufferStatus.value()];
+        let mut rx_buffer_status = [0x00u8; 2];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut rx_buffer_status).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+
+        let payload_length = rx_buffer_status[0];
+        let offset = rx_buffer_status[1];
+
+        if (payload_length as usize) > receiving_buffer.len() {
+            Err(RadioError::PayloadSizeMismatch(
+                payload_length as usize,
+                receiving_buffer.len(),
+            ))
+        } else {
+            self.intf
+                .read(
+                    &[&[OpCode::ReadBuffer.value(), offset, 0x00u8]],
+                    receiving_buffer,
+                    Some(payload_length),
+                )
+                .await?;
+            Ok(payload_length)
+        }
+    }
+
+    async fn get_fsk_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
+        let op_code = [OpCode::GetPacketStatus.value()];
+        let mut pkt_status = [0x00u8; 3];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut pkt_status).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        // RSSI at sync word detection, with no SNR available for GFSK packets
+        let rssi = ((-(pkt_status[1] as i32)) >> 1) as i16;
+
+        Ok(PacketStatus::new(rssi, 0))
+    }
+
     async fn get_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
         let op_code = [OpCode::GetPacketStatus.value()];
         let mut pkt_status = [0x00u8; 3];
@@ -732,16 +1036,58 @@ where
         // check this ???
         let rssi = ((-(pkt_status[0] as i32)) >> 1) as i16;
         let snr = (((pkt_status[1] as i8) + 2) >> 2) as i16;
-        let _signal_rssi = ((-(pkt_status[2] as i32)) >> 1) as i16; // unused currently
+        let signal_rssi = ((-(pkt_status[2] as i32)) >> 1) as i16;
 
-        Ok(PacketStatus { rssi, snr })
+        Ok(PacketStatus {
+            rssi,
+            snr,
+            signal_rssi: Some(signal_rssi),
+            ..Default::default()
+        })
     }
 
-    async fn do_cad(
-        &mut self,
-        mdltn_params: &ModulationParams,
-        rx_boosted_if_supported: bool,
-    ) -> Result<(), RadioError> {
+    async fn get_rssi(&mut self) -> Result<i16, RadioError> {
+        let op_code = [OpCode::GetRSSIInst.value()];
+        let mut rssi = [0x00u8];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut rssi).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        Ok(((-(rssi[0] as i32)) >> 1) as i16)
+    }
+
+    async fn get_rx_stats(&mut self) -> Result<RxStats, RadioError> {
+        let op_code = [OpCode::GetStats.value()];
+        let mut stats = [0x00u8; 6];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut stats).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        Ok(RxStats {
+            packets_received: u16::from_be_bytes([stats[0], stats[1]]),
+            crc_errors: u16::from_be_bytes([stats[2], stats[3]]),
+            header_errors: Some(u16::from_be_bytes([stats[4], stats[5]])),
+        })
+    }
+
+    async fn reset_rx_stats(&mut self) -> Result<(), RadioError> {
+        let op_code_and_stats = [
+            OpCode::ResetStats.value(),
+            0x00u8,
+            0x00u8,
+            0x00u8,
+            0x00u8,
+            0x00u8,
+            0x00u8,
+        ];
+        self.intf.write(&[&op_code_and_stats], false).await
+    }
+
+    fn cad_rx_supported(&self) -> bool {
+        true
+    }
+
+    async fn do_cad(&mut self, cad_params: &CadParams, rx_boosted_if_supported: bool) -> Result<(), RadioError> {
         self.intf.iv.enable_rf_switch_rx().await?;
 
         let mut rx_gain_final = 0x94u8;
@@ -758,19 +1104,21 @@ where
         ];
         self.intf.write(&[&register_and_rx_gain], false).await?;
 
-        // See:
-        //  https://lora-developers.semtech.com/documentation/tech-papers-and-guides/channel-activity-detection-ensuring-your-lora-packets-are-sent/how-to-ensure-your-lora-packets-are-sent-properly
-        // for default values used here.
-        let spreading_factor_val = spreading_factor_value(mdltn_params.spreading_factor)?;
+        let exit_mode = match cad_params.exit_mode {
+            CadExitMode::CadOnly => 0x00u8,
+            CadExitMode::CadRx => 0x01u8,
+            CadExitMode::CadLbt => 0x10u8,
+        };
+        let timeout = cad_params.timeout_in_ms << 6; // timeout of the chained receive or send operation
         let op_code_and_cad_params = [
             OpCode::SetCADParams.value(),
-            CADSymbols::_8.value(),      // number of symbols for detection
-            spreading_factor_val + 13u8, // limit for detection of SNR peak
-            10u8,                        // minimum symbol recognition
-            0x00u8,                      // CAD exit mode without listen-before-send or subsequent receive processing
-            0x00u8,                      // no timeout
-            0x00u8,
-            0x00u8,
+            CADSymbols::from(cad_params.symbols).value(), // number of symbols for detection
+            cad_params.det_peak,                          // limit for detection of SNR peak
+            cad_params.det_min,                           // minimum symbol recognition
+            exit_mode,
+            Self::timeout_1(timeout),
+            Self::timeout_2(timeout),
+            Self::timeout_3(timeout),
         ];
         self.intf.write(&[&op_code_and_cad_params], false).await?;
 
@@ -799,8 +1147,15 @@ where
                 dio1_mask = IrqMask::All.value();
             }
             Some(RadioMode::ChannelActivityDetection) => {
-                irq_mask = IrqMask::CADDone.value() | IrqMask::CADActivityDetected.value();
-                dio1_mask = IrqMask::CADDone.value() | IrqMask::CADActivityDetected.value();
+                // include the events of a receive or send operation chained by the CAD exit mode
+                irq_mask = IrqMask::CADDone.value()
+                    | IrqMask::CADActivityDetected.value()
+                    | IrqMask::TxDone.value()
+                    | IrqMask::RxDone.value()
+                    | IrqMask::HeaderError.value()
+                    | IrqMask::CRCError.value()
+                    | IrqMask::RxTxTimeout.value();
+                dio1_mask = irq_mask;
             }
             _ => {}
         }
@@ -819,6 +1174,34 @@ where
         self.intf.write(&[&op_code_and_masks], false).await
     }
 
+    async fn get_device_errors(&mut self) -> Result<DeviceErrors, RadioError> {
+        let op_code = [OpCode::GetErrors.value()];
+        let mut op_error = [0x00u8; 2];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut op_error).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        Ok(device_errors(u16::from_be_bytes(op_error)))
+    }
+
+    // Calibrate all blocks, including the image rejection over the default band, after clearing the errors, as
+    // needed to recover from a failure to start the oscillator
+    async fn clear_device_errors_and_calibrate(&mut self) -> Result<(), RadioError> {
+        let op_code_and_null = [OpCode::ClrErrors.value(), 0x00u8, 0x00u8];
+        self.intf.write(&[&op_code_and_null], false).await?;
+        let calibration_params = CalibrationParams {
+            rc64k_enable: true,
+            rc13m_enable: true,
+            pll_enable: true,
+            adc_pulse_enable: true,
+            adc_bulkn_enable: true,
+            adc_bulkp_enable: true,
+            img_enable: true,
+        };
+        let op_code_and_calibration_params = [OpCode::Calibrate.value(), calibration_params.value()];
+        self.intf.write(&[&op_code_and_calibration_params], false).await
+    }
+
     /// Process the radio IRQ.  Log unexpected interrupts, but only bail out on timeout.  Packets from other devices can cause unexpected interrupts.
     async fn process_irq(
         &mut self,
@@ -873,7 +1256,7 @@ where
                 }
                 if (irq_flags & IrqMask::RxDone.value()) == IrqMask::RxDone.value() {
                     debug!("RxDone in radio mode {}", radio_mode);
-                    if !rx_continuous {
+                    if !rx_continuous && self.packet_type == PacketType::LoRa {
                         // implicit header mode timeout behavior (see DS_SX1261-2_V1.2 datasheet chapter 15.3)
                         let register_and_clear = [
                             OpCode::WriteRegister.value(),
@@ -925,19 +1308,12 @@ where
             // if an interrupt occurred for other than an error or operation completion, loop to wait again
         }
     }
-}
 
-impl<SPI, IV> crate::RngRadio for SX1261_2<SPI, IV>
-where
-    SPI: SpiBus<u8>,
-    IV: InterfaceVariant,
-{
     /// Generate a 32 bit random value based on the RSSI readings, after disabling all interrupts.
     /// The random numbers produced by the generator do not have a uniform or Gaussian distribution.
     /// If uniformity is needed, perform appropriate software post-processing.
     async fn get_random_number(&mut self) -> Result<u32, RadioError> {
-        // The stm32wl often returns 0 on the first random number generation operation.  Documentation for the stm32wl does not recommend LNA register modification.
-        if self.board_type == BoardType::Stm32wlSx1262 {
+        if !self.board_config.rng_supported {
             return Err(RadioError::RngUnsupported);
         }
         self.set_irq_params(None).await?;
@@ -1028,6 +1404,13 @@ where
     }
 }
 
+impl<SPI, IV> crate::RngRadio for SX1261_2<SPI, IV>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+{
+}
+
 #[cfg(test)]
 mod tests {
     // use super::*;
@@ -45,6 +45,8 @@ pub enum Register {
     PayloadLength = 0x0702,         // payload size
     SynchTimeout = 0x0706,          // recalculated number of symbols
     Syncword = 0x06C0,              // Syncword values
+    CrcInitialValue = 0x06BC,       // GFSK CRC initial value
+    CrcPolynomial = 0x06BE,         // GFSK CRC polynomial
     LoRaSyncword = 0x0740,          // LoRa Syncword value
     GeneratedRandomNumber = 0x0819, //32-bit generated random number
     AnaLNA = 0x08E2,                // disable the LNA
@@ -203,8 +205,22 @@ impl CalibrationParams {
     }
 }
 
+// Decode the OpError word returned by GetErrors (see DS_SX1261-2_V1.2 datasheet, section 13.6.1)
+pub fn device_errors(op_error: u16) -> DeviceErrors {
+    let bit = |n: u16| (op_error & (1 << n)) != 0;
+    DeviceErrors {
+        rc64k_calibration: bit(0),
+        rc13m_calibration: bit(1),
+        pll_calibration: bit(2),
+        adc_calibration: bit(3),
+        image_calibration: bit(4),
+        xosc_start: bit(5),
+        pll_lock: bit(6),
+        pa_ramp: bit(8),
+    }
+}
+
 #[derive(Clone, Copy)]
-#[allow(dead_code)]
 pub enum TcxoCtrlVoltage {
     Ctrl1V6 = 0x00,
     Ctrl1V7 = 0x01,
@@ -222,6 +238,21 @@ impl TcxoCtrlVoltage {
     }
 }
 
+impl From<TcxoVoltage> for TcxoCtrlVoltage {
+    fn from(voltage: TcxoVoltage) -> Self {
+        match voltage {
+            TcxoVoltage::_1V6 => TcxoCtrlVoltage::Ctrl1V6,
+            TcxoVoltage::_1V7 => TcxoCtrlVoltage::Ctrl1V7,
+            TcxoVoltage::_1V8 => TcxoCtrlVoltage::Ctrl1V8,
+            TcxoVoltage::_2V2 => TcxoCtrlVoltage::Ctrl2V2,
+            TcxoVoltage::_2V4 => TcxoCtrlVoltage::Ctrl2V4,
+            TcxoVoltage::_2V7 => TcxoCtrlVoltage::Ctrl2V7,
+            TcxoVoltage::_3V0 => TcxoCtrlVoltage::Ctrl3V0,
+            TcxoVoltage::_3V3 => TcxoCtrlVoltage::Ctrl3V3,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 #[allow(clippy::enum_variant_names)]
@@ -267,6 +298,9 @@ pub fn bandwidth_value(bandwidth: Bandwidth) -> Result<u8, RadioError> {
         Bandwidth::_125KHz => Ok(0x04),
         Bandwidth::_250KHz => Ok(0x05),
         Bandwidth::_500KHz => Ok(0x06),
+        Bandwidth::_203KHz | Bandwidth::_406KHz | Bandwidth::_812KHz | Bandwidth::_1625KHz => {
+            Err(RadioError::UnavailableBandwidth)
+        }
     }
 }
 
@@ -276,6 +310,94 @@ pub fn coding_rate_value(coding_rate: CodingRate) -> Result<u8, RadioError> {
         CodingRate::_4_6 => Ok(0x02),
         CodingRate::_4_7 => Ok(0x03),
         CodingRate::_4_8 => Ok(0x04),
+        CodingRate::_4_5Li | CodingRate::_4_6Li | CodingRate::_4_8Li => Err(RadioError::UnavailableCodingRate),
+    }
+}
+
+pub fn pulse_shape_value(pulse_shape: PulseShape) -> Result<u8, RadioError> {
+    match pulse_shape {
+        PulseShape::None => Ok(0x00),
+        PulseShape::GaussianBt0_3 => Ok(0x08),
+        PulseShape::GaussianBt0_5 => Ok(0x09),
+        PulseShape::GaussianBt0_7 => Ok(0x0A),
+        PulseShape::GaussianBt1_0 => Ok(0x0B),
+        PulseShape::Ook | PulseShape::OokCutoffBitrate | PulseShape::OokCutoff2xBitrate => {
+            Err(RadioError::UnavailablePulseShape)
+        }
+    }
+}
+
+// GFSK receive bandwidths (Hz) and their values, in increasing bandwidth order
+const RX_BANDWIDTHS: [(u32, u8); 21] = [
+    (4800, 0x1F),
+    (5800, 0x17),
+    (7300, 0x0F),
+    (9700, 0x1E),
+    (11700, 0x16),
+    (14600, 0x0E),
+    (19500, 0x1D),
+    (23400, 0x15),
+    (29300, 0x0D),
+    (39000, 0x1C),
+    (46900, 0x14),
+    (58600, 0x0C),
+    (78200, 0x1B),
+    (93800, 0x13),
+    (117300, 0x0B),
+    (156200, 0x1A),
+    (187200, 0x12),
+    (234300, 0x0A),
+    (312000, 0x19),
+    (373600, 0x11),
+    (467000, 0x09),
+];
+
+// Get the smallest GFSK receive bandwidth covering the given bandwidth
+pub fn supported_rx_bandwidth_in_hz(bandwidth_in_hz: u32) -> Result<u32, RadioError> {
+    RX_BANDWIDTHS
+        .iter()
+        .find(|(bw, _)| *bw >= bandwidth_in_hz)
+        .map(|(bw, _)| *bw)
+        .ok_or(RadioError::UnavailableRxBandwidth)
+}
+
+pub fn rx_bandwidth_value(bandwidth_in_hz: u32) -> Result<u8, RadioError> {
+    RX_BANDWIDTHS
+        .iter()
+        .find(|(bw, _)| *bw == bandwidth_in_hz)
+        .map(|(_, value)| *value)
+        .ok_or(RadioError::UnavailableRxBandwidth)
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum PreambleDetectorLength {
+    Off = 0x00,
+    _8Bits = 0x04,
+    _16Bits = 0x05,
+    _24Bits = 0x06,
+    _32Bits = 0x07,
+}
+
+impl PreambleDetectorLength {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum GfskCrcType {
+    Off = 0x01,
+    _1Byte = 0x00,
+    _2Byte = 0x02,
+    _1ByteInv = 0x04,
+    _2ByteInv = 0x06,
+}
+
+impl GfskCrcType {
+    pub fn value(self) -> u8 {
+        self as u8
     }
 }
 
@@ -293,3 +415,15 @@ impl CADSymbols {
         self as u8
     }
 }
+
+impl From<CadSymbols> for CADSymbols {
+    fn from(symbols: CadSymbols) -> Self {
+        match symbols {
+            CadSymbols::_1 => CADSymbols::_1,
+            CadSymbols::_2 => CADSymbols::_2,
+            CadSymbols::_4 => CADSymbols::_4,
+            CadSymbols::_8 => CADSymbols::_8,
+            CadSymbols::_16 => CADSymbols::_16,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-mod radio_kind_params;
+pub(crate) mod radio_kind_params;
 
 use defmt::debug;
 use embedded_hal_async::delay::DelayUs;
@@ -6,18 +6,55 @@ use embedded_hal_async::spi::*;
 use radio_kind_params::*;
 
 use crate::mod_params::*;
+use crate::timer::with_timeout;
 use crate::{InterfaceVariant, RadioKind, SpiInterface};
 
-// Syncwords for public and private networks
-const LORA_MAC_PUBLIC_SYNCWORD: u8 = 0x34; // corresponds to sx126x 0x3444
-const LORA_MAC_PRIVATE_SYNCWORD: u8 = 0x12; // corresponds to sx126x 0x1424
-
 // TCXO flag
 const TCXO_FOR_OSCILLATOR: u8 = 0x10u8;
 
+// Crystal oscillator frequency (Hz)
+const FXOSC: u32 = 32_000_000;
+
 // Frequency synthesizer step for frequency calculation (Hz)
 const FREQUENCY_SYNTHESIZER_STEP: f64 = 61.03515625; // FXOSC (32 MHz) * 1000000 (Hz/MHz) / 524288 (2^19)
 
+// Size of the FIFO in FSK/OOK mode, which must hold an entire packet (including any length byte)
+const FSK_FIFO_SIZE: usize = 64;
+
+// Offsets of the packet RSSI for the sx1276/7/8/9 high frequency (RFO_HF/RFI_HF) and low frequency (RFO_LF/RFI_LF)
+// ports and for the sx1272/3 (dBm)
+const RSSI_OFFSET_HF: i16 = -157;
+const RSSI_OFFSET_LF: i16 = -164;
+const RSSI_OFFSET_SX1272: i16 = -139;
+
+// Frequencies above this threshold use the high frequency port (Hz)
+const RF_MID_BAND_THRESHOLD_IN_HZ: u32 = 525_000_000;
+
+// RegModemStat flags set once a LoRa preamble is detected (signal detected, signal synchronized, header info valid)
+const MODEM_STAT_SIGNAL_DETECTED: u8 = 0x0b;
+
+// Duration of the receive and sleep intervals of duty-cycled receive operations, which are given in the 15.625 us
+// steps of the Sx126x chips
+fn duty_cycle_interval_in_us(interval: u32) -> u32 {
+    ((interval as u64 * 15_625) / 1000) as u32
+}
+
+// Compute the RSSI (dBm) and SNR (dB) of a received LoRa packet from the RegPktRssiValue and RegPktSnrValue register
+// values, where the SNR is given in signed quarter dB.  See DS_SX1276-7-8-9_W_APP_V7 section 5.5.5: the RSSI slope is
+// corrected by 16/15 for a positive SNR (a correction the Sx1272/3 datasheet does not give), while for a negative SNR
+// the packet is below the noise floor and its strength is given by adding the SNR.
+fn packet_rssi_and_snr(pkt_rssi_value: u8, pkt_snr_value: u8, rssi_offset: i16, slope_corrected: bool) -> (i16, i16) {
+    let snr_in_quarter_db = pkt_snr_value as i8 as i16;
+    let pkt_rssi = pkt_rssi_value as i16;
+    let rssi = match (snr_in_quarter_db < 0, slope_corrected) {
+        (true, _) => rssi_offset + pkt_rssi + snr_in_quarter_db / 4,
+        (false, true) => rssi_offset + (pkt_rssi * 16) / 15,
+        (false, false) => rssi_offset + pkt_rssi,
+    };
+    let snr = (snr_in_quarter_db + 2) >> 2;
+    (rssi, snr)
+}
+
 impl ModulationParams {
     /// Create modulation parameters specific to the LoRa chip kind and type
     pub fn new_for_sx1276_7_8_9(
@@ -52,8 +89,52 @@ impl ModulationParams {
     }
 }
 
+impl ModulationParams {
+    /// Create modulation parameters specific to the Sx1272 chip
+    pub fn new_for_sx1272(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        // Parameter validation
+        spreading_factor_value(spreading_factor)?;
+        sx1272_bandwidth_value(bandwidth)?;
+        coding_rate_value(coding_rate)?;
+
+        // Section 4.1.1.6
+        let bw_in_hz = bandwidth.value_in_hz();
+        let symbol_duration = 1000 / (bw_in_hz / (0x01u32 << spreading_factor_value(spreading_factor)?));
+        let mut low_data_rate_optimize = 0x00u8;
+        if symbol_duration > 16 {
+            low_data_rate_optimize = 0x01u8
+        }
+
+        Ok(Self {
+            spreading_factor,
+            bandwidth,
+            coding_rate,
+            low_data_rate_optimize,
+            frequency_in_hz,
+        })
+    }
+
+    /// Create modulation parameters specific to the Sx1273 chip, which supports spreading factors 6 to 9
+    pub fn new_for_sx1273(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        if spreading_factor_value(spreading_factor)? > 0x09 {
+            return Err(RadioError::UnavailableSpreadingFactor);
+        }
+        Self::new_for_sx1272(spreading_factor, bandwidth, coding_rate, frequency_in_hz)
+    }
+}
+
 impl PacketParams {
-    /// Create packet parameters specific to the LoRa chip kind and type
+    /// Create packet parameters specific to the LoRa chip kind and type (also valid for the Sx1272/3 chips)
     pub fn new_for_sx1276_7_8_9(
         preamble_length: u16,
         implicit_header: bool,
@@ -77,10 +158,92 @@ impl PacketParams {
     }
 }
 
-/// Base for the RadioKind implementation for the LoRa chip kind and board type
-pub struct SX1276_7_8_9<SPI, IV> {
-    board_type: BoardType,
+impl FskModulationParams {
+    /// Create FSK or OOK modulation parameters specific to the LoRa chip kind and type.  OOK modulation is selected
+    /// using an OOK pulse shape.
+    pub fn new_for_sx1276_7_8_9(
+        bitrate_in_bps: u32,
+        frequency_deviation_in_hz: u32,
+        pulse_shape: PulseShape,
+        rx_bandwidth_in_hz: u32,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        // Parameter validation (section 2.5.1 and table 7)
+        pulse_shape_value(pulse_shape)?;
+        let ook = is_ook(pulse_shape);
+        let max_bitrate_in_bps = if ook { 32_768 } else { 300_000 };
+        if !(1_200..=max_bitrate_in_bps).contains(&bitrate_in_bps) {
+            return Err(RadioError::UnavailableBitrate);
+        }
+        if !ook
+            && (!(600..=200_000).contains(&frequency_deviation_in_hz)
+                || (frequency_deviation_in_hz + bitrate_in_bps / 2) > 250_000)
+        {
+            return Err(RadioError::UnavailableFrequencyDeviation);
+        }
+
+        Ok(Self {
+            bitrate_in_bps,
+            frequency_deviation_in_hz,
+            pulse_shape,
+            rx_bandwidth_in_hz: supported_rx_bandwidth_in_hz(rx_bandwidth_in_hz, ook)?,
+            frequency_in_hz,
+        })
+    }
+}
+
+impl FskPacketParams {
+    /// Create FSK or OOK packet parameters specific to the LoRa chip kind and type.  Packets, including the length
+    /// byte of variable length packets, are limited to the size of the FIFO (64 bytes).
+    pub fn new_for_sx1276_7_8_9(
+        preamble_length: u16,
+        sync_word: &[u8],
+        variable_length: bool,
+        payload_length: u8,
+        crc_type: FskCrcType,
+        whitening: bool,
+        _modulation_params: &FskModulationParams,
+    ) -> Result<Self, RadioError> {
+        if (payload_length as usize + variable_length as usize) > FSK_FIFO_SIZE {
+            return Err(RadioError::PayloadSizeUnexpected(payload_length as usize));
+        }
+        Self::new(
+            preamble_length,
+            sync_word,
+            variable_length,
+            payload_length,
+            crc_type,
+            whitening,
+        )
+    }
+}
+
+/// Delay type of a RadioKind implementation created without a delay, which cannot time operations in software
+pub struct NoDelay;
+
+impl DelayUs for NoDelay {
+    async fn delay_us(&mut self, _us: u32) {}
+
+    async fn delay_ms(&mut self, _ms: u32) {}
+}
+
+/// Base for the RadioKind implementation for the LoRa chip kind and board type.  Boards with an Sx1272/3 chip are also
+/// supported, accounting for their different modem configuration and power amplifier registers.
+pub struct SX1276_7_8_9<SPI, IV, D = NoDelay> {
+    board_config: BoardConfig,
     intf: SpiInterface<SPI, IV>,
+    delay: Option<D>, // times operations in software, as the LoRa modem has no timers
+    modem: Modem,
+    frequency_in_hz: u32,                     // channel frequency, which determines the RF port used
+    spreading_factor: SpreadingFactor,        // LoRa spreading factor, needed to size duty-cycled receive windows
+    bandwidth: Bandwidth,                     // LoRa bandwidth, needed to scale the frequency error of received packets
+    ook: bool,                                // FSK/OOK mode is set to OOK modulation
+    fsk_variable_length: bool,                // FSK/OOK packets are sent with a length byte
+    tx_continuous_preamble: Option<(u8, u8)>, // preamble length to restore once a continuous preamble transmission ends
+    rx_stats: RxStats,                        // counted in software, as the chip has no statistics counters
+    rx_duty_cycle: Option<DutyCycleParams>,   // receive windows and sleep periods alternated in software
+    tx_timeout_in_ms: u32,                    // send operation deadline, enforced in software (0 for none)
+    rx_timeout_in_ms: u32,                    // single receive operation deadline, enforced in software (0 for none)
 }
 
 impl<SPI, IV> SX1276_7_8_9<SPI, IV>
@@ -88,11 +251,45 @@ where
     SPI: SpiBus<u8>,
     IV: InterfaceVariant,
 {
-    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board type
-    pub fn new(board_type: BoardType, spi: SPI, mut iv: IV) -> Self {
-        iv.set_board_type(board_type);
+    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board configuration, given as a
+    /// BoardConfig or as the BoardType of a preset board
+    pub fn new(board_config: impl Into<BoardConfig>, spi: SPI, iv: IV) -> Self {
+        Self::create(board_config.into(), spi, iv, None)
+    }
+}
+
+impl<SPI, IV, D> SX1276_7_8_9<SPI, IV, D>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+    D: DelayUs,
+{
+    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board configuration, with a delay
+    /// used to emulate the duty-cycled receive operations of chips with timers and to end send and receive operations
+    /// which exceed their timeouts
+    pub fn new_with_delay(board_config: impl Into<BoardConfig>, spi: SPI, iv: IV, delay: D) -> Self {
+        Self::create(board_config.into(), spi, iv, Some(delay))
+    }
+
+    fn create(board_config: BoardConfig, spi: SPI, mut iv: IV, delay: Option<D>) -> Self {
+        iv.set_board_type(board_config.board_type);
         let intf = SpiInterface::new(spi, iv);
-        Self { board_type, intf }
+        Self {
+            board_config,
+            intf,
+            delay,
+            modem: Modem::LoRa,
+            frequency_in_hz: 0,
+            spreading_factor: SpreadingFactor::_7,
+            bandwidth: Bandwidth::_125KHz,
+            ook: false,
+            fsk_variable_length: false,
+            tx_continuous_preamble: None,
+            rx_stats: RxStats::default(),
+            rx_duty_cycle: None,
+            tx_timeout_in_ms: 0,
+            rx_timeout_in_ms: 0,
+        }
     }
 
     // Utility functions
@@ -113,29 +310,256 @@ where
         Ok(read_buffer[0])
     }
 
+    async fn write_fsk_register(&mut self, register: FskRegister, value: u8) -> Result<(), RadioError> {
+        let write_buffer = [register.write_addr(), value];
+        self.intf.write(&[&write_buffer], false).await
+    }
+
+    async fn read_fsk_register(&mut self, register: FskRegister) -> Result<u8, RadioError> {
+        let write_buffer = [register.read_addr()];
+        let mut read_buffer = [0x00u8];
+        self.intf.read(&[&write_buffer], &mut read_buffer, None).await?;
+        Ok(read_buffer[0])
+    }
+
     // Set the number of symbols the radio will wait to validate a reception
     async fn set_lora_symbol_num_timeout(&mut self, symbol_num: u8) -> Result<(), RadioError> {
         self.write_register(Register::RegSymbTimeoutLsb, symbol_num, false)
             .await
     }
 
+    // Emulate the duty-cycled receive mode of the Sx126x chips, alternating the receive window opened by do_rx with
+    // sleep periods until a packet is received.  The window in which a preamble is detected is extended by polling the
+    // IRQ flags every symbol, since the RxTimeout ending the window of a false detection is not signalled on DIO0; the
+    // duty cycle then resumes.
+    async fn await_rx_duty_cycle_packet(&mut self, duty_cycle: DutyCycleParams) -> Result<(), RadioError> {
+        let rx_time_in_us = duty_cycle_interval_in_us(duty_cycle.rx_time);
+        let symbol_time_in_us = self.symbol_time_in_us();
+        loop {
+            loop {
+                if let Some(delay) = self.delay.as_mut() {
+                    delay.delay_us(rx_time_in_us).await;
+                }
+                let modem_stat = self.read_register(Register::RegModemStat).await?;
+                let irq_flags = self.read_register(Register::RegIrqFlags).await?;
+                if (irq_flags & IrqMask::RxDone.value()) != 0 {
+                    return Ok(());
+                }
+                if (modem_stat & MODEM_STAT_SIGNAL_DETECTED) != 0 {
+                    break;
+                }
+                self.reopen_rx_duty_cycle_window(duty_cycle).await?;
+            }
+
+            loop {
+                let irq_flags = self.read_register(Register::RegIrqFlags).await?;
+                if (irq_flags & IrqMask::RxDone.value()) != 0 {
+                    return Ok(());
+                }
+                if (irq_flags & IrqMask::RxTimeout.value()) != 0 {
+                    debug!("RxTimeout following a preamble detection in radio mode ReceiveDutyCycle");
+                    break;
+                }
+                if let Some(delay) = self.delay.as_mut() {
+                    delay.delay_us(symbol_time_in_us).await;
+                }
+            }
+            self.reopen_rx_duty_cycle_window(duty_cycle).await?;
+        }
+    }
+
+    // Duration of a LoRa symbol with the modulation parameters last set
+    fn symbol_time_in_us(&self) -> u32 {
+        (((1u64 << self.spreading_factor.factor()) * 1_000_000) / self.bandwidth.value_in_hz() as u64) as u32
+    }
+
+    // Sleep for the sleep interval of a duty-cycled receive operation, then open the next receive window
+    async fn reopen_rx_duty_cycle_window(&mut self, duty_cycle: DutyCycleParams) -> Result<(), RadioError> {
+        self.write_register(Register::RegOpMode, LoRaMode::Sleep.value(), true)
+            .await?;
+        if let Some(delay) = self.delay.as_mut() {
+            delay.delay_us(duty_cycle_interval_in_us(duty_cycle.sleep_time)).await;
+        }
+        self.write_register(Register::RegOpMode, LoRaMode::Standby.value(), false)
+            .await?;
+        self.write_register(Register::RegIrqFlags, 0xffu8, false).await?; // clear the receive timeout
+        self.write_register(Register::RegFifoAddrPtr, 0x00u8, false).await?;
+        self.write_register(Register::RegOpMode, LoRaMode::RxSingle.value(), false)
+            .await
+    }
+
+    // Wait for an event signalled on DIO0.  As the chip has no timers, the wait is bounded in software by the timeout
+    // of the send or single receive operation, if a delay is available, and duty-cycled receive operations are
+    // emulated in software.
+    async fn await_irq(&mut self, radio_mode: RadioMode) -> Result<(), RadioError> {
+        if let (RadioMode::ReceiveDutyCycle, Some(duty_cycle)) = (radio_mode, self.rx_duty_cycle) {
+            return self.await_rx_duty_cycle_packet(duty_cycle).await;
+        }
+        let (timeout_in_ms, timeout_error) = match radio_mode {
+            RadioMode::Transmit => (self.tx_timeout_in_ms, RadioError::TransmitTimeout),
+            RadioMode::Receive => (self.rx_timeout_in_ms, RadioError::ReceiveTimeout),
+            _ => (0, RadioError::Irq),
+        };
+        match self.delay.as_mut() {
+            Some(delay) if timeout_in_ms != 0 => with_timeout(delay, timeout_in_ms, self.intf.iv.await_irq())
+                .await
+                .unwrap_or(Err(timeout_error)),
+            _ => self.intf.iv.await_irq().await,
+        }
+    }
+
+    // End the back to back packets of a continuous preamble transmission, clearing TxContinuousMode and restoring the
+    // preamble length, so the next send operation transmits a single packet
+    async fn end_tx_continuous_preamble(&mut self) -> Result<(), RadioError> {
+        if let Some((preamble_msb, preamble_lsb)) = self.tx_continuous_preamble.take() {
+            let config_2 = self.read_register(Register::RegModemConfig2).await?;
+            self.write_register(Register::RegModemConfig2, config_2 & !0x08u8, false)
+                .await?;
+            self.write_register(Register::RegPreambleMsb, preamble_msb, false)
+                .await?;
+            self.write_register(Register::RegPreambleLsb, preamble_lsb, false)
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn is_sx1272_3(&self) -> bool {
+        matches!(self.board_config.chip_type(), ChipType::Sx1272 | ChipType::Sx1273)
+    }
+
+    // Set parameters associated with power for an sx1272/3 send operation, where the output power is set as
+    // Pout=-1+OutputPower for the RFO pin and Pout=2+OutputPower for the PA_BOOST pin (Pout=5+OutputPower with the
+    // +20 dBm option)
+    async fn set_sx1272_tx_power(&mut self, p_out: i32, tx_boosted_if_possible: bool) -> Result<(), RadioError> {
+        if tx_boosted_if_possible {
+            if !(2..=20).contains(&p_out) {
+                return Err(RadioError::InvalidOutputPower);
+            }
+
+            let output_power = if p_out > 17 {
+                self.write_register(Register::RegPaDacSx1272, PaDac::_20DbmOn.value(), false)
+                    .await?;
+                self.set_ocp(OcpTrim::_240Ma).await?;
+                p_out - 5
+            } else {
+                self.write_register(Register::RegPaDacSx1272, PaDac::_20DbmOff.value(), false)
+                    .await?;
+                self.set_ocp(OcpTrim::_100Ma).await?;
+                p_out - 2
+            };
+            debug!("tx power = {}", output_power);
+            self.write_register(
+                Register::RegPaConfig,
+                PaConfig::PaBoost.value() | (output_power as u8),
+                false,
+            )
+            .await
+        } else {
+            if !(-1..=14).contains(&p_out) {
+                return Err(RadioError::InvalidOutputPower);
+            }
+
+            let output_power: i32 = p_out + 1;
+            debug!("tx power = {}", output_power);
+
+            self.write_register(Register::RegPaDacSx1272, PaDac::_20DbmOff.value(), false)
+                .await?;
+            self.set_ocp(OcpTrim::_100Ma).await?;
+            self.write_register(
+                Register::RegPaConfig,
+                PaConfig::RfoSx1272.value() | (output_power as u8),
+                false,
+            )
+            .await
+        }
+    }
+
+    // Offset of the LoRa RSSI values for the RF port in use
+    fn rssi_offset(&self) -> i16 {
+        match self.is_sx1272_3() {
+            true => RSSI_OFFSET_SX1272,
+            false if self.frequency_in_hz > RF_MID_BAND_THRESHOLD_IN_HZ => RSSI_OFFSET_HF,
+            false => RSSI_OFFSET_LF,
+        }
+    }
+
     // Set the over current protection (mA) on the radio
     async fn set_ocp(&mut self, ocp_trim: OcpTrim) -> Result<(), RadioError> {
         self.write_register(Register::RegOcp, ocp_trim.value(), false).await
     }
+
+    // Map PacketSent (transmit) or PayloadReady (receive) to DIO0 (the IRQ pin).  FSK/OOK interrupt flags cannot be
+    // masked and are cleared by the chip.
+    async fn set_fsk_irq_params(&mut self) -> Result<(), RadioError> {
+        let mut dio_mapping_1 = self.read_register(Register::RegDioMapping1).await?;
+        dio_mapping_1 =
+            (dio_mapping_1 & FskDioMapping1Dio0::Mask.value()) | FskDioMapping1Dio0::PacketSentPayloadReady.value();
+        self.write_register(Register::RegDioMapping1, dio_mapping_1, false)
+            .await
+    }
+
+    // Clearing the FIFO overrun flag also clears the FIFO
+    async fn clear_fsk_fifo(&mut self) -> Result<(), RadioError> {
+        self.write_fsk_register(FskRegister::RegIrqFlags2, FskIrqFlags2::FifoOverrun.value())
+            .await
+    }
+
+    // Process the radio IRQ in FSK/OOK mode.  Packets failing the CRC check are discarded by the chip without raising
+    // PayloadReady.
+    async fn process_fsk_irq(&mut self, radio_mode: RadioMode, rx_continuous: bool) -> Result<(), RadioError> {
+        loop {
+            debug!("process_fsk_irq loop entered");
+
+            self.await_irq(radio_mode).await?;
+
+            let irq_flags_1 = self.read_fsk_register(FskRegister::RegIrqFlags1).await?;
+            let irq_flags_2 = self.read_fsk_register(FskRegister::RegIrqFlags2).await?;
+
+            debug!(
+                "process_fsk_irq satisfied: irq_flags_1 = 0x{:x}, irq_flags_2 = 0x{:x} in radio mode {}",
+                irq_flags_1, irq_flags_2, radio_mode
+            );
+
+            if (irq_flags_2 & FskIrqFlags2::FifoLevel.value()) == FskIrqFlags2::FifoLevel.value() {
+                debug!("FifoLevel in radio mode {}", radio_mode);
+            }
+
+            if radio_mode == RadioMode::Transmit {
+                if (irq_flags_2 & FskIrqFlags2::PacketSent.value()) == FskIrqFlags2::PacketSent.value() {
+                    debug!("PacketSent in radio mode {}", radio_mode);
+                    // the transmitter stays on until the mode is changed
+                    return self.set_standby().await;
+                }
+            } else if radio_mode == RadioMode::Receive
+                && (irq_flags_2 & FskIrqFlags2::PayloadReady.value()) == FskIrqFlags2::PayloadReady.value()
+            {
+                debug!("PayloadReady in radio mode {}", radio_mode);
+                self.rx_stats.packets_received = self.rx_stats.packets_received.wrapping_add(1);
+                // the FIFO content is retained in standby mode
+                if !rx_continuous {
+                    self.set_standby().await?;
+                }
+                return Ok(());
+            }
+
+            // if an interrupt occurred for other than an error or operation completion, loop to wait again
+        }
+    }
 }
 
-impl<SPI, IV> RadioKind for SX1276_7_8_9<SPI, IV>
+impl<SPI, IV, D> RadioKind for SX1276_7_8_9<SPI, IV, D>
 where
     SPI: SpiBus<u8>,
     IV: InterfaceVariant,
+    D: DelayUs,
 {
     fn get_board_type(&self) -> BoardType {
-        self.board_type
+        self.board_config.board_type
     }
 
     async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
         self.intf.iv.reset(delay).await?;
+        self.modem = Modem::LoRa;
         self.set_sleep(delay).await?; // ensure sleep mode is entered so that the LoRa mode bit is set
         Ok(())
     }
@@ -150,31 +574,66 @@ where
     }
 
     async fn set_standby(&mut self) -> Result<(), RadioError> {
-        self.write_register(Register::RegOpMode, LoRaMode::Standby.value(), false)
-            .await?;
+        let mode = match self.modem {
+            Modem::LoRa => LoRaMode::Standby.value(),
+            Modem::Fsk => FskMode::Standby.value(self.ook),
+        };
+        self.write_register(Register::RegOpMode, mode, false).await?;
+        self.end_tx_continuous_preamble().await?;
         self.intf.iv.disable_rf_switch().await
     }
 
     async fn set_sleep(&mut self, _delay: &mut impl DelayUs) -> Result<bool, RadioError> {
         self.intf.iv.disable_rf_switch().await?;
-        self.write_register(Register::RegOpMode, LoRaMode::Sleep.value(), true)
-            .await?;
+        let mode = match self.modem {
+            Modem::LoRa => LoRaMode::Sleep.value(),
+            Modem::Fsk => FskMode::Sleep.value(self.ook),
+        };
+        self.write_register(Register::RegOpMode, mode, true).await?;
+        self.end_tx_continuous_preamble().await?;
         Ok(false) // warm start unavailable for sx127x
     }
 
     /// The sx127x LoRa mode is set when setting a mode while in sleep mode.
-    async fn set_lora_modem(&mut self, enable_public_network: bool) -> Result<(), RadioError> {
-        if enable_public_network {
-            self.write_register(Register::RegSyncWord, LORA_MAC_PUBLIC_SYNCWORD, false)
-                .await
-        } else {
-            self.write_register(Register::RegSyncWord, LORA_MAC_PRIVATE_SYNCWORD, false)
-                .await
+    async fn set_lora_modem(&mut self, sync_word: SyncWord) -> Result<(), RadioError> {
+        if self.modem != Modem::LoRa {
+            self.write_register(Register::RegOpMode, FskMode::Sleep.value(self.ook), true)
+                .await?;
+            self.write_register(Register::RegOpMode, LoRaMode::Sleep.value(), true)
+                .await?;
+            self.write_register(Register::RegOpMode, LoRaMode::Standby.value(), false)
+                .await?;
+            self.modem = Modem::LoRa;
         }
+        self.write_register(Register::RegSyncWord, sync_word.value(), false)
+            .await
+    }
+
+    /// The sx127x FSK/OOK mode is set when clearing the LoRa mode bit while in sleep mode.
+    async fn set_fsk_modem(&mut self) -> Result<(), RadioError> {
+        if self.modem != Modem::Fsk {
+            self.ook = false;
+            self.write_register(Register::RegOpMode, LoRaMode::Sleep.value(), true)
+                .await?;
+            self.write_register(Register::RegOpMode, FskMode::Sleep.value(self.ook), true)
+                .await?;
+            self.write_register(Register::RegOpMode, FskMode::Standby.value(self.ook), false)
+                .await?;
+            self.modem = Modem::Fsk;
+        }
+        Ok(())
     }
 
     async fn set_oscillator(&mut self) -> Result<(), RadioError> {
-        self.write_register(Register::RegTcxo, TCXO_FOR_OSCILLATOR, false).await
+        // the crystal input is selected on reset
+        if self.board_config.oscillator == Oscillator::Xtal {
+            return Ok(());
+        }
+        let register = match self.is_sx1272_3() {
+            true => Register::RegTcxoSx1272,
+            false => Register::RegTcxo,
+        };
+        self.write_register(register, TCXO_FOR_OSCILLATOR, false).await
     }
 
     async fn set_regulator_mode(&mut self) -> Result<(), RadioError> {
@@ -196,7 +655,8 @@ where
     // Set parameters associated with power for a send operation.
     //   p_out                   desired RF output power (dBm)
     //   mdltn_params            needed for a power vs channel frequency validation
-    //   tx_boosted_if_possible  determine if transmit boost is requested
+    //   tx_boosted_if_possible  determine if transmit boost is requested, when the board has both the RFO and PA_BOOST
+    //                           pins wired
     //   is_tx_prep              indicates which ramp up time to use
     async fn set_tx_power_and_ramp_time(
         &mut self,
@@ -205,7 +665,23 @@ where
         tx_boosted_if_possible: bool,
         is_tx_prep: bool,
     ) -> Result<(), RadioError> {
-        if tx_boosted_if_possible {
+        if p_out > self.board_config.max_tx_power {
+            return Err(RadioError::InvalidOutputPower);
+        }
+        let tx_boosted_if_possible = match self.board_config.pa_wiring {
+            PaWiring::LowPower => false,
+            PaWiring::HighPower => true,
+            PaWiring::LowAndHighPower => tx_boosted_if_possible,
+        };
+        // the power set on initialization only holds until the first send operation, so it is raised to the PA_BOOST
+        // minimum on boards where only that pin is wired
+        let p_out = match tx_boosted_if_possible && !is_tx_prep {
+            true => p_out.max(2),
+            false => p_out,
+        };
+        if self.is_sx1272_3() {
+            self.set_sx1272_tx_power(p_out, tx_boosted_if_possible).await?;
+        } else if tx_boosted_if_possible {
             if !(2..=20).contains(&p_out) {
                 return Err(RadioError::InvalidOutputPower);
             }
@@ -254,7 +730,10 @@ where
             true => RampTime::Ramp40Us,   // for instance, prior to TX or CAD
             false => RampTime::Ramp250Us, // for instance, on initialization
         };
-        self.write_register(Register::RegPaRamp, ramp_time.value(), false).await
+        // retain the FSK/OOK modulation shaping, which shares the register
+        let modulation_shaping = self.read_register(Register::RegPaRamp).await? & 0x60u8;
+        self.write_register(Register::RegPaRamp, modulation_shaping | ramp_time.value(), false)
+            .await
     }
 
     async fn update_retention_list(&mut self) -> Result<(), RadioError> {
@@ -263,8 +742,13 @@ where
 
     async fn set_modulation_params(&mut self, mdltn_params: &ModulationParams) -> Result<(), RadioError> {
         let spreading_factor_val = spreading_factor_value(mdltn_params.spreading_factor)?;
-        let bandwidth_val = bandwidth_value(mdltn_params.bandwidth)?;
+        let bandwidth_val = match self.is_sx1272_3() {
+            true => sx1272_bandwidth_value(mdltn_params.bandwidth)?,
+            false => bandwidth_value(mdltn_params.bandwidth)?,
+        };
         let coding_rate_denominator_val = coding_rate_denominator_value(mdltn_params.coding_rate)?;
+        self.spreading_factor = mdltn_params.spreading_factor;
+        self.bandwidth = mdltn_params.bandwidth;
         debug!(
             "sf = {}, bw = {}, cr_denom = {}",
             spreading_factor_val, bandwidth_val, coding_rate_denominator_val
@@ -285,10 +769,20 @@ where
         self.write_register(Register::RegDetectionThreshold, threshold, false)
             .await?;
 
+        // TxContinuousMode (bit 3) off
         let mut config_2 = self.read_register(Register::RegModemConfig2).await?;
-        config_2 = (config_2 & 0x0fu8) | ((spreading_factor_val << 4) & 0xf0u8);
+        config_2 = (config_2 & 0x07u8) | ((spreading_factor_val << 4) & 0xf0u8);
         self.write_register(Register::RegModemConfig2, config_2, false).await?;
 
+        // the sx1272/3 sets the bandwidth, coding rate, and LDRO in RegModemConfig1 (bits 7-6, 5-3, and 0)
+        if self.is_sx1272_3() {
+            let cr = coding_rate_value(mdltn_params.coding_rate)?;
+            let mut config_1 = self.read_register(Register::RegModemConfig1).await?;
+            config_1 =
+                (config_1 & 0x06u8) | (bandwidth_val << 6) | (cr << 3) | (mdltn_params.low_data_rate_optimize & 0x01u8);
+            return self.write_register(Register::RegModemConfig1, config_1, false).await;
+        }
+
         let mut config_1 = self.read_register(Register::RegModemConfig1).await?;
         config_1 = (config_1 & 0x0fu8) | (bandwidth_val << 4);
         self.write_register(Register::RegModemConfig1, config_1, false).await?;
@@ -318,21 +812,34 @@ where
         )
         .await?;
 
-        let mut config_1 = self.read_register(Register::RegModemConfig1).await?;
-        if pkt_params.implicit_header {
-            config_1 |= 0x01u8;
+        if self.is_sx1272_3() {
+            // implicit header and CRC flags are bits 2 and 1 of RegModemConfig1
+            let mut config_1 = self.read_register(Register::RegModemConfig1).await?;
+            config_1 &= 0xf9u8;
+            if pkt_params.implicit_header {
+                config_1 |= 0x04u8;
+            }
+            if pkt_params.crc_on {
+                config_1 |= 0x02u8;
+            }
+            self.write_register(Register::RegModemConfig1, config_1, false).await?;
         } else {
-            config_1 &= 0xfeu8;
-        }
-        self.write_register(Register::RegModemConfig1, config_1, false).await?;
+            let mut config_1 = self.read_register(Register::RegModemConfig1).await?;
+            if pkt_params.implicit_header {
+                config_1 |= 0x01u8;
+            } else {
+                config_1 &= 0xfeu8;
+            }
+            self.write_register(Register::RegModemConfig1, config_1, false).await?;
 
-        let mut config_2 = self.read_register(Register::RegModemConfig2).await?;
-        if pkt_params.crc_on {
-            config_2 |= 0x04u8;
-        } else {
-            config_2 &= 0xfbu8;
+            let mut config_2 = self.read_register(Register::RegModemConfig2).await?;
+            if pkt_params.crc_on {
+                config_2 |= 0x04u8;
+            } else {
+                config_2 &= 0xfbu8;
+            }
+            self.write_register(Register::RegModemConfig2, config_2, false).await?;
         }
-        self.write_register(Register::RegModemConfig2, config_2, false).await?;
 
         let mut invert_iq = 0x27u8;
         let mut invert_iq2 = 0x1du8;
@@ -344,6 +851,95 @@ where
         self.write_register(Register::RegInvertiq2, invert_iq2, false).await
     }
 
+    async fn set_fsk_modulation_params(&mut self, mdltn_params: &FskModulationParams) -> Result<(), RadioError> {
+        self.ook = is_ook(mdltn_params.pulse_shape);
+        self.write_register(Register::RegOpMode, FskMode::Standby.value(self.ook), false)
+            .await?;
+
+        // Section 2.5.1
+        let bitrate = FXOSC / mdltn_params.bitrate_in_bps;
+        self.write_fsk_register(FskRegister::RegBitrateMsb, ((bitrate >> 8) & 0xff) as u8)
+            .await?;
+        self.write_fsk_register(FskRegister::RegBitrateLsb, (bitrate & 0xff) as u8)
+            .await?;
+
+        let frequency_deviation = (mdltn_params.frequency_deviation_in_hz as f64 / FREQUENCY_SYNTHESIZER_STEP) as u16;
+        self.write_fsk_register(FskRegister::RegFdevMsb, ((frequency_deviation >> 8) & 0x3f) as u8)
+            .await?;
+        self.write_fsk_register(FskRegister::RegFdevLsb, (frequency_deviation & 0xff) as u8)
+            .await?;
+
+        let mut pa_ramp = self.read_register(Register::RegPaRamp).await?;
+        pa_ramp = (pa_ramp & 0x9fu8) | pulse_shape_value(mdltn_params.pulse_shape)?;
+        self.write_register(Register::RegPaRamp, pa_ramp, false).await?;
+
+        let rx_bandwidth_val = rx_bandwidth_value(mdltn_params.rx_bandwidth_in_hz, self.ook)?;
+        debug!(
+            "bitrate = {}, fdev = {}, rx_bw = 0x{:x}",
+            bitrate, frequency_deviation, rx_bandwidth_val
+        );
+        self.write_fsk_register(FskRegister::RegRxBw, rx_bandwidth_val).await?;
+        self.write_fsk_register(FskRegister::RegAfcBw, rx_bandwidth_val).await
+    }
+
+    async fn set_fsk_packet_params(&mut self, pkt_params: &FskPacketParams) -> Result<(), RadioError> {
+        self.write_fsk_register(
+            FskRegister::RegPreambleMsb,
+            ((pkt_params.preamble_length >> 8) & 0x00ff) as u8,
+        )
+        .await?;
+        self.write_fsk_register(FskRegister::RegPreambleLsb, (pkt_params.preamble_length & 0x00ff) as u8)
+            .await?;
+
+        // detector on (1 or 2 bytes), with a tolerance of 10 chip errors
+        let preamble_detect = match pkt_params.preamble_length {
+            0 => 0x0au8,
+            1 => 0x8au8,
+            _ => 0xaau8,
+        };
+        self.write_fsk_register(FskRegister::RegPreambleDetect, preamble_detect)
+            .await?;
+
+        // receiver auto restart after PayloadReady, sync word on
+        let sync_config = 0x50u8 | (pkt_params.sync_word_length - 1);
+        self.write_fsk_register(FskRegister::RegSyncConfig, sync_config).await?;
+        let write_buffer = [FskRegister::RegSyncValue1.write_addr()];
+        self.intf
+            .write(
+                &[
+                    &write_buffer,
+                    &pkt_params.sync_word[..(pkt_params.sync_word_length as usize)],
+                ],
+                false,
+            )
+            .await?;
+
+        let mut packet_config_1 = 0x00u8;
+        if pkt_params.variable_length {
+            packet_config_1 |= 0x80u8;
+        }
+        if pkt_params.whitening {
+            packet_config_1 |= 0x40u8;
+        }
+        match pkt_params.crc_type {
+            FskCrcType::None => {}
+            FskCrcType::Ccitt => packet_config_1 |= 0x10u8,
+            FskCrcType::Ibm => packet_config_1 |= 0x11u8,
+        }
+        self.write_fsk_register(FskRegister::RegPacketConfig1, packet_config_1)
+            .await?;
+        self.write_fsk_register(FskRegister::RegPacketConfig2, 0x40u8).await?; // packet mode
+        self.write_fsk_register(FskRegister::RegPayloadLength, pkt_params.payload_length)
+            .await?;
+
+        // start transmitting once the FIFO is not empty
+        self.write_fsk_register(FskRegister::RegFifoThresh, 0x80u8 | 0x0fu8)
+            .await?;
+
+        self.fsk_variable_length = pkt_params.variable_length;
+        Ok(())
+    }
+
     // Calibrate the image rejection based on the given frequency
     async fn calibrate_image(&mut self, _frequency_in_hz: u32) -> Result<(), RadioError> {
         // An automatic process, but can set bit ImageCalStart in RegImageCal, when the device is in Standby mode.
@@ -352,6 +948,7 @@ where
 
     async fn set_channel(&mut self, frequency_in_hz: u32) -> Result<(), RadioError> {
         debug!("channel = {}", frequency_in_hz);
+        self.frequency_in_hz = frequency_in_hz;
         let frf = (frequency_in_hz as f64 / FREQUENCY_SYNTHESIZER_STEP) as u32;
         self.write_register(Register::RegFrfMsb, ((frf & 0x00FF0000) >> 16) as u8, false)
             .await?;
@@ -362,6 +959,21 @@ where
     }
 
     async fn set_payload(&mut self, payload: &[u8]) -> Result<(), RadioError> {
+        if self.modem == Modem::Fsk {
+            if (payload.len() + self.fsk_variable_length as usize) > FSK_FIFO_SIZE {
+                return Err(RadioError::PayloadSizeUnexpected(payload.len()));
+            }
+            self.clear_fsk_fifo().await?;
+            if self.fsk_variable_length {
+                self.write_register(Register::RegFifo, payload.len() as u8, false)
+                    .await?;
+            }
+            for byte in payload {
+                self.write_register(Register::RegFifo, *byte, false).await?;
+            }
+            return Ok(());
+        }
+
         self.write_register(Register::RegFifoAddrPtr, 0x00u8, false).await?;
         self.write_register(Register::RegPayloadLength, 0x00u8, false).await?;
         for byte in payload {
@@ -371,9 +983,44 @@ where
             .await
     }
 
-    async fn do_tx(&mut self, _timeout_in_ms: u32) -> Result<(), RadioError> {
+    async fn do_tx(&mut self, timeout_in_ms: u32) -> Result<(), RadioError> {
+        self.tx_timeout_in_ms = timeout_in_ms;
         self.intf.iv.enable_rf_switch_tx().await?;
 
+        let mode = match self.modem {
+            Modem::LoRa => LoRaMode::Tx.value(),
+            Modem::Fsk => FskMode::Tx.value(self.ook),
+        };
+        self.write_register(Register::RegOpMode, mode, false).await
+    }
+
+    // The carrier is transmitted by the FSK modem, selected by the caller, in continuous mode with no frequency
+    // deviation
+    async fn do_tx_continuous_wave(&mut self) -> Result<(), RadioError> {
+        self.ook = false; // an OOK transmitter would key the carrier rather than send it unmodulated
+        self.write_fsk_register(FskRegister::RegFdevMsb, 0x00u8).await?;
+        self.write_fsk_register(FskRegister::RegFdevLsb, 0x00u8).await?;
+        self.write_fsk_register(FskRegister::RegPacketConfig2, 0x00u8).await?; // continuous mode
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.write_register(Register::RegOpMode, FskMode::Tx.value(self.ook), false)
+            .await
+    }
+
+    // The LoRa modem has no endless preamble, so packets with the longest preamble are sent back to back in
+    // TxContinuousMode, until standby or sleep mode is set
+    async fn do_tx_continuous_preamble(&mut self) -> Result<(), RadioError> {
+        if self.tx_continuous_preamble.is_none() {
+            let preamble_msb = self.read_register(Register::RegPreambleMsb).await?;
+            let preamble_lsb = self.read_register(Register::RegPreambleLsb).await?;
+            self.tx_continuous_preamble = Some((preamble_msb, preamble_lsb));
+        }
+        self.write_register(Register::RegPreambleMsb, 0xffu8, false).await?;
+        self.write_register(Register::RegPreambleLsb, 0xffu8, false).await?;
+        self.set_payload(&[0x00u8]).await?;
+        let config_2 = self.read_register(Register::RegModemConfig2).await?;
+        self.write_register(Register::RegModemConfig2, config_2 | 0x08u8, false)
+            .await?;
+        self.intf.iv.enable_rf_switch_tx().await?;
         self.write_register(Register::RegOpMode, LoRaMode::Tx.value(), false)
             .await
     }
@@ -385,15 +1032,34 @@ where
         rx_continuous: bool,
         rx_boosted_if_supported: bool,
         symbol_timeout: u16,
-        _rx_timeout_in_ms: u32,
+        rx_timeout_in_ms: u32,
     ) -> Result<(), RadioError> {
-        if let Some(&_duty_cycle) = duty_cycle_params {
-            return Err(RadioError::DutyCycleUnsupported);
+        let mut symbol_timeout_final = symbol_timeout;
+        self.rx_duty_cycle = None;
+        self.rx_timeout_in_ms = match rx_continuous {
+            true => 0,
+            false => rx_timeout_in_ms,
+        };
+        if let Some(&duty_cycle) = duty_cycle_params {
+            if rx_continuous {
+                return Err(RadioError::DutyCycleRxContinuousUnsupported);
+            }
+            if self.delay.is_none() {
+                return Err(RadioError::DutyCycleUnsupported);
+            }
+            // the windows and sleep periods are timed in software, which would spin on the IRQ flags without a window
+            if duty_cycle.rx_time == 0 {
+                return Err(RadioError::InvalidDutyCycleParams);
+            }
+            // each receive window times out unless a preamble is detected
+            let symbol_time_in_us = self.symbol_time_in_us() as u64;
+            let window_in_us = duty_cycle_interval_in_us(duty_cycle.rx_time) as u64;
+            symbol_timeout_final = ((window_in_us + symbol_time_in_us - 1) / symbol_time_in_us).clamp(1, 0xff) as u16;
+            self.rx_duty_cycle = Some(duty_cycle);
         }
 
         self.intf.iv.enable_rf_switch_rx().await?;
 
-        let mut symbol_timeout_final = symbol_timeout;
         if rx_continuous {
             symbol_timeout_final = 0;
         }
@@ -444,18 +1110,133 @@ where
     }
 
     async fn get_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
-        let rssi_raw = self.read_register(Register::RegPktRssiValue).await?;
-        let rssi = (rssi_raw as i16) - 157i16; // or -164 for low frequency port ???
-        let snr_raw = self.read_register(Register::RegPktRssiValue).await?;
-        let snr = snr_raw as i16;
-        Ok(PacketStatus { rssi, snr })
+        let pkt_rssi_value = self.read_register(Register::RegPktRssiValue).await?;
+        let pkt_snr_value = self.read_register(Register::RegPktSnrValue).await?;
+        let (rssi, snr) = packet_rssi_and_snr(pkt_rssi_value, pkt_snr_value, self.rssi_offset(), !self.is_sx1272_3());
+
+        // the frequency error is a 20 bit signed value, scaled as FreqError * 2^24 / FXOSC * BW / 500 kHz
+        let freq_error_msb = self.read_register(Register::RegFreqErrorMsb).await?;
+        let freq_error_mid = self.read_register(Register::RegFreqErrorMid).await?;
+        let freq_error_lsb = self.read_register(Register::RegFreqErrorLsb).await?;
+        let freq_error = ((((freq_error_msb as u32) << 28)
+            | ((freq_error_mid as u32) << 20)
+            | ((freq_error_lsb as u32) << 12)) as i32
+            >> 12) as i64;
+        let frequency_error_in_hz =
+            (freq_error * (1 << 24) * self.bandwidth.value_in_hz() as i64 / (FXOSC as i64 * 500_000)) as i32;
+
+        let implicit_header_flag = match self.is_sx1272_3() {
+            true => 0x04u8,
+            false => 0x01u8,
+        };
+        let implicit_header = (self.read_register(Register::RegModemConfig1).await? & implicit_header_flag) != 0;
+        let (coding_rate, crc_present, header_payload_length) = match implicit_header {
+            true => (None, None, None),
+            false => {
+                let modem_stat = self.read_register(Register::RegModemStat).await?;
+                let hop_channel = self.read_register(Register::RegHopChannel).await?;
+                let payload_length = self.read_register(Register::RegRxNbBytes).await?;
+                (
+                    rx_coding_rate(modem_stat),
+                    Some((hop_channel & 0x40u8) != 0),
+                    Some(payload_length),
+                )
+            }
+        };
+
+        Ok(PacketStatus {
+            rssi,
+            snr,
+            frequency_error_in_hz: Some(frequency_error_in_hz),
+            coding_rate,
+            crc_present,
+            header_payload_length,
+            ..Default::default()
+        })
+    }
+
+    async fn get_rssi(&mut self) -> Result<i16, RadioError> {
+        match self.modem {
+            Modem::Fsk => {
+                let rssi_value = self.read_fsk_register(FskRegister::RegRssiValue).await?;
+                Ok(-((rssi_value / 2) as i16))
+            }
+            Modem::LoRa => {
+                let rssi_value = self.read_register(Register::RegRssiValue).await?;
+                Ok(self.rssi_offset() + rssi_value as i16)
+            }
+        }
+    }
+
+    async fn get_rx_stats(&mut self) -> Result<RxStats, RadioError> {
+        Ok(self.rx_stats)
     }
 
-    async fn do_cad(
+    async fn reset_rx_stats(&mut self) -> Result<(), RadioError> {
+        self.rx_stats = RxStats::default();
+        Ok(())
+    }
+
+    async fn do_fsk_rx(
         &mut self,
-        _mdltn_params: &ModulationParams,
+        rx_continuous: bool,
         rx_boosted_if_supported: bool,
+        rx_timeout_in_ms: u32,
     ) -> Result<(), RadioError> {
+        self.rx_timeout_in_ms = match rx_continuous {
+            true => 0,
+            false => rx_timeout_in_ms,
+        };
+        self.intf.iv.enable_rf_switch_rx().await?;
+
+        let mut lna_gain_final = LnaGain::G1.value();
+        if rx_boosted_if_supported {
+            lna_gain_final = LnaGain::G1.boosted_value();
+        }
+        self.write_register(Register::RegLna, lna_gain_final, false).await?;
+
+        // AGC auto on, receiver triggered by preamble detection
+        self.write_fsk_register(FskRegister::RegRxConfig, 0x0eu8).await?;
+        self.clear_fsk_fifo().await?;
+
+        self.write_register(Register::RegOpMode, FskMode::Rx.value(self.ook), false)
+            .await
+    }
+
+    async fn get_fsk_rx_payload(
+        &mut self,
+        rx_pkt_params: &FskPacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError> {
+        let payload_length = match rx_pkt_params.variable_length {
+            true => self.read_register(Register::RegFifo).await?,
+            false => rx_pkt_params.payload_length,
+        };
+        if (payload_length as usize) > receiving_buffer.len() {
+            return Err(RadioError::PayloadSizeMismatch(
+                payload_length as usize,
+                receiving_buffer.len(),
+            ));
+        }
+        for i in 0..payload_length {
+            let byte = self.read_register(Register::RegFifo).await?;
+            receiving_buffer[i as usize] = byte;
+        }
+
+        Ok(payload_length)
+    }
+
+    async fn get_fsk_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
+        let rssi_raw = self.read_fsk_register(FskRegister::RegRssiValue).await?;
+        let rssi = -((rssi_raw / 2) as i16);
+        Ok(PacketStatus::new(rssi, 0))
+    }
+
+    // The Sx127x chips look for activity over a fixed number of symbols, without chained operations
+    async fn do_cad(&mut self, cad_params: &CadParams, rx_boosted_if_supported: bool) -> Result<(), RadioError> {
+        if cad_params.exit_mode != CadExitMode::CadOnly {
+            return Err(RadioError::CadExitModeUnsupported);
+        }
         self.intf.iv.enable_rf_switch_rx().await?;
 
         let mut lna_gain_final = LnaGain::G1.value();
@@ -470,6 +1251,10 @@ where
 
     // Set the IRQ mask to disable unwanted interrupts, enable interrupts on DIO0 (the IRQ pin), and allow interrupts.
     async fn set_irq_params(&mut self, radio_mode: Option<RadioMode>) -> Result<(), RadioError> {
+        if self.modem == Modem::Fsk {
+            return self.set_fsk_irq_params().await;
+        }
+
         match radio_mode {
             Some(RadioMode::Transmit) => {
                 self.write_register(
@@ -486,7 +1271,7 @@ where
 
                 self.write_register(Register::RegIrqFlags, 0x00u8, false).await?;
             }
-            Some(RadioMode::Receive) => {
+            Some(RadioMode::Receive) | Some(RadioMode::ReceiveDutyCycle) => {
                 self.write_register(
                     Register::RegIrqFlagsMask,
                     IrqMask::All.value()
@@ -533,17 +1318,28 @@ where
         Ok(())
     }
 
+    async fn get_device_errors(&mut self) -> Result<DeviceErrors, RadioError> {
+        Ok(DeviceErrors::default())
+    }
+
+    async fn clear_device_errors_and_calibrate(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
     /// Process the radio IRQ.  Log unexpected interrupts, but only bail out on timeout.  Packets from other devices can cause unexpected interrupts.
     async fn process_irq(
         &mut self,
         radio_mode: RadioMode,
-        _rx_continuous: bool,
+        rx_continuous: bool,
         cad_activity_detected: Option<&mut bool>,
     ) -> Result<(), RadioError> {
+        if self.modem == Modem::Fsk {
+            return self.process_fsk_irq(radio_mode, rx_continuous).await;
+        }
         loop {
             debug!("process_irq loop entered");
 
-            self.intf.iv.await_irq().await?;
+            self.await_irq(radio_mode).await?;
 
             let irq_flags = self.read_register(Register::RegIrqFlags).await?;
             self.write_register(Register::RegIrqFlags, 0xffu8, false).await?; // clear all interrupts
@@ -562,12 +1358,14 @@ where
                     debug!("TxDone in radio mode {}", radio_mode);
                     return Ok(());
                 }
-            } else if radio_mode == RadioMode::Receive {
+            } else if radio_mode == RadioMode::Receive || radio_mode == RadioMode::ReceiveDutyCycle {
                 if (irq_flags & IrqMask::CRCError.value()) == IrqMask::CRCError.value() {
                     debug!("CRCError in radio mode {}", radio_mode);
+                    self.rx_stats.crc_errors = self.rx_stats.crc_errors.wrapping_add(1);
                 }
                 if (irq_flags & IrqMask::RxDone.value()) == IrqMask::RxDone.value() {
                     debug!("RxDone in radio mode {}", radio_mode);
+                    self.rx_stats.packets_received = self.rx_stats.packets_received.wrapping_add(1);
                     return Ok(());
                 }
                 if (irq_flags & IrqMask::RxTimeout.value()) == IrqMask::RxTimeout.value() {
@@ -589,3 +1387,29 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_rssi_and_snr_positive_snr() {
+        // 8 dB SNR, with the RSSI slope corrected by 16/15
+        assert_eq!(packet_rssi_and_snr(100, 0x20, RSSI_OFFSET_HF, true), (-51, 8));
+        assert_eq!(packet_rssi_and_snr(100, 0x20, RSSI_OFFSET_LF, true), (-58, 8));
+        assert_eq!(packet_rssi_and_snr(15, 0x29, RSSI_OFFSET_HF, true), (-141, 10));
+        // without the correction on the Sx1272/3
+        assert_eq!(packet_rssi_and_snr(100, 0x00, RSSI_OFFSET_SX1272, false), (-39, 0));
+    }
+
+    #[test]
+    fn packet_rssi_and_snr_negative_snr() {
+        // -3 dB SNR (0xf4 is -12 quarter dB), added to the RSSI
+        assert_eq!(packet_rssi_and_snr(60, 0xf4, RSSI_OFFSET_HF, true), (-100, -3));
+        assert_eq!(packet_rssi_and_snr(60, 0xf4, RSSI_OFFSET_SX1272, false), (-82, -3));
+        // -4.5 dB SNR, rounded to -4 dB
+        assert_eq!(packet_rssi_and_snr(90, 0xee, RSSI_OFFSET_LF, true), (-78, -4));
+        // -20 dB SNR, at the sensitivity limit
+        assert_eq!(packet_rssi_and_snr(30, 0xb0, RSSI_OFFSET_HF, true), (-147, -20));
+    }
+}
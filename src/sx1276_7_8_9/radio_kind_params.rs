@@ -18,6 +18,26 @@ impl LoRaMode {
     }
 }
 
+/// Internal sx127x FSK/OOK modes (signified by a cleared most significant bit flag)
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum FskMode {
+    Sleep = 0x00,
+    Standby = 0x01,
+    Tx = 0x03,
+    Rx = 0x05,
+}
+
+impl FskMode {
+    /// Mode value, including the OOK modulation type flag
+    pub fn value(self, ook: bool) -> u8 {
+        match ook {
+            true => (self as u8) | 0x20u8,
+            false => self as u8,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 pub enum DioMapping1Dio0 {
@@ -34,6 +54,20 @@ impl DioMapping1Dio0 {
     }
 }
 
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum FskDioMapping1Dio0 {
+    PacketSentPayloadReady = 0x00,
+    CrcOk = 0x40,
+    Mask = 0x3f,
+}
+
+impl FskDioMapping1Dio0 {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 pub enum IrqMask {
@@ -55,6 +89,45 @@ impl IrqMask {
     }
 }
 
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum FskIrqFlags1 {
+    ModeReady = 0x80,
+    RxReady = 0x40,
+    TxReady = 0x20,
+    PllLock = 0x10,
+    Rssi = 0x08,
+    Timeout = 0x04,
+    PreambleDetect = 0x02,
+    SyncAddressMatch = 0x01,
+}
+
+#[allow(dead_code)]
+impl FskIrqFlags1 {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum FskIrqFlags2 {
+    FifoFull = 0x80,
+    FifoEmpty = 0x40,
+    FifoLevel = 0x20,
+    FifoOverrun = 0x10,
+    PacketSent = 0x08,
+    PayloadReady = 0x04,
+    CrcOk = 0x02,
+    LowBat = 0x01,
+}
+
+impl FskIrqFlags2 {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 pub enum Register {
@@ -77,6 +150,8 @@ pub enum Register {
     RegPktSnrValue = 0x19,
     RegModemStat = 0x18,
     RegPktRssiValue = 0x1a,
+    RegRssiValue = 0x1b,
+    RegHopChannel = 0x1c,
     RegModemConfig1 = 0x1d,
     RegModemConfig2 = 0x1e,
     RegSymbTimeoutLsb = 0x1f,
@@ -98,6 +173,8 @@ pub enum Register {
     RegVersion = 0x42,
     RegTcxo = 0x4b,
     RegPaDac = 0x4d,
+    RegTcxoSx1272 = 0x58,
+    RegPaDacSx1272 = 0x5a,
 }
 
 impl Register {
@@ -109,6 +186,40 @@ impl Register {
     }
 }
 
+/// Registers of the FSK/OOK page, which share addresses 0x0d to 0x3f with the LoRa page
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum FskRegister {
+    RegBitrateMsb = 0x02,
+    RegBitrateLsb = 0x03,
+    RegFdevMsb = 0x04,
+    RegFdevLsb = 0x05,
+    RegRxConfig = 0x0d,
+    RegRssiValue = 0x11,
+    RegRxBw = 0x12,
+    RegAfcBw = 0x13,
+    RegPreambleDetect = 0x1f,
+    RegPreambleMsb = 0x25,
+    RegPreambleLsb = 0x26,
+    RegSyncConfig = 0x27,
+    RegSyncValue1 = 0x28,
+    RegPacketConfig1 = 0x30,
+    RegPacketConfig2 = 0x31,
+    RegPayloadLength = 0x32,
+    RegFifoThresh = 0x35,
+    RegIrqFlags1 = 0x3e,
+    RegIrqFlags2 = 0x3f,
+}
+
+impl FskRegister {
+    pub fn read_addr(self) -> u8 {
+        (self as u8) & 0x7f
+    }
+    pub fn write_addr(self) -> u8 {
+        (self as u8) | 0x80
+    }
+}
+
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 pub enum RampTime {
@@ -174,6 +285,7 @@ impl PaDac {
 pub enum PaConfig {
     PaBoost = 0x80,
     MaxPower7NoPaBoost = 0x70,
+    RfoSx1272 = 0x00,
 }
 
 impl PaConfig {
@@ -247,16 +359,29 @@ pub fn bandwidth_value(bandwidth: Bandwidth) -> Result<u8, RadioError> {
         Bandwidth::_125KHz => Ok(0x07),
         Bandwidth::_250KHz => Ok(0x08),
         Bandwidth::_500KHz => Ok(0x09),
+        Bandwidth::_203KHz | Bandwidth::_406KHz | Bandwidth::_812KHz | Bandwidth::_1625KHz => {
+            Err(RadioError::UnavailableBandwidth)
+        }
+    }
+}
+
+// Bandwidth bits of RegModemConfig1 on the sx1272/3
+pub fn sx1272_bandwidth_value(bandwidth: Bandwidth) -> Result<u8, RadioError> {
+    match bandwidth {
+        Bandwidth::_125KHz => Ok(0x00),
+        Bandwidth::_250KHz => Ok(0x01),
+        Bandwidth::_500KHz => Ok(0x02),
+        _ => Err(RadioError::UnavailableBandwidth),
     }
 }
 
-#[allow(dead_code)]
 pub fn coding_rate_value(coding_rate: CodingRate) -> Result<u8, RadioError> {
     match coding_rate {
         CodingRate::_4_5 => Ok(0x01),
         CodingRate::_4_6 => Ok(0x02),
         CodingRate::_4_7 => Ok(0x03),
         CodingRate::_4_8 => Ok(0x04),
+        CodingRate::_4_5Li | CodingRate::_4_6Li | CodingRate::_4_8Li => Err(RadioError::UnavailableCodingRate),
     }
 }
 
@@ -266,5 +391,67 @@ pub fn coding_rate_denominator_value(coding_rate: CodingRate) -> Result<u8, Radi
         CodingRate::_4_6 => Ok(0x06),
         CodingRate::_4_7 => Ok(0x07),
         CodingRate::_4_8 => Ok(0x08),
+        CodingRate::_4_5Li | CodingRate::_4_6Li | CodingRate::_4_8Li => Err(RadioError::UnavailableCodingRate),
+    }
+}
+
+// Coding rate of the last received header, given in bits 7-5 of RegModemStat
+pub fn rx_coding_rate(modem_stat: u8) -> Option<CodingRate> {
+    match modem_stat >> 5 {
+        0x01 => Some(CodingRate::_4_5),
+        0x02 => Some(CodingRate::_4_6),
+        0x03 => Some(CodingRate::_4_7),
+        0x04 => Some(CodingRate::_4_8),
+        _ => None,
     }
 }
+
+// Modulation shaping bits of RegPaRamp
+pub fn pulse_shape_value(pulse_shape: PulseShape) -> Result<u8, RadioError> {
+    match pulse_shape {
+        PulseShape::None => Ok(0x00),
+        PulseShape::GaussianBt1_0 => Ok(0x20),
+        PulseShape::GaussianBt0_5 => Ok(0x40),
+        PulseShape::GaussianBt0_3 => Ok(0x60),
+        PulseShape::GaussianBt0_7 => Err(RadioError::UnavailablePulseShape),
+        PulseShape::Ook => Ok(0x00),
+        PulseShape::OokCutoffBitrate => Ok(0x20),
+        PulseShape::OokCutoff2xBitrate => Ok(0x40),
+    }
+}
+
+pub fn is_ook(pulse_shape: PulseShape) -> bool {
+    matches!(
+        pulse_shape,
+        PulseShape::Ook | PulseShape::OokCutoffBitrate | PulseShape::OokCutoff2xBitrate
+    )
+}
+
+// RxBwMant values (16, 20, or 24) and their RegRxBw bits, in decreasing mantissa order
+const RX_BANDWIDTH_MANTISSAS: [(u32, u8); 3] = [(24, 0x10), (20, 0x08), (16, 0x00)];
+
+// Iterate over the FSK (or OOK) receive bandwidths in increasing bandwidth order, as FXOSC / (RxBwMant * 2^(RxBwExp + 2))
+// for FSK and FXOSC / (RxBwMant * 2^(RxBwExp + 3)) for OOK
+fn rx_bandwidths(ook: bool) -> impl Iterator<Item = (u32, u8)> {
+    (1..=7u8).rev().flat_map(move |exp| {
+        RX_BANDWIDTH_MANTISSAS.iter().map(move |(mant, mant_bits)| {
+            let bandwidth_in_hz = 32_000_000 / (mant << (exp as u32 + 2 + ook as u32));
+            (bandwidth_in_hz, mant_bits | exp)
+        })
+    })
+}
+
+// Get the smallest receive bandwidth covering the given bandwidth
+pub fn supported_rx_bandwidth_in_hz(bandwidth_in_hz: u32, ook: bool) -> Result<u32, RadioError> {
+    rx_bandwidths(ook)
+        .find(|(bw, _)| *bw >= bandwidth_in_hz)
+        .map(|(bw, _)| bw)
+        .ok_or(RadioError::UnavailableRxBandwidth)
+}
+
+pub fn rx_bandwidth_value(bandwidth_in_hz: u32, ook: bool) -> Result<u8, RadioError> {
+    rx_bandwidths(ook)
+        .find(|(bw, _)| *bw == bandwidth_in_hz)
+        .map(|(_, value)| value)
+        .ok_or(RadioError::UnavailableRxBandwidth)
+}
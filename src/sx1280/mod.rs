@@ -0,0 +1,684 @@
+pub(crate) mod radio_kind_params;
+
+use defmt::debug;
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::spi::*;
+use radio_kind_params::*;
+
+use crate::mod_params::*;
+use crate::{InterfaceVariant, RadioKind, SpiInterface};
+
+// Internal frequency of the radio
+const SX1280_XTAL_FREQ: u64 = 52000000;
+
+// The PLL step is the crystal frequency divided by 2^18
+const SX1280_PLL_STEP_SHIFT_AMOUNT: u32 = 18;
+
+// Timeout count requesting continuous receive
+const SX1280_RX_CONTINUOUS_TIMEOUT: u16 = 0xFFFF;
+
+// Frequencies supported by the Sx1280 and Sx1281 (Hz)
+const SX1280_FREQUENCY_RANGE_IN_HZ: core::ops::RangeInclusive<u32> = 2_400_000_000..=2_500_000_000;
+
+impl ModulationParams {
+    /// Create modulation parameters specific to the Sx1280 and Sx1281 chips, which support the 2.4 GHz band and the
+    /// 203, 406, 812, and 1625 kHz bandwidths
+    pub fn new_for_sx1280(
+        spreading_factor: SpreadingFactor,
+        bandwidth: Bandwidth,
+        coding_rate: CodingRate,
+        frequency_in_hz: u32,
+    ) -> Result<Self, RadioError> {
+        // Parameter validation
+        spreading_factor_value(spreading_factor)?;
+        bandwidth_value(bandwidth)?;
+        coding_rate_value(coding_rate)?;
+        if !SX1280_FREQUENCY_RANGE_IN_HZ.contains(&frequency_in_hz) {
+            return Err(RadioError::InvalidFrequency);
+        }
+
+        // the symbol times at the Sx128x bandwidths do not need low data rate optimization
+        Ok(Self {
+            spreading_factor,
+            bandwidth,
+            coding_rate,
+            low_data_rate_optimize: 0x00u8,
+            frequency_in_hz,
+        })
+    }
+}
+
+impl PacketParams {
+    /// Create packet parameters specific to the LoRa chip kind and type
+    pub fn new_for_sx1280(
+        mut preamble_length: u16,
+        implicit_header: bool,
+        payload_length: u8,
+        crc_on: bool,
+        iq_inverted: bool,
+        modulation_params: &ModulationParams,
+    ) -> Result<Self, RadioError> {
+        if ((modulation_params.spreading_factor == SpreadingFactor::_5)
+            || (modulation_params.spreading_factor == SpreadingFactor::_6))
+            && (preamble_length < 12)
+        {
+            preamble_length = 12;
+        }
+
+        Ok(Self {
+            preamble_length,
+            implicit_header,
+            payload_length,
+            crc_on,
+            iq_inverted,
+        })
+    }
+}
+
+/// Base for the RadioKind implementation for the LoRa chip kind and board type
+pub struct SX1280<SPI, IV> {
+    board_type: BoardType,
+    intf: SpiInterface<SPI, IV>,
+    rx_stats: RxStats, // counted in software, as the chip has no statistics counters for LoRa packets
+}
+
+impl<SPI, IV> SX1280<SPI, IV>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+{
+    /// Create an instance of the RadioKind implementation for the LoRa chip kind and board type
+    pub fn new(board_type: BoardType, spi: SPI, mut iv: IV) -> Self {
+        iv.set_board_type(board_type);
+        let intf = SpiInterface::new(spi, iv);
+        Self {
+            board_type,
+            intf,
+            rx_stats: RxStats {
+                header_errors: Some(0),
+                ..Default::default()
+            },
+        }
+    }
+
+    // Utility functions
+
+    async fn read_register(&mut self, register: Register) -> Result<u8, RadioError> {
+        let mut value = [0x00u8];
+        self.intf
+            .read(
+                &[&[OpCode::ReadRegister.value(), register.addr1(), register.addr2(), 0x00u8]],
+                &mut value,
+                None,
+            )
+            .await?;
+        Ok(value[0])
+    }
+
+    async fn write_register(&mut self, register: Register, value: u8) -> Result<(), RadioError> {
+        let register_and_value = [OpCode::WriteRegister.value(), register.addr1(), register.addr2(), value];
+        self.intf.write(&[&register_and_value], false).await
+    }
+
+    // Give a timeout to a SetTx, SetRx, or SetRxDutyCycle command as a period base and a 16 bit count of periods
+    fn period_base_and_count(period_base: PeriodBase, count: u16) -> [u8; 3] {
+        [period_base.value(), ((count >> 8) & 0xFF) as u8, (count & 0xFF) as u8]
+    }
+
+    // The duty cycle intervals are given in steps of 15.625 us; use the finest period base able to hold both
+    fn duty_cycle_periods(duty_cycle: &DutyCycleParams) -> Result<(PeriodBase, u16, u16), RadioError> {
+        for (period_base, steps_per_period) in [
+            (PeriodBase::_15_625Us, 1u32),
+            (PeriodBase::_62_5Us, 4u32),
+            (PeriodBase::_1Ms, 64u32),
+            (PeriodBase::_4Ms, 256u32),
+        ] {
+            let rx_count = duty_cycle.rx_time / steps_per_period;
+            let sleep_count = duty_cycle.sleep_time / steps_per_period;
+            if rx_count <= u16::MAX as u32 && sleep_count <= u16::MAX as u32 {
+                return Ok((period_base, rx_count as u16, sleep_count as u16));
+            }
+        }
+        Err(RadioError::DutyCycleUnsupported)
+    }
+
+    // Round to the nearest PLL step
+    fn convert_freq_in_hz_to_pll_step(freq_in_hz: u32) -> u32 {
+        ((((freq_in_hz as u64) << SX1280_PLL_STEP_SHIFT_AMOUNT) + (SX1280_XTAL_FREQ >> 1)) / SX1280_XTAL_FREQ) as u32
+    }
+}
+
+impl<SPI, IV> RadioKind for SX1280<SPI, IV>
+where
+    SPI: SpiBus<u8>,
+    IV: InterfaceVariant,
+{
+    fn get_board_type(&self) -> BoardType {
+        self.board_type
+    }
+
+    async fn reset(&mut self, delay: &mut impl DelayUs) -> Result<(), RadioError> {
+        self.intf.iv.reset(delay).await
+    }
+
+    // Wakeup the radio if it is in Sleep or ReceiveDutyCycle mode; otherwise, ensure it is not busy.
+    async fn ensure_ready(&mut self, mode: RadioMode) -> Result<(), RadioError> {
+        if mode == RadioMode::Sleep || mode == RadioMode::ReceiveDutyCycle {
+            let op_code_and_null = [OpCode::GetStatus.value(), 0x00u8];
+            self.intf.write(&[&op_code_and_null], false).await?;
+        } else {
+            self.intf.iv.wait_on_busy().await?;
+        }
+        Ok(())
+    }
+
+    // The Sx128x chips have no DIO dedicated to an RF switch; it is controlled through the interface variant.
+    async fn init_rf_switch(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    // Use standby mode RC (not XOSC).
+    async fn set_standby(&mut self) -> Result<(), RadioError> {
+        let op_code_and_standby_mode = [OpCode::SetStandby.value(), StandbyMode::RC.value()];
+        self.intf.write(&[&op_code_and_standby_mode], false).await?;
+        self.intf.iv.disable_rf_switch().await
+    }
+
+    async fn set_sleep(&mut self, delay: &mut impl DelayUs) -> Result<bool, RadioError> {
+        self.intf.iv.disable_rf_switch().await?;
+        let sleep_params = SleepParams {
+            data_buffer_retention: false,
+            warm_start: true,
+        };
+        let op_code_and_sleep_params = [OpCode::SetSleep.value(), sleep_params.value()];
+        self.intf.write(&[&op_code_and_sleep_params], true).await?;
+        delay.delay_ms(2).await;
+
+        Ok(sleep_params.warm_start) // indicate if warm start enabled
+    }
+
+    /// Configure the radio for LoRa and the given sync word.
+    async fn set_lora_modem(&mut self, sync_word: SyncWord) -> Result<(), RadioError> {
+        let op_code_and_packet_type = [OpCode::SetPacketType.value(), PacketType::LoRa.value()];
+        self.intf.write(&[&op_code_and_packet_type], false).await?;
+
+        let syncword = sync_word.value();
+        // each nibble of the syncword goes in the upper nibble of its register, preserving the lower nibbles
+        let msb = self.read_register(Register::LoRaSyncwordMsb).await?;
+        self.write_register(Register::LoRaSyncwordMsb, (syncword & 0xF0) | (msb & 0x0F))
+            .await?;
+        let lsb = self.read_register(Register::LoRaSyncwordLsb).await?;
+        self.write_register(Register::LoRaSyncwordLsb, ((syncword & 0x0F) << 4) | (lsb & 0x0F))
+            .await
+    }
+
+    async fn set_fsk_modem(&mut self) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    // The Sx128x chips are clocked by a crystal, without TCXO control.
+    async fn set_oscillator(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    // Set the power regulators operating mode to DC_DC.  Using only LDO implies that the Rx/Tx current is doubled.
+    async fn set_regulator_mode(&mut self) -> Result<(), RadioError> {
+        let op_code_and_regulator_mode = [OpCode::SetRegulatorMode.value(), RegulatorMode::UseDCDC.value()];
+        self.intf.write(&[&op_code_and_regulator_mode], false).await
+    }
+
+    async fn set_tx_rx_buffer_base_address(
+        &mut self,
+        tx_base_addr: usize,
+        rx_base_addr: usize,
+    ) -> Result<(), RadioError> {
+        if tx_base_addr > 255 || rx_base_addr > 255 {
+            return Err(RadioError::InvalidBaseAddress(tx_base_addr, rx_base_addr));
+        }
+        let op_code_and_base_addrs = [
+            OpCode::SetBufferBaseAddress.value(),
+            tx_base_addr as u8,
+            rx_base_addr as u8,
+        ];
+        self.intf.write(&[&op_code_and_base_addrs], false).await
+    }
+
+    // Set parameters associated with power for a send operation.  The Sx128x chips have a single PA.
+    //   output_power            desired RF output power (dBm)
+    //   mdltn_params            not needed, since all channels are in the 2.4 GHz band
+    //   tx_boosted_if_possible  not applicable
+    //   is_tx_prep              indicates which ramp up time to use
+    async fn set_tx_power_and_ramp_time(
+        &mut self,
+        output_power: i32,
+        _mdltn_params: Option<&ModulationParams>,
+        _tx_boosted_if_possible: bool,
+        is_tx_prep: bool,
+    ) -> Result<(), RadioError> {
+        let ramp_time = match is_tx_prep {
+            true => RampTime::Ramp02Us,  // for instance, prior to TX or CAD
+            false => RampTime::Ramp20Us, // for instance, on initialization
+        };
+
+        if !(-18..=13).contains(&output_power) {
+            return Err(RadioError::InvalidOutputPower);
+        }
+        // the power is given to the radio as an offset from -18 dBm
+        let tx_params_power = (output_power + 18) as u8;
+
+        debug!("tx power = {}", output_power);
+
+        let op_code_and_tx_params = [OpCode::SetTxParams.value(), tx_params_power, ramp_time.value()];
+        self.intf.write(&[&op_code_and_tx_params], false).await
+    }
+
+    // The whole configuration is retained in sleep mode when warm starting.
+    async fn update_retention_list(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn set_modulation_params(&mut self, mdltn_params: &ModulationParams) -> Result<(), RadioError> {
+        let spreading_factor_val = spreading_factor_value(mdltn_params.spreading_factor)?;
+        let bandwidth_val = bandwidth_value(mdltn_params.bandwidth)?;
+        let coding_rate_val = coding_rate_value(mdltn_params.coding_rate)?;
+        debug!(
+            "sf = {}, bw = {}, cr = {}",
+            spreading_factor_val, bandwidth_val, coding_rate_val
+        );
+        let op_code_and_mod_params = [
+            OpCode::SetModulationParams.value(),
+            spreading_factor_val,
+            bandwidth_val,
+            coding_rate_val,
+        ];
+        self.intf.write(&[&op_code_and_mod_params], false).await?;
+
+        // adjust the demodulator to the spreading factor, then enable the frequency error compensation (see
+        // DS_SX1280-1_V3.2 datasheet section 14.4.1)
+        self.write_register(
+            Register::SfAdditionalConfiguration,
+            sf_additional_configuration_value(mdltn_params.spreading_factor),
+        )
+        .await?;
+        self.write_register(Register::FrequencyErrorCorrection, 0x01u8).await
+    }
+
+    async fn set_packet_params(&mut self, pkt_params: &PacketParams) -> Result<(), RadioError> {
+        let op_code_and_pkt_params = [
+            OpCode::SetPacketParams.value(),
+            preamble_length_value(pkt_params.preamble_length),
+            match pkt_params.implicit_header {
+                true => 0x80u8,
+                false => 0x00u8,
+            },
+            pkt_params.payload_length,
+            match pkt_params.crc_on {
+                true => 0x20u8,
+                false => 0x00u8,
+            },
+            match pkt_params.iq_inverted {
+                true => 0x00u8,
+                false => 0x40u8,
+            },
+            0x00u8, // unused
+            0x00u8, // unused
+        ];
+        self.intf.write(&[&op_code_and_pkt_params], false).await
+    }
+
+    async fn set_fsk_modulation_params(&mut self, _mdltn_params: &FskModulationParams) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn set_fsk_packet_params(&mut self, _pkt_params: &FskPacketParams) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    // The Sx128x chips do not need image calibration.
+    async fn calibrate_image(&mut self, _frequency_in_hz: u32) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    async fn set_channel(&mut self, frequency_in_hz: u32) -> Result<(), RadioError> {
+        debug!("channel = {}", frequency_in_hz);
+        let freq_in_pll_steps = Self::convert_freq_in_hz_to_pll_step(frequency_in_hz);
+        let op_code_and_pll_steps = [
+            OpCode::SetRFFrequency.value(),
+            ((freq_in_pll_steps >> 16) & 0xFF) as u8,
+            ((freq_in_pll_steps >> 8) & 0xFF) as u8,
+            (freq_in_pll_steps & 0xFF) as u8,
+        ];
+        self.intf.write(&[&op_code_and_pll_steps], false).await
+    }
+
+    async fn set_payload(&mut self, payload: &[u8]) -> Result<(), RadioError> {
+        let op_code_and_offset = [OpCode::WriteBuffer.value(), 0x00u8];
+        self.intf.write(&[&op_code_and_offset, payload], false).await
+    }
+
+    async fn do_tx(&mut self, timeout_in_ms: u32) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+
+        let timeout = core::cmp::min(timeout_in_ms, u16::MAX as u32) as u16;
+        let [period_base, count_1, count_2] = Self::period_base_and_count(PeriodBase::_1Ms, timeout);
+        let op_code_and_timeout = [OpCode::SetTx.value(), period_base, count_1, count_2];
+        self.intf.write(&[&op_code_and_timeout], false).await
+    }
+
+    async fn do_tx_continuous_wave(&mut self) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.intf.write(&[&[OpCode::SetTxContinuousWave.value()]], false).await
+    }
+
+    async fn do_tx_continuous_preamble(&mut self) -> Result<(), RadioError> {
+        self.intf.iv.enable_rf_switch_tx().await?;
+        self.intf
+            .write(&[&[OpCode::SetTxContinuousPremable.value()]], false)
+            .await
+    }
+
+    // The Sx128x chips have no symbol based receive timeout, so only the receive timeout applies.
+    async fn do_rx(
+        &mut self,
+        _rx_pkt_params: &PacketParams,
+        duty_cycle_params: Option<&DutyCycleParams>,
+        rx_continuous: bool,
+        rx_boosted_if_supported: bool,
+        _symbol_timeout: u16,
+        rx_timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        // a count of 0xFFFF requests continuous receive, so single receive timeouts stop just short of it
+        let mut rx_timeout_final = core::cmp::min(rx_timeout_in_ms, (SX1280_RX_CONTINUOUS_TIMEOUT - 1) as u32) as u16;
+
+        if let Some(&_duty_cycle) = duty_cycle_params {
+            if rx_continuous {
+                return Err(RadioError::DutyCycleRxContinuousUnsupported);
+            }
+        }
+
+        self.intf.iv.enable_rf_switch_rx().await?;
+
+        if rx_continuous {
+            rx_timeout_final = SX1280_RX_CONTINUOUS_TIMEOUT;
+        }
+
+        // if Rx boosted, use the high sensitivity mode, increasing current for around ~3dB in sensitivity
+        let lna_regime = self.read_register(Register::LnaRegime).await?;
+        let lna_regime_final = match rx_boosted_if_supported {
+            true => lna_regime | 0xC0u8,
+            false => lna_regime & !0xC0u8,
+        };
+        self.write_register(Register::LnaRegime, lna_regime_final).await?;
+
+        match duty_cycle_params {
+            Some(duty_cycle) => {
+                let (period_base, rx_count, sleep_count) = Self::duty_cycle_periods(duty_cycle)?;
+                let op_code_and_duty_cycle = [
+                    OpCode::SetRxDutyCycle.value(),
+                    period_base.value(),
+                    ((rx_count >> 8) & 0xFF) as u8,
+                    (rx_count & 0xFF) as u8,
+                    ((sleep_count >> 8) & 0xFF) as u8,
+                    (sleep_count & 0xFF) as u8,
+                ];
+                self.intf.write(&[&op_code_and_duty_cycle], false).await
+            }
+            None => {
+                let [period_base, count_1, count_2] = Self::period_base_and_count(PeriodBase::_1Ms, rx_timeout_final);
+                let op_code_and_timeout = [OpCode::SetRx.value(), period_base, count_1, count_2];
+                self.intf.write(&[&op_code_and_timeout], false).await
+            }
+        }
+    }
+
+    async fn get_rx_payload(
+        &mut self,
+        rx_pkt_params: &PacketParams,
+        receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError> {
+        let op_code = [OpCode::GetRxBufferStatus.value()];
+        let mut rx_buffer_status = [0x00u8; 2];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut rx_buffer_status).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+
+        // in implicit header mode, the buffer status does not give the payload length
+        let payload_length = match rx_pkt_params.implicit_header {
+            true => self.read_register(Register::PayloadLength).await?,
+            false => rx_buffer_status[0],
+        };
+        let offset = rx_buffer_status[1];
+
+        if (payload_length as usize) > receiving_buffer.len() {
+            Err(RadioError::PayloadSizeMismatch(
+                payload_length as usize,
+                receiving_buffer.len(),
+            ))
+        } else {
+            self.intf
+                .read(
+                    &[&[OpCode::ReadBuffer.value(), offset, 0x00u8]],
+                    receiving_buffer,
+                    Some(payload_length),
+                )
+                .await?;
+            Ok(payload_length)
+        }
+    }
+
+    // The LoRa packet status gives the RSSI at syncword detection and the SNR in quarters of a dB.  For packets
+    // received below the noise floor, the RSSI of the signal is that RSSI corrected by the SNR.
+    async fn get_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
+        let op_code = [OpCode::GetPacketStatus.value()];
+        let mut pkt_status = [0x00u8; 5];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut pkt_status).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        let snr = ((pkt_status[1] as i8) / 4) as i16;
+        let mut rssi = ((-(pkt_status[0] as i32)) >> 1) as i16;
+        if snr < 0 {
+            rssi += snr;
+        }
+
+        Ok(PacketStatus::new(rssi, snr))
+    }
+
+    async fn get_rssi(&mut self) -> Result<i16, RadioError> {
+        let op_code = [OpCode::GetRSSIInst.value()];
+        let mut rssi = [0x00u8];
+        let read_status = self.intf.read_with_status(&[&op_code], &mut rssi).await?;
+        if OpStatusErrorMask::is_error(read_status) {
+            return Err(RadioError::OpError(read_status));
+        }
+        Ok(((-(rssi[0] as i32)) >> 1) as i16)
+    }
+
+    async fn get_rx_stats(&mut self) -> Result<RxStats, RadioError> {
+        Ok(self.rx_stats)
+    }
+
+    async fn reset_rx_stats(&mut self) -> Result<(), RadioError> {
+        self.rx_stats = RxStats {
+            header_errors: Some(0),
+            ..Default::default()
+        };
+        Ok(())
+    }
+
+    async fn do_fsk_rx(
+        &mut self,
+        _rx_continuous: bool,
+        _rx_boosted_if_supported: bool,
+        _rx_timeout_in_ms: u32,
+    ) -> Result<(), RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn get_fsk_rx_payload(
+        &mut self,
+        _rx_pkt_params: &FskPacketParams,
+        _receiving_buffer: &mut [u8],
+    ) -> Result<u8, RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn get_fsk_rx_packet_status(&mut self) -> Result<PacketStatus, RadioError> {
+        Err(RadioError::FskUnsupported)
+    }
+
+    async fn do_cad(&mut self, cad_params: &CadParams, rx_boosted_if_supported: bool) -> Result<(), RadioError> {
+        if cad_params.exit_mode != CadExitMode::CadOnly {
+            return Err(RadioError::CadExitModeUnsupported);
+        }
+        self.intf.iv.enable_rf_switch_rx().await?;
+
+        let lna_regime = self.read_register(Register::LnaRegime).await?;
+        let lna_regime_final = match rx_boosted_if_supported {
+            true => lna_regime | 0xC0u8,
+            false => lna_regime & !0xC0u8,
+        };
+        self.write_register(Register::LnaRegime, lna_regime_final).await?;
+
+        // the detection thresholds of the Sx128x chips are fixed, so only the number of symbols is configured
+        let op_code_and_cad_params = [
+            OpCode::SetCADParams.value(),
+            CADSymbols::from(cad_params.symbols).value(),
+        ];
+        self.intf.write(&[&op_code_and_cad_params], false).await?;
+
+        let op_code_for_set_cad = [OpCode::SetCAD.value()];
+        self.intf.write(&[&op_code_for_set_cad], false).await
+    }
+
+    // Set the IRQ mask and DIO masks
+    async fn set_irq_params(&mut self, radio_mode: Option<RadioMode>) -> Result<(), RadioError> {
+        let mut irq_mask: u16 = IrqMask::None.value();
+        let mut dio1_mask: u16 = IrqMask::None.value();
+        let dio2_mask: u16 = IrqMask::None.value();
+        let dio3_mask: u16 = IrqMask::None.value();
+
+        match radio_mode {
+            Some(RadioMode::Standby) => {
+                irq_mask = IrqMask::All.value();
+                dio1_mask = IrqMask::All.value();
+            }
+            Some(RadioMode::Transmit) => {
+                irq_mask = IrqMask::TxDone.value() | IrqMask::RxTxTimeout.value();
+                dio1_mask = IrqMask::TxDone.value() | IrqMask::RxTxTimeout.value();
+            }
+            Some(RadioMode::Receive) | Some(RadioMode::ReceiveDutyCycle) => {
+                irq_mask = IrqMask::All.value();
+                dio1_mask = IrqMask::All.value();
+            }
+            Some(RadioMode::ChannelActivityDetection) => {
+                irq_mask = IrqMask::CADDone.value() | IrqMask::CADActivityDetected.value();
+                dio1_mask = IrqMask::CADDone.value() | IrqMask::CADActivityDetected.value();
+            }
+            _ => {}
+        }
+
+        let op_code_and_masks = [
+            OpCode::CfgDIOIrq.value(),
+            ((irq_mask >> 8) & 0x00FF) as u8,
+            (irq_mask & 0x00FF) as u8,
+            ((dio1_mask >> 8) & 0x00FF) as u8,
+            (dio1_mask & 0x00FF) as u8,
+            ((dio2_mask >> 8) & 0x00FF) as u8,
+            (dio2_mask & 0x00FF) as u8,
+            ((dio3_mask >> 8) & 0x00FF) as u8,
+            (dio3_mask & 0x00FF) as u8,
+        ];
+        self.intf.write(&[&op_code_and_masks], false).await
+    }
+
+    async fn get_device_errors(&mut self) -> Result<DeviceErrors, RadioError> {
+        Ok(DeviceErrors::default())
+    }
+
+    async fn clear_device_errors_and_calibrate(&mut self) -> Result<(), RadioError> {
+        Ok(())
+    }
+
+    /// Process the radio IRQ.  Log unexpected interrupts, but only bail out on timeout.  Packets from other devices can cause unexpected interrupts.
+    async fn process_irq(
+        &mut self,
+        radio_mode: RadioMode,
+        _rx_continuous: bool,
+        cad_activity_detected: Option<&mut bool>,
+    ) -> Result<(), RadioError> {
+        loop {
+            debug!("process_irq loop entered");
+
+            self.intf.iv.await_irq().await?;
+            let op_code = [OpCode::GetIrqStatus.value()];
+            let mut irq_status = [0x00u8, 0x00u8];
+            let read_status = self.intf.read_with_status(&[&op_code], &mut irq_status).await?;
+            if OpStatusErrorMask::is_error(read_status) {
+                return Err(RadioError::OpError(read_status));
+            }
+            let irq_flags = ((irq_status[0] as u16) << 8) | (irq_status[1] as u16);
+            let op_code_and_irq_status = [OpCode::ClrIrqStatus.value(), irq_status[0], irq_status[1]];
+            self.intf.write(&[&op_code_and_irq_status], false).await?;
+
+            debug!(
+                "process_irq satisfied: irq_flags = 0x{:x} in radio mode {}",
+                irq_flags, radio_mode
+            );
+
+            if (irq_flags & IrqMask::HeaderValid.value()) == IrqMask::HeaderValid.value() {
+                debug!("HeaderValid in radio mode {}", radio_mode);
+            }
+            if (irq_flags & IrqMask::PreambleDetected.value()) == IrqMask::PreambleDetected.value() {
+                debug!("PreambleDetected in radio mode {}", radio_mode);
+            }
+            if (irq_flags & IrqMask::SyncwordValid.value()) == IrqMask::SyncwordValid.value() {
+                debug!("SyncwordValid in radio mode {}", radio_mode);
+            }
+
+            if radio_mode == RadioMode::Transmit {
+                if (irq_flags & IrqMask::TxDone.value()) == IrqMask::TxDone.value() {
+                    debug!("TxDone in radio mode {}", radio_mode);
+                    return Ok(());
+                }
+                if (irq_flags & IrqMask::RxTxTimeout.value()) == IrqMask::RxTxTimeout.value() {
+                    debug!("RxTxTimeout in radio mode {}", radio_mode);
+                    return Err(RadioError::TransmitTimeout);
+                }
+            } else if (radio_mode == RadioMode::Receive) || (radio_mode == RadioMode::ReceiveDutyCycle) {
+                if (irq_flags & IrqMask::HeaderError.value()) == IrqMask::HeaderError.value() {
+                    debug!("HeaderError in radio mode {}", radio_mode);
+                    self.rx_stats.header_errors = self.rx_stats.header_errors.map(|count| count.wrapping_add(1));
+                }
+                if (irq_flags & IrqMask::SyncwordError.value()) == IrqMask::SyncwordError.value() {
+                    debug!("SyncwordError in radio mode {}", radio_mode);
+                }
+                if (irq_flags & IrqMask::CRCError.value()) == IrqMask::CRCError.value() {
+                    debug!("CRCError in radio mode {}", radio_mode);
+                    self.rx_stats.crc_errors = self.rx_stats.crc_errors.wrapping_add(1);
+                }
+                if (irq_flags & IrqMask::RxDone.value()) == IrqMask::RxDone.value() {
+                    debug!("RxDone in radio mode {}", radio_mode);
+                    self.rx_stats.packets_received = self.rx_stats.packets_received.wrapping_add(1);
+                    return Ok(());
+                }
+                if (irq_flags & IrqMask::RxTxTimeout.value()) == IrqMask::RxTxTimeout.value() {
+                    debug!("RxTxTimeout in radio mode {}", radio_mode);
+                    return Err(RadioError::ReceiveTimeout);
+                }
+            } else if radio_mode == RadioMode::ChannelActivityDetection
+                && (irq_flags & IrqMask::CADDone.value()) == IrqMask::CADDone.value()
+            {
+                debug!("CADDone in radio mode {}", radio_mode);
+                if cad_activity_detected.is_some() {
+                    *(cad_activity_detected.unwrap()) =
+                        (irq_flags & IrqMask::CADActivityDetected.value()) == IrqMask::CADActivityDetected.value();
+                }
+                return Ok(());
+            }
+
+            // if an interrupt occurred for other than an error or operation completion, loop to wait again
+        }
+    }
+}
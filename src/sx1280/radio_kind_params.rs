@@ -0,0 +1,289 @@
+use crate::mod_params::*;
+
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum PacketType {
+    GFSK = 0x00,
+    LoRa = 0x01,
+    Ranging = 0x02,
+    FLRC = 0x03,
+    BLE = 0x04,
+}
+
+impl PacketType {
+    pub const fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum IrqMask {
+    None = 0x0000,
+    TxDone = 0x0001,
+    RxDone = 0x0002,
+    SyncwordValid = 0x0004,
+    SyncwordError = 0x0008,
+    HeaderValid = 0x0010,
+    HeaderError = 0x0020,
+    CRCError = 0x0040,
+    CADDone = 0x1000,
+    CADActivityDetected = 0x2000,
+    RxTxTimeout = 0x4000,
+    PreambleDetected = 0x8000,
+    All = 0xFFFF,
+}
+
+impl IrqMask {
+    pub fn value(self) -> u16 {
+        self as u16
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Register {
+    PayloadLength = 0x0901,             // payload size in implicit header mode
+    LnaRegime = 0x0891,                 // receiver sensitivity (bits 7:6 set: high sensitivity)
+    SfAdditionalConfiguration = 0x0925, // demodulator setting depending on the spreading factor
+    FrequencyErrorCorrection = 0x093C,  // frequency error compensation, set after the modulation parameters
+    LoRaSyncwordMsb = 0x0944,           // LoRa Syncword value, most significant nibble in bits 7:4
+    LoRaSyncwordLsb = 0x0945,           // LoRa Syncword value, least significant nibble in bits 7:4
+}
+
+impl Register {
+    pub fn addr1(self) -> u8 {
+        ((self as u16 & 0xFF00) >> 8) as u8
+    }
+    pub fn addr2(self) -> u8 {
+        (self as u16 & 0x00FF) as u8
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum OpCode {
+    GetStatus = 0xC0,
+    WriteRegister = 0x18,
+    ReadRegister = 0x19,
+    WriteBuffer = 0x1A,
+    ReadBuffer = 0x1B,
+    SetSleep = 0x84,
+    SetStandby = 0x80,
+    SetFS = 0xC1,
+    SetTx = 0x83,
+    SetRx = 0x82,
+    SetRxDutyCycle = 0x94,
+    SetCAD = 0xC5,
+    SetTxContinuousWave = 0xD1,
+    SetTxContinuousPremable = 0xD2,
+    SetPacketType = 0x8A,
+    GetPacketType = 0x03,
+    SetRFFrequency = 0x86,
+    SetTxParams = 0x8E,
+    SetCADParams = 0x88,
+    SetBufferBaseAddress = 0x8F,
+    SetModulationParams = 0x8B,
+    SetPacketParams = 0x8C,
+    GetRxBufferStatus = 0x17,
+    GetPacketStatus = 0x1D,
+    GetRSSIInst = 0x1F,
+    CfgDIOIrq = 0x8D,
+    GetIrqStatus = 0x15,
+    ClrIrqStatus = 0x97,
+    SetRegulatorMode = 0x96,
+    SetSaveContext = 0xD5,
+    SetAutoFs = 0x9E,
+}
+
+impl OpCode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+// See DS_SX1280-1_V3.2 datasheet, section 11.3; the command status is given in bits 4:2 of the status
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpStatusErrorMask {
+    Timeout = (0x03 << 2),
+    ProcessingError = (0x04 << 2),
+    ExecutionError = (0x05 << 2),
+}
+
+impl OpStatusErrorMask {
+    pub fn is_error(status: u8) -> bool {
+        let error_flags = status & 0x1c;
+        OpStatusErrorMask::Timeout as u8 == error_flags
+            || OpStatusErrorMask::ProcessingError as u8 == error_flags
+            || OpStatusErrorMask::ExecutionError as u8 == error_flags
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SleepParams {
+    pub data_buffer_retention: bool, // retain the content of the data buffer
+    pub warm_start: bool,            // retain the configuration held in the data RAM
+}
+
+impl SleepParams {
+    pub fn value(self) -> u8 {
+        ((self.data_buffer_retention as u8) << 1) | (self.warm_start as u8)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum StandbyMode {
+    RC = 0x00,
+    XOSC = 0x01,
+}
+
+impl StandbyMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum RegulatorMode {
+    UseLDO = 0x00,
+    UseDCDC = 0x01,
+}
+
+impl RegulatorMode {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+#[allow(clippy::enum_variant_names)]
+pub enum RampTime {
+    Ramp02Us = 0x00,
+    Ramp04Us = 0x20,
+    Ramp06Us = 0x40,
+    Ramp08Us = 0x60,
+    Ramp10Us = 0x80,
+    Ramp12Us = 0xA0,
+    Ramp16Us = 0xC0,
+    Ramp20Us = 0xE0,
+}
+
+impl RampTime {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+// Step of the timeouts given to the SetTx, SetRx, and SetRxDutyCycle commands
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum PeriodBase {
+    _15_625Us = 0x00,
+    _62_5Us = 0x01,
+    _1Ms = 0x02,
+    _4Ms = 0x03,
+}
+
+impl PeriodBase {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+pub fn spreading_factor_value(spreading_factor: SpreadingFactor) -> Result<u8, RadioError> {
+    match spreading_factor {
+        SpreadingFactor::_5 => Ok(0x50),
+        SpreadingFactor::_6 => Ok(0x60),
+        SpreadingFactor::_7 => Ok(0x70),
+        SpreadingFactor::_8 => Ok(0x80),
+        SpreadingFactor::_9 => Ok(0x90),
+        SpreadingFactor::_10 => Ok(0xA0),
+        SpreadingFactor::_11 => Ok(0xB0),
+        SpreadingFactor::_12 => Ok(0xC0),
+    }
+}
+
+pub fn bandwidth_value(bandwidth: Bandwidth) -> Result<u8, RadioError> {
+    match bandwidth {
+        Bandwidth::_203KHz => Ok(0x34),
+        Bandwidth::_406KHz => Ok(0x26),
+        Bandwidth::_812KHz => Ok(0x18),
+        Bandwidth::_1625KHz => Ok(0x0A),
+        Bandwidth::_7KHz
+        | Bandwidth::_10KHz
+        | Bandwidth::_15KHz
+        | Bandwidth::_20KHz
+        | Bandwidth::_31KHz
+        | Bandwidth::_41KHz
+        | Bandwidth::_62KHz
+        | Bandwidth::_125KHz
+        | Bandwidth::_250KHz
+        | Bandwidth::_500KHz => Err(RadioError::UnavailableBandwidth),
+    }
+}
+
+pub fn coding_rate_value(coding_rate: CodingRate) -> Result<u8, RadioError> {
+    match coding_rate {
+        CodingRate::_4_5 => Ok(0x01),
+        CodingRate::_4_6 => Ok(0x02),
+        CodingRate::_4_7 => Ok(0x03),
+        CodingRate::_4_8 => Ok(0x04),
+        CodingRate::_4_5Li => Ok(0x05),
+        CodingRate::_4_6Li => Ok(0x06),
+        CodingRate::_4_8Li => Ok(0x07),
+    }
+}
+
+// Value written to the SfAdditionalConfiguration register for the spreading factor
+pub fn sf_additional_configuration_value(spreading_factor: SpreadingFactor) -> u8 {
+    match spreading_factor {
+        SpreadingFactor::_5 | SpreadingFactor::_6 => 0x1E,
+        SpreadingFactor::_7 | SpreadingFactor::_8 => 0x37,
+        _ => 0x32,
+    }
+}
+
+// The preamble length is given to the radio as mantissa * 2^exponent, with both in the range 1..=15
+pub fn preamble_length_value(preamble_length: u16) -> u8 {
+    let mut exponent = 0u32;
+    let mut mantissa = core::cmp::max(preamble_length as u32, 1);
+    while mantissa > 15 {
+        exponent += 1;
+        // round up, so the preamble is never shorter than requested
+        mantissa = (preamble_length as u32 + (1 << exponent) - 1) >> exponent;
+    }
+    ((exponent as u8) << 4) | (mantissa as u8)
+}
+
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub enum CADSymbols {
+    _1 = 0x00,
+    _2 = 0x20,
+    _4 = 0x40,
+    _8 = 0x60,
+    _16 = 0x80,
+}
+
+impl CADSymbols {
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<CadSymbols> for CADSymbols {
+    fn from(symbols: CadSymbols) -> Self {
+        match symbols {
+            CadSymbols::_1 => CADSymbols::_1,
+            CadSymbols::_2 => CADSymbols::_2,
+            CadSymbols::_4 => CADSymbols::_4,
+            CadSymbols::_8 => CADSymbols::_8,
+            CadSymbols::_16 => CADSymbols::_16,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::Poll;
+
+use embedded_hal_async::delay::DelayUs;
+
+/// Run the future until it completes or the timeout elapses on the delay, giving None if the timeout elapses first.
+/// This bounds the operations of LoRa chips without hardware timers (Sx127x).
+pub async fn with_timeout<F: Future>(delay: &mut impl DelayUs, timeout_in_ms: u32, future: F) -> Option<F::Output> {
+    let mut future = future;
+    let mut timeout = delay.delay_ms(timeout_in_ms);
+    // Safety: the futures are shadowed, so they are never moved again after being pinned
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+    let mut timeout = unsafe { Pin::new_unchecked(&mut timeout) };
+
+    poll_fn(|context| {
+        if let Poll::Ready(output) = future.as_mut().poll(context) {
+            return Poll::Ready(Some(output));
+        }
+        match timeout.as_mut().poll(context) {
+            Poll::Ready(()) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::{pending, ready};
+
+    use super::*;
+    use crate::sim::{block_on, SimDelay};
+
+    #[test]
+    fn completed_future_wins() {
+        let mut delay = SimDelay::default();
+        assert_eq!(block_on(with_timeout(&mut delay, 100, ready(7))), Some(7));
+    }
+
+    #[test]
+    fn pending_future_times_out() {
+        let mut delay = SimDelay::default();
+        assert_eq!(block_on(with_timeout(&mut delay, 100, pending::<()>())), None);
+        assert_eq!(delay.elapsed_in_us, 100_000);
+    }
+}